@@ -0,0 +1,754 @@
+use std::{
+    borrow::Borrow,
+    collections::{
+        BTreeMap,
+        HashMap,
+        VecDeque,
+        vec_deque::Drain,
+    },
+    hash::Hash,
+    io::{
+        self,
+        Read,
+    },
+    ops::Range,
+};
+
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// Controls which matches an [`AhoCorasick`] automaton reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Reports every occurrence of every key, including occurrences that
+    /// overlap each other.
+    ///
+    /// Matches are reported in order of where they end. Matches that end at
+    /// the same position are reported from longest to shortest.
+    #[default]
+    Overlapping,
+    /// Reports the occurrence that ends first, preferring the longest key if
+    /// several end at the same position. Scanning then resumes after the end
+    /// of the match, so no two matches overlap.
+    NonOverlapping,
+    /// Reports the occurrence that starts first, preferring the longest key if
+    /// several start at the same position. Scanning then resumes after the end
+    /// of the match, so no two matches overlap.
+    ///
+    /// This is usually the most useful mode when replacing or highlighting
+    /// keys.
+    LeftmostLongest,
+}
+
+/// An occurrence of a key within a haystack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Match {
+    start: usize,
+    end: usize,
+    pattern: usize,
+}
+
+impl Match {
+    /// Returns the position of the first element of the match.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the position after the last element of the match.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the range of positions covered by the match.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the index of the matched key. The key itself can be found
+    /// using [`AhoCorasick::key`].
+    pub fn pattern(&self) -> usize {
+        self.pattern
+    }
+}
+
+/// A state within an [`AhoCorasick`] automaton. Each state corresponds to a
+/// prefix of at least one key.
+struct State<T> {
+    /// The state reached by each element that extends this state's prefix.
+    next: HashMap<T, usize>,
+    /// The state for the longest proper suffix of this state's prefix that is
+    /// also a prefix of some key.
+    fail: usize,
+    /// The length of this state's prefix.
+    depth: usize,
+    /// The index of the key that ends at this state, if any.
+    pattern: Option<usize>,
+    /// The nearest state along the failure links that ends a key, if any.
+    output: Option<usize>,
+}
+
+impl<T> State<T> {
+    fn new(depth: usize) -> Self {
+        Self {
+            next: HashMap::new(),
+            fail: 0,
+            depth,
+            pattern: None,
+            output: None,
+        }
+    }
+}
+
+/// Finds every key of a trie within a haystack in a single pass.
+///
+/// An Aho-Corasick automaton is a trie with failure links. When the next
+/// element of the haystack cannot extend the current prefix, the failure link
+/// leads to the longest suffix of what has been read that can. This means the
+/// haystack is never re-read, no matter how many keys there are.
+///
+/// The automaton is a snapshot of the trie and is not updated if the trie
+/// changes. The empty key never matches.
+pub struct AhoCorasick<T> {
+    states: Vec<State<T>>,
+    /// Every key, indexed by [`Match::pattern`].
+    keys: Vec<Vec<T>>,
+    kind: MatchKind,
+}
+
+impl<T> AhoCorasick<T>
+where
+    T: Hash + Eq + Copy,
+{
+    /// Compiles an [`AhoCorasick`] automaton from every key at or below
+    /// `root`.
+    pub fn new<V, H>(root: &TrieNode<T, H, V>, kind: MatchKind) -> Self
+    where
+        H: ChildStorage<T>,
+    {
+        Self::build(root, kind, |element, buffer| buffer.push(*element))
+    }
+
+    /// Compiles an automaton from the keys at or below `root`, where each
+    /// element of a key is converted into a run of automaton elements by
+    /// `encode`.
+    pub(super) fn build<E, V, H>(
+        root: &TrieNode<E, H, V>,
+        kind: MatchKind,
+        encode: impl Fn(&E, &mut Vec<T>),
+    ) -> Self
+    where
+        H: ChildStorage<E>,
+    {
+        let mut automaton = Self {
+            states: vec![State::new(0)],
+            keys: Vec::new(),
+            kind,
+        };
+
+        // Copy the shape of the trie. Each stack entry holds the last element
+        // of a key, its node, the state of its parent and the encoded length
+        // of its parent's key:
+        let mut buffer = Vec::new();
+        let mut stack: Vec<_> = root
+            .children
+            .iter()
+            .map(|(element, child)| (element, child, 0, 0))
+            .collect();
+        while let Some((element, node, parent, depth)) = stack.pop() {
+            // If we're backtracking, truncate the key to the current depth:
+            buffer.truncate(depth);
+            encode(element, &mut buffer);
+
+            let state = automaton.add_path(parent, &buffer[depth..]);
+            if node.value.is_some() {
+                automaton.states[state].pattern = Some(automaton.keys.len());
+                automaton.keys.push(buffer.clone());
+            }
+            for (element, child) in node.children.iter() {
+                stack.push((element, child, state, buffer.len()));
+            }
+        }
+
+        automaton.link();
+        automaton
+    }
+
+    /// Follows `elements` from `state`, creating any states that are missing.
+    ///
+    /// Returns the state at the end of the path.
+    fn add_path(&mut self, mut state: usize, elements: &[T]) -> usize {
+        for element in elements {
+            state = match self.states[state].next.get(element) {
+                Some(next) => *next,
+                None => {
+                    let next = self.states.len();
+                    self.states.push(State::new(self.states[state].depth + 1));
+                    self.states[state].next.insert(*element, next);
+                    next
+                },
+            };
+        }
+        state
+    }
+
+    /// Computes the failure and output links of every state.
+    ///
+    /// States are visited in order of depth, so the links of every shorter
+    /// state are known by the time they are needed.
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.states[0].next.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let edges: Vec<(T, usize)> = self.states[state]
+                .next
+                .iter()
+                .map(|(element, next)| (*element, *next))
+                .collect();
+            for (element, next) in edges {
+                // The longest suffix of the child is found by extending the
+                // longest suffix of the parent that can be extended:
+                let mut fail = self.states[state].fail;
+                let fail = loop {
+                    if let Some(target) = self.states[fail].next.get(&element) {
+                        break *target;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = self.states[fail].fail;
+                };
+                self.states[next].fail = fail;
+                self.states[next].output = match self.states[fail].pattern {
+                    Some(..) => Some(fail),
+                    None => self.states[fail].output,
+                };
+                queue.push_back(next);
+            }
+        }
+    }
+
+    /// Returns the state reached by reading `element` in `state`.
+    fn next_state(&self, mut state: usize, element: &T) -> usize {
+        loop {
+            if let Some(next) = self.states[state].next.get(element) {
+                return *next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.states[state].fail;
+        }
+    }
+
+    /// Returns every state that ends a key which is a suffix of `state`'s
+    /// prefix, from longest to shortest.
+    fn outputs(&self, state: usize) -> impl Iterator<Item = usize> + '_ {
+        let first = match self.states[state].pattern {
+            Some(..) => Some(state),
+            None => self.states[state].output,
+        };
+        std::iter::successors(first, |state| self.states[*state].output)
+    }
+
+    /// Returns the [`Match`] for the key ending at `state`, where the match
+    /// ends at `end`.
+    fn found(&self, state: usize, end: usize) -> Match {
+        let state = &self.states[state];
+        Match {
+            start: end - state.depth,
+            end,
+            pattern: state.pattern.unwrap(),
+        }
+    }
+
+    /// Reads the next element of the haystack, adding any matches that are
+    /// now known to `found`.
+    pub(super) fn step(&self, search: &mut Search, element: &T, found: &mut VecDeque<Match>) {
+        search.state = self.next_state(search.state, element);
+        search.position += 1;
+        let end = search.position;
+
+        match self.kind {
+            MatchKind::Overlapping => {
+                found.extend(self.outputs(search.state).map(|state| self.found(state, end)));
+            },
+            MatchKind::NonOverlapping => {
+                // Outputs are ordered from longest to shortest, so the first
+                // one that doesn't overlap the previous match is the longest:
+                let next = self
+                    .outputs(search.state)
+                    .map(|state| self.found(state, end))
+                    .find(|found| found.start >= search.resume);
+                if let Some(next) = next {
+                    search.resume = end;
+                    found.push_back(next);
+                }
+            },
+            MatchKind::LeftmostLongest => {
+                // Matches are found in order of their end, so a later match
+                // with the same start is always longer:
+                for state in self.outputs(search.state) {
+                    let next = self.found(state, end);
+                    if next.start >= search.resume {
+                        search.pending.insert(next.start, next);
+                    }
+                }
+
+                // Any future match must start within the prefix of the
+                // current state:
+                let bound = end - self.states[search.state].depth;
+                search.flush(bound, found);
+            },
+        }
+    }
+
+    /// Adds every match that is still pending to `found`, once the end of the
+    /// haystack has been reached.
+    pub(super) fn finish(&self, search: &mut Search, found: &mut VecDeque<Match>) {
+        search.flush(usize::MAX, found);
+    }
+
+    /// Returns the key for a [`Match::pattern`].
+    ///
+    /// # Panics
+    /// Panics if `pattern` is not the index of a key within the automaton.
+    pub fn key(&self, pattern: usize) -> &[T] {
+        &self.keys[pattern]
+    }
+
+    /// Returns the number of keys within the automaton.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the automaton contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the [`MatchKind`] the automaton was compiled with.
+    pub fn match_kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Returns an iterator over every [`Match`] within `haystack`.
+    ///
+    /// Positions count the elements of `haystack` from zero.
+    pub fn find_iter<I>(&self, haystack: I) -> FindIter<'_, T, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        FindIter {
+            automaton: self,
+            haystack: Some(haystack.into_iter()),
+            search: Search::default(),
+            found: VecDeque::new(),
+        }
+    }
+
+    /// Returns a [`StreamMatcher`], which finds every [`Match`] within a
+    /// haystack that arrives in chunks.
+    pub fn stream(&self) -> StreamMatcher<'_, T> {
+        StreamMatcher {
+            automaton: self,
+            search: Search::default(),
+            found: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if any key occurs within `haystack`.
+    pub fn is_match<I>(&self, haystack: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        let mut state = 0;
+        haystack.into_iter().any(|element| {
+            state = self.next_state(state, element.borrow());
+            self.outputs(state).next().is_some()
+        })
+    }
+}
+
+/// The progress of a search through a haystack.
+#[derive(Default)]
+pub(super) struct Search {
+    /// The current state of the automaton.
+    state: usize,
+    /// The number of elements read so far.
+    position: usize,
+    /// Matches must start at or after this position so they don't overlap the
+    /// previously reported match.
+    resume: usize,
+    /// Candidate matches for [`MatchKind::LeftmostLongest`], keyed by their
+    /// start.
+    pending: BTreeMap<usize, Match>,
+}
+
+impl Search {
+    /// Reports every pending match that starts before `bound`, leftmost
+    /// first, skipping any that overlap a reported match.
+    fn flush(&mut self, bound: usize, found: &mut VecDeque<Match>) {
+        while let Some(entry) = self.pending.first_entry() {
+            if *entry.key() >= bound {
+                break;
+            }
+            let next = entry.remove();
+            if next.start >= self.resume {
+                self.resume = next.end;
+                found.push_back(next);
+            }
+        }
+    }
+}
+
+/// Iterates over every [`Match`] of an [`AhoCorasick`] automaton within a
+/// haystack.
+pub struct FindIter<'a, T, I> {
+    automaton: &'a AhoCorasick<T>,
+    /// The rest of the haystack, or [`None`] once it has been read.
+    haystack: Option<I>,
+    search: Search,
+    /// Matches that have been found but not yet returned.
+    found: VecDeque<Match>,
+}
+
+impl<T, I> Iterator for FindIter<'_, T, I>
+where
+    T: Hash + Eq + Copy,
+    I: Iterator,
+    I::Item: Borrow<T>,
+{
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.found.pop_front() {
+                return Some(found);
+            }
+            let haystack = self.haystack.as_mut()?;
+            match haystack.next() {
+                Some(element) => self.automaton.step(&mut self.search, element.borrow(), &mut self.found),
+                None => {
+                    self.haystack = None;
+                    self.automaton.finish(&mut self.search, &mut self.found);
+                },
+            }
+        }
+    }
+}
+
+/// Finds every [`Match`] of an [`AhoCorasick`] automaton within a haystack
+/// that is read in chunks.
+///
+/// The state of the automaton is kept between chunks, so matches that span
+/// several chunks are still found. Positions are counted from the start of
+/// the first chunk.
+pub struct StreamMatcher<'a, T> {
+    automaton: &'a AhoCorasick<T>,
+    search: Search,
+    /// Matches that have been found but not yet returned.
+    found: VecDeque<Match>,
+}
+
+impl<T> StreamMatcher<'_, T>
+where
+    T: Hash + Eq + Copy,
+{
+    /// Reads the next chunk of the haystack.
+    ///
+    /// Returns every match that is known once the chunk has been read. With
+    /// [`MatchKind::LeftmostLongest`], a match near the end of the chunk may
+    /// only be returned by a later chunk or by [`StreamMatcher::finish`], since
+    /// a longer or earlier match could still follow.
+    pub fn feed<I>(&mut self, chunk: I) -> Drain<'_, Match>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        self.read(chunk);
+        self.found.drain(..)
+    }
+
+    /// Reads the next chunk of the haystack, keeping any matches found.
+    fn read<I>(&mut self, chunk: I)
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        for element in chunk {
+            self.automaton.step(&mut self.search, element.borrow(), &mut self.found);
+        }
+    }
+
+    /// Ends the haystack, returning every match that is still pending.
+    pub fn finish(mut self) -> impl Iterator<Item = Match> {
+        self.automaton.finish(&mut self.search, &mut self.found);
+        self.found.into_iter()
+    }
+
+    /// Returns the number of elements read so far.
+    pub fn position(&self) -> usize {
+        self.search.position
+    }
+}
+
+impl AhoCorasick<u8> {
+    /// Returns an iterator over every [`Match`] within the bytes read from
+    /// `reader`.
+    ///
+    /// The reader is read in chunks, so the input never needs to be held in
+    /// memory at once. Any error from the reader is returned by the iterator
+    /// after the matches found before it, after which the iterator is empty.
+    pub fn stream_reader<R: Read>(&self, reader: R) -> ReadMatches<'_, R> {
+        ReadMatches::new(self.stream(), reader, false)
+    }
+}
+
+/// Iterates over every [`Match`] within the bytes read from a [`Read`].
+pub struct ReadMatches<'a, R> {
+    matcher: StreamMatcher<'a, u8>,
+    /// The reader, or [`None`] once it has been read to the end or has
+    /// failed.
+    reader: Option<R>,
+    buffer: Box<[u8]>,
+    /// If set, the input must be valid UTF-8.
+    utf8: bool,
+    /// The number of bytes at the start of `buffer` that form an incomplete
+    /// UTF-8 character. These are only matched once the rest of the character
+    /// has been read.
+    incomplete: usize,
+    /// An error from the reader, returned once the matches found before it.
+    error: Option<io::Error>,
+}
+
+impl<'a, R> ReadMatches<'a, R>
+where
+    R: Read,
+{
+    /// The number of bytes read from the reader at once.
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    pub(super) fn new(matcher: StreamMatcher<'a, u8>, reader: R, utf8: bool) -> Self {
+        Self {
+            matcher,
+            reader: Some(reader),
+            buffer: vec![0; Self::CHUNK_SIZE].into_boxed_slice(),
+            utf8,
+            incomplete: 0,
+            error: None,
+        }
+    }
+
+    /// Reads and matches the next chunk of the input.
+    ///
+    /// Returns `false` once the end of the input has been reached.
+    fn read_chunk(&mut self, reader: &mut R) -> io::Result<bool> {
+        let read = loop {
+            match reader.read(&mut self.buffer[self.incomplete..]) {
+                Ok(read) => break read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        };
+        if read == 0 {
+            if self.incomplete > 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended within a UTF-8 character",
+                ));
+            }
+            return Ok(false);
+        }
+
+        let end = self.incomplete + read;
+        let valid = match self.utf8 {
+            true => match std::str::from_utf8(&self.buffer[..end]) {
+                Ok(..) => end,
+                // The chunk ends part way through a character:
+                Err(error) if error.error_len().is_none() => error.valid_up_to(),
+                Err(error) => {
+                    // Match the valid bytes before reporting the error:
+                    self.matcher.read(&self.buffer[..error.valid_up_to()]);
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+                },
+            },
+            false => end,
+        };
+
+        // Move the incomplete character to the front, ready for the next
+        // chunk:
+        self.matcher.read(&self.buffer[..valid]);
+        self.buffer.copy_within(valid..end, 0);
+        self.incomplete = end - valid;
+        Ok(true)
+    }
+}
+
+impl<R> Iterator for ReadMatches<'_, R>
+where
+    R: Read,
+{
+    type Item = io::Result<Match>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.matcher.found.pop_front() {
+                return Some(Ok(found));
+            }
+
+            let Some(mut reader) = self.reader.take() else {
+                return self.error.take().map(Err);
+            };
+            match self.read_chunk(&mut reader) {
+                Ok(true) => {
+                    self.reader = Some(reader);
+                    continue;
+                },
+                Ok(false) => {},
+                Err(error) => self.error = Some(error),
+            }
+            let StreamMatcher { automaton, search, found } = &mut self.matcher;
+            automaton.finish(search, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    use super::*;
+
+    /// Returns every match as the matched text and its start.
+    fn find(automaton: &AhoCorasick<char>, haystack: &str) -> Vec<(String, usize)> {
+        let haystack: Vec<char> = haystack.chars().collect();
+        automaton
+            .find_iter(&haystack)
+            .map(|found| {
+                assert_eq!(automaton.key(found.pattern()), &haystack[found.range()]);
+                (String::from_iter(&haystack[found.range()]), found.start())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let trie = Trie::<char>::from_iter(["he", "she", "his", "hers", ""].map(str::chars));
+        let automaton = trie.aho_corasick(MatchKind::Overlapping);
+        assert_eq!(automaton.len(), 4);
+
+        assert_eq!(find(&automaton, "ushers"), [
+            ("she".to_string(), 1),
+            ("he".to_string(), 2),
+            ("hers".to_string(), 2),
+        ]);
+        assert!(find(&automaton, "").is_empty());
+        assert!(automaton.is_match("this".chars()));
+        assert!(!automaton.is_match("hello".chars().skip(1)));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let keys = ["a", "ab", "abc", "bc", "c", "bca", "aa", "cab"];
+        let trie = Trie::<char>::from_iter(keys.map(str::chars));
+        let automaton = trie.aho_corasick(MatchKind::Overlapping);
+
+        let haystack = "abcabcaabccab";
+        let mut expected = Vec::new();
+        for end in 1..=haystack.len() {
+            for start in 0..end {
+                if keys.contains(&&haystack[start..end]) {
+                    expected.push((haystack[start..end].to_string(), start));
+                }
+            }
+        }
+
+        let mut found = find(&automaton, haystack);
+        found.sort_by_key(|(key, start)| (start + key.len(), *start));
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_non_overlapping() {
+        let trie = Trie::<char>::from_iter(["abcd", "bc", "b", "cde"].map(str::chars));
+        let automaton = trie.aho_corasick(MatchKind::NonOverlapping);
+
+        // "b" ends first, then scanning resumes at "c":
+        assert_eq!(find(&automaton, "abcde"), [
+            ("b".to_string(), 1),
+            ("cde".to_string(), 2),
+        ]);
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        let trie = Trie::<char>::from_iter(["abcdef", "ab", "cd", "bcd", "abcde", "x"].map(str::chars));
+        let automaton = trie.aho_corasick(MatchKind::LeftmostLongest);
+
+        assert_eq!(find(&automaton, "abcdx"), [
+            ("ab".to_string(), 0),
+            ("cd".to_string(), 2),
+            ("x".to_string(), 4),
+        ]);
+        assert_eq!(find(&automaton, "abcdefx"), [
+            ("abcdef".to_string(), 0),
+            ("x".to_string(), 6),
+        ]);
+        assert_eq!(find(&automaton, "zbcdez"), [("bcd".to_string(), 1)]);
+
+        // Pending matches are reported once the end of the haystack is
+        // reached:
+        assert_eq!(find(&automaton, "abcde"), [("abcde".to_string(), 0)]);
+    }
+
+    #[test]
+    fn test_stream() {
+        let trie = Trie::<char>::from_iter(["abc", "bcd", "cd", "dab"].map(str::chars));
+        let haystack: Vec<char> = "abcdabcdab".chars().collect();
+
+        for kind in [MatchKind::Overlapping, MatchKind::NonOverlapping, MatchKind::LeftmostLongest] {
+            let automaton = trie.aho_corasick(kind);
+            let expected: Vec<Match> = automaton.find_iter(&haystack).collect();
+
+            // Every way of splitting the haystack finds the same matches:
+            for size in 1..=haystack.len() {
+                let mut stream = automaton.stream();
+                let mut found = Vec::new();
+                for chunk in haystack.chunks(size) {
+                    found.extend(stream.feed(chunk));
+                }
+                assert_eq!(stream.position(), haystack.len());
+                found.extend(stream.finish());
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_reader() {
+        let mut trie = Trie::<u8>::new();
+        for key in [&b"needle"[..], b"ne", b"\xff\xfe"] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+        let automaton = trie.aho_corasick(MatchKind::LeftmostLongest);
+
+        // Matches span the chunks read from the reader:
+        let mut haystack = vec![b'x'; 20_000];
+        haystack[8_190..8_196].copy_from_slice(b"needle");
+        haystack[19_998..].copy_from_slice(b"ne");
+        haystack[100] = 0xff;
+        haystack[101] = 0xfe;
+
+        let found: Vec<Range<usize>> = automaton
+            .stream_reader(haystack.as_slice())
+            .map(|found| found.unwrap().range())
+            .collect();
+        assert_eq!(found, [100..102, 8_190..8_196, 19_998..20_000]);
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    collections::VecDeque,
+    io::{
+        self,
+        Read,
+        Write,
+    },
+};
+
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    double_array::Symbol,
+    node::TrieNode,
+};
+
+/// Identifies a trie written by [`write`].
+const MAGIC: &[u8; 4] = b"FTTR";
+
+/// The version of the format.
+const VERSION: u32 = 1;
+
+/// The size of the header in bytes.
+const HEADER: usize = 32;
+
+/// Set in the flags of a node that ends a key.
+const END_FLAG: u8 = 1;
+
+/// The CRC-32 lookup table for the reflected polynomial `0xedb88320`.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+/// Returns the CRC-32 checksum of `parts`, as if they were concatenated.
+fn crc32<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u32 {
+    let mut crc = !0;
+    for byte in parts.into_iter().flatten() {
+        crc = CRC_TABLE[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Creates an [`InvalidData`](io::ErrorKind::InvalidData) error.
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Writes every key at or below `root` to `writer`.
+///
+/// The format is a 32 byte header, a flat table of nodes and a checksum. The
+/// header holds the magic number `FTTR`, a `u32` format version and the `u64`
+/// number of keys, nodes and bytes in the node table. The table lists every
+/// node in breadth-first order, as a flags byte, the `u32` number of
+/// children, the `u32` length of their elements and then the [`Symbol`]
+/// encoding of the element leading to each child. A node's children follow
+/// every child of the nodes before it, so the table doesn't need to store
+/// where they are. Last comes the CRC-32 of the header and the table. Every
+/// number is little-endian.
+pub(super) fn write<T, V, H>(root: &TrieNode<T, H, V>, count: usize, mut writer: impl Write) -> io::Result<()>
+where
+    T: Symbol,
+    H: ChildStorage<T>,
+{
+    let mut table = Vec::new();
+    let mut nodes = 0u64;
+    let mut queue = VecDeque::from([root]);
+    let mut elements = Vec::new();
+    while let Some(node) = queue.pop_front() {
+        nodes += 1;
+        elements.clear();
+        let mut buffer = [0; 4];
+        for (element, child) in node.children.iter() {
+            elements.extend_from_slice(element.encode(&mut buffer));
+            queue.push_back(child);
+        }
+
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "node has too many children");
+        table.push(if node.value.is_some() { END_FLAG } else { 0 });
+        table.extend_from_slice(&u32::try_from(node.children.len()).map_err(|_| too_large())?.to_le_bytes());
+        table.extend_from_slice(&u32::try_from(elements.len()).map_err(|_| too_large())?.to_le_bytes());
+        table.extend_from_slice(&elements);
+    }
+
+    let mut header = Vec::with_capacity(HEADER);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&(count as u64).to_le_bytes());
+    header.extend_from_slice(&nodes.to_le_bytes());
+    header.extend_from_slice(&(table.len() as u64).to_le_bytes());
+
+    writer.write_all(&header)?;
+    writer.write_all(&table)?;
+    writer.write_all(&crc32([header.as_slice(), &table]).to_le_bytes())
+}
+
+/// Reads a trie written by [`write`], returning its root and the number of
+/// keys within it.
+///
+/// The checksum is verified before any node is read. The node table must then
+/// describe a well-formed trie: every node other than the root is the child of
+/// exactly one node, no node has two children for the same element and every
+/// branch leads to a key. Any other input is rejected with an
+/// [`InvalidData`](io::ErrorKind::InvalidData) error.
+pub(super) fn read<T, H>(mut reader: impl Read) -> io::Result<(TrieNode<T, H>, usize)>
+where
+    T: Symbol,
+    H: ChildStorage<T>,
+{
+    let truncated = |error: io::Error| match error.kind() {
+        io::ErrorKind::UnexpectedEof => invalid("trie is truncated"),
+        _ => error,
+    };
+
+    let mut header = [0; HEADER];
+    reader.read_exact(&mut header).map_err(truncated)?;
+    if &header[..4] != MAGIC {
+        return Err(invalid("not a serialized trie"));
+    }
+    if u32::from_le_bytes(header[4..8].try_into().unwrap()) != VERSION {
+        return Err(invalid("unsupported trie version"));
+    }
+    let number = |range: std::ops::Range<usize>| u64::from_le_bytes(header[range].try_into().unwrap());
+    let count = number(8..16);
+    let nodes = number(16..24);
+    let length = number(24..32);
+
+    // The table is read without trusting its length, so a corrupt header
+    // can't cause a huge allocation:
+    let mut table = Vec::new();
+    reader.by_ref().take(length).read_to_end(&mut table)?;
+    if table.len() as u64 != length {
+        return Err(invalid("trie is truncated"));
+    }
+    let mut checksum = [0; 4];
+    reader.read_exact(&mut checksum).map_err(truncated)?;
+    if u32::from_le_bytes(checksum) != crc32([header.as_slice(), &table]) {
+        return Err(invalid("trie checksum does not match"));
+    }
+
+    // Read the end flag and child elements of every node:
+    let mut records: Vec<(bool, Vec<T>)> = Vec::new();
+    let mut rest = table.as_slice();
+    let mut children = 0u64;
+    let mut keys = 0u64;
+    while !rest.is_empty() {
+        let (fixed, tail) = rest.split_at_checked(9).ok_or_else(|| invalid("node table is truncated"))?;
+        let end = match fixed[0] {
+            0 => false,
+            END_FLAG => true,
+            _ => return Err(invalid("invalid node flags")),
+        };
+        let degree = u32::from_le_bytes(fixed[1..5].try_into().unwrap());
+        let size = u32::from_le_bytes(fixed[5..9].try_into().unwrap()) as usize;
+        let (mut labels, tail) = tail.split_at_checked(size).ok_or_else(|| invalid("node table is truncated"))?;
+
+        let mut elements = Vec::new();
+        for _ in 0..degree {
+            let (element, used) = T::decode(labels).ok_or_else(|| invalid("invalid element"))?;
+            elements.push(element);
+            labels = &labels[used..];
+        }
+        if !labels.is_empty() {
+            return Err(invalid("invalid element"));
+        }
+
+        children += u64::from(degree);
+        keys += u64::from(end);
+        records.push((end, elements));
+        rest = tail;
+    }
+    if records.len() as u64 != nodes || children + 1 != nodes {
+        return Err(invalid("node count does not match"));
+    }
+    if keys != count {
+        return Err(invalid("key count does not match"));
+    }
+    let count = usize::try_from(count).map_err(|_| invalid("key count is too large"))?;
+
+    // The children of each node follow the children of every node before it,
+    // and must come after the node itself:
+    let mut first = Vec::with_capacity(records.len());
+    let mut next = 1;
+    for (index, (_, elements)) in records.iter().enumerate() {
+        if !elements.is_empty() && next <= index {
+            return Err(invalid("node table is out of order"));
+        }
+        first.push(next);
+        next += elements.len();
+    }
+
+    // Build the trie from the bottom up, so every child is complete before it
+    // is moved into its parent:
+    let mut built: Vec<Option<TrieNode<T, H>>> = Vec::new();
+    built.resize_with(records.len(), || None);
+    for (index, (end, elements)) in records.into_iter().enumerate().rev() {
+        let mut node = TrieNode::<T, H>::empty();
+        for (offset, element) in elements.into_iter().enumerate() {
+            let child = built[first[index] + offset].take().unwrap();
+            if node.children.insert(element, child).is_some() {
+                return Err(invalid("node has a duplicate child"));
+            }
+        }
+        if index > 0 && !end && node.is_empty() {
+            return Err(invalid("branch does not lead to a key"));
+        }
+        node.value = end.then_some(());
+        #[cfg(feature = "counts")]
+        {
+            node.count = usize::from(end) + node.children.values().map(|child| child.count).sum::<usize>();
+        }
+        built[index] = Some(node);
+    }
+    Ok((built[0].take().unwrap(), count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::Trie;
+
+    fn written(trie: &Trie<char>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Rewrites the checksum of `bytes` after they have been modified.
+    fn reseal(bytes: &mut [u8]) {
+        let (body, checksum) = bytes.split_at_mut(bytes.len() - 4);
+        checksum.copy_from_slice(&crc32([&*body]).to_le_bytes());
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        let error = Trie::<char>::read_from(bytes).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        error.to_string()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32([&b"123456789"[..]]), 0xcbf4_3926);
+        assert_eq!(crc32([&b"1234"[..], b"56789"]), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_write_read() {
+        let keys = ["", "a", "ab", "abc", "banana", "band", "日本", "日本語"];
+        let bytes = written(&Trie::from_iter(keys.map(str::chars)));
+        let trie = Trie::<char>::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(trie.len(), keys.len());
+        let found: Vec<String> = trie.iter().map(String::from_iter).collect();
+        assert_eq!(found, keys);
+        assert_eq!(trie.count_with_prefix("ban".chars()), 2);
+
+        // Nodes aren't nested, so keys far deeper than a JSON parser's
+        // recursion limit read back:
+        let long = "a".repeat(1_000);
+        let trie = Trie::<char>::read_from(written(&Trie::from_iter([long.chars()])).as_slice()).unwrap();
+        assert!(trie.contains(long.chars()));
+    }
+
+    #[test]
+    fn test_reject_corrupt() {
+        let bytes = written(&Trie::from_iter(["tea", "ten"].map(str::chars)));
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(error(&corrupt), "not a serialized trie");
+
+        let mut corrupt = bytes.clone();
+        corrupt[4] = 9;
+        assert_eq!(error(&corrupt), "unsupported trie version");
+
+        assert_eq!(error(&bytes[..bytes.len() - 1]), "trie is truncated");
+
+        // Flip a single bit of an element:
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER + 9] ^= 1;
+        assert_eq!(error(&corrupt), "trie checksum does not match");
+
+        // A node with the wrong key count, but a valid checksum:
+        let mut corrupt = bytes.clone();
+        corrupt[8] = 3;
+        reseal(&mut corrupt);
+        assert_eq!(error(&corrupt), "key count does not match");
+
+        // Clear the end flag of the last leaf, leaving a branch without a key:
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 4 - 9;
+        assert_eq!(corrupt[last], END_FLAG);
+        corrupt[last] = 0;
+        corrupt[8] = 1;
+        reseal(&mut corrupt);
+        assert_eq!(error(&corrupt), "branch does not lead to a key");
+    }
+
+    #[test]
+    fn test_reject_out_of_order() {
+        let bytes = written(&Trie::from_iter(["", "a"].map(str::chars)));
+        // Swap the root and its child, so the child claims to be its own
+        // child:
+        let mut corrupt = bytes.clone();
+        let table = HEADER..corrupt.len() - 4;
+        let root = corrupt[table.start..table.start + 10].to_vec();
+        let leaf = corrupt[table.start + 10..table.end].to_vec();
+        corrupt[table.clone()].copy_from_slice(&[leaf, root].concat());
+        reseal(&mut corrupt);
+        assert_eq!(error(&corrupt), "node table is out of order");
+    }
+
+    #[test]
+    fn test_reject_duplicate_child() {
+        let bytes = written(&Trie::from_iter(["ab", "ac"].map(str::chars)));
+        // Both children of "a" lead through "b":
+        let mut corrupt = bytes.clone();
+        let position = corrupt.len() - 4 - 2 * 9 - 2;
+        corrupt[position..position + 2].copy_from_slice(b"bb");
+        reseal(&mut corrupt);
+        assert_eq!(error(&corrupt), "node has a duplicate child");
+    }
+}
@@ -0,0 +1,151 @@
+/// The number of bits covered by each entry of the rank directory.
+const BLOCK_BITS: usize = 512;
+
+/// The number of words covered by each entry of the rank directory.
+const BLOCK_WORDS: usize = BLOCK_BITS / 64;
+
+/// A bit vector that supports rank and select queries.
+///
+/// Alongside the bits, the vector stores the number of set bits before every
+/// block of 512 bits. This adds one eighth to the size of the bits, and lets
+/// `rank` count at most eight words. `select` binary searches the blocks
+/// before scanning a single block.
+pub(super) struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+    /// The number of set bits before each block.
+    ranks: Vec<usize>,
+}
+
+impl BitVec {
+    /// Creates a new empty [`BitVec`].
+    pub(super) fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            len: 0,
+            ranks: vec![0],
+        }
+    }
+
+    /// Appends a bit to the end of the vector.
+    pub(super) fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        if bit {
+            *self.words.last_mut().unwrap() |= 1 << (self.len % 64);
+        }
+        self.len += 1;
+
+        // Start a new block once the last one is full:
+        if self.len.is_multiple_of(BLOCK_BITS) {
+            let block = self.len / BLOCK_BITS - 1;
+            let ones = self.words[block * BLOCK_WORDS..]
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
+            self.ranks.push(self.ranks[block] + ones);
+        }
+    }
+
+    /// Returns the number of bits in the vector.
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the bit at `index`.
+    pub(super) fn get(&self, index: usize) -> bool {
+        debug_assert!(index < self.len);
+        self.words[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    /// Returns the number of set bits before `index`.
+    pub(super) fn rank1(&self, index: usize) -> usize {
+        debug_assert!(index <= self.len);
+        let block = index / BLOCK_BITS;
+        let word = index / 64;
+        let mut rank = self.ranks[block];
+        rank += self.words[block * BLOCK_WORDS..word]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>();
+        if !index.is_multiple_of(64) {
+            let mask = (1 << (index % 64)) - 1;
+            rank += (self.words[word] & mask).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Returns the position of the unset bit with `rank` unset bits before
+    /// it.
+    ///
+    /// # Panics
+    /// Panics if there are not more than `rank` unset bits.
+    pub(super) fn select0(&self, rank: usize) -> usize {
+        // Find the last block with at most `rank` unset bits before it:
+        let zeros = |block: usize| block * BLOCK_BITS - self.ranks[block];
+        let mut low = 0;
+        let mut high = self.ranks.len();
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if zeros(middle) <= rank {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        // Scan the words of the block:
+        let mut remaining = rank - zeros(low);
+        for (index, word) in self.words[low * BLOCK_WORDS..].iter().enumerate() {
+            let mut word = !word;
+            let count = word.count_ones() as usize;
+            if remaining < count {
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                let position = (low * BLOCK_WORDS + index) * 64 + word.trailing_zeros() as usize;
+                assert!(position < self.len, "select0 out of bounds");
+                return position;
+            }
+            remaining -= count;
+        }
+        panic!("select0 out of bounds");
+    }
+
+    /// Returns the number of bytes used by the vector and its directory.
+    pub(super) fn size_in_bytes(&self) -> usize {
+        self.words.len() * std::mem::size_of::<u64>()
+            + self.ranks.len() * std::mem::size_of::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_select() {
+        // Every third bit is unset:
+        let mut bits = BitVec::new();
+        let len = 5_000;
+        for index in 0..len {
+            bits.push(index % 3 != 0);
+        }
+        assert_eq!(bits.len(), len);
+
+        let mut ones = 0;
+        for index in 0..len {
+            assert_eq!(bits.rank1(index), ones);
+            assert_eq!(bits.get(index), index % 3 != 0);
+            if bits.get(index) {
+                ones += 1;
+            }
+        }
+        assert_eq!(bits.rank1(len), ones);
+
+        for rank in 0..len.div_ceil(3) {
+            assert_eq!(bits.select0(rank), rank * 3);
+        }
+    }
+}
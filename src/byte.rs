@@ -0,0 +1,301 @@
+use std::io::{
+    self,
+    Read,
+    Write,
+};
+
+use crate::{
+    aho_corasick::{
+        AhoCorasick,
+        MatchKind,
+    },
+    children::ByteBitmap,
+    iter::TrieIter,
+    node::TrieNode,
+    trie::Trie,
+};
+
+/// A trie keyed on bytes.
+///
+/// Keys are stored one byte per node, with the children of each node in a
+/// [`ByteBitmap`], so raw bytes can be matched without decoding them first.
+/// Both `&[u8]` and `&str` keys are accepted. The trie tracks whether every key
+/// is valid UTF-8, and while that holds its keys can also be read back as
+/// strings.
+///
+/// Keys are always visited in lexicographic order of their bytes, which for
+/// UTF-8 keys is the same as the order of their [`char`]s.
+#[derive(Default)]
+pub struct ByteTrie {
+    trie: Trie<u8, ByteBitmap>,
+    /// The number of keys that are not valid UTF-8.
+    binary: usize,
+}
+
+impl ByteTrie {
+    /// Returns a new empty byte trie.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if no values are stored within the trie.
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.binary = 0;
+    }
+
+    /// Returns `true` if every key within the trie is valid UTF-8.
+    pub fn is_utf8(&self) -> bool {
+        self.binary == 0
+    }
+
+    /// Inserts a key into the trie.
+    ///
+    /// Returns `true` if the key was not already in the trie.
+    pub fn insert(&mut self, key: impl AsRef<[u8]>) -> bool {
+        let key = key.as_ref();
+        let inserted = self.trie.insert(key.iter().copied());
+        if inserted && std::str::from_utf8(key).is_err() {
+            self.binary += 1;
+        }
+        inserted
+    }
+
+    /// Removes a key from the trie.
+    ///
+    /// Returns `true` if the key was in the trie.
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> bool {
+        let key = key.as_ref();
+        let removed = self.trie.remove(key.iter().copied());
+        if removed && std::str::from_utf8(key).is_err() {
+            self.binary -= 1;
+        }
+        removed
+    }
+
+    /// Returns `true` if the trie contains the key, otherwise returns `false`.
+    pub fn contains(&self, key: impl AsRef<[u8]>) -> bool {
+        self.trie.contains(key.as_ref().iter().copied())
+    }
+
+    /// Returns `true` if any key within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: impl AsRef<[u8]>) -> bool {
+        self.trie.starts_with(prefix.as_ref().iter().copied())
+    }
+
+    /// Returns the number of keys within the trie that start with `prefix`.
+    pub fn count_with_prefix(&self, prefix: impl AsRef<[u8]>) -> usize {
+        self.trie.count_with_prefix(prefix.as_ref().iter().copied())
+    }
+
+    /// Returns an iterator over every key within the trie that is a prefix of
+    /// `input`, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a [u8]) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.trie
+            .prefixes_of(input.iter().copied())
+            .map(|length| &input[..length])
+    }
+
+    /// Returns the longest key within the trie that is a prefix of `input`.
+    pub fn longest_prefix_of<'a>(&'a self, input: &'a [u8]) -> Option<&'a [u8]> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns the shortest key within the trie that is a prefix of `input`.
+    pub fn shortest_prefix_of<'a>(&'a self, input: &'a [u8]) -> Option<&'a [u8]> {
+        self.prefixes_of(input).next()
+    }
+
+    /// Returns an iterator over every key within the trie that is a prefix of
+    /// `input`, from shortest to longest.
+    ///
+    /// Keys that end part of the way through a [`char`] of `input` are
+    /// skipped. This can only happen if some key is not valid UTF-8.
+    pub fn prefixes_of_str<'a>(&'a self, input: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.trie
+            .prefixes_of(input.bytes())
+            .filter(|length| input.is_char_boundary(*length))
+            .map(|length| &input[..length])
+    }
+
+    /// Returns the longest key within the trie that is a prefix of `input`.
+    ///
+    /// See [`ByteTrie::prefixes_of_str`].
+    pub fn longest_prefix_of_str<'a>(&'a self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of_str(input).last()
+    }
+
+    /// Returns the shortest key within the trie that is a prefix of `input`.
+    ///
+    /// See [`ByteTrie::prefixes_of_str`].
+    pub fn shortest_prefix_of_str<'a>(&'a self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of_str(input).next()
+    }
+
+    /// Returns an iterator over every key in the trie, in lexicographic order.
+    pub fn iter(&self) -> TrieIter<'_, u8, ByteBitmap> {
+        self.trie.iter()
+    }
+
+    /// Returns an iterator over every key in the trie that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: impl AsRef<[u8]>) -> TrieIter<'_, u8, ByteBitmap> {
+        self.trie.iter_prefix(prefix.as_ref().iter().copied())
+    }
+
+    /// Returns an iterator over every key in the trie as a [`String`], in
+    /// lexicographic order.
+    ///
+    /// Returns [`None`] if any key is not valid UTF-8.
+    pub fn iter_str(&self) -> Option<impl Iterator<Item = String> + '_> {
+        self.is_utf8()
+            .then(|| self.iter().map(into_string))
+    }
+
+    /// Returns an iterator over every key in the trie that starts with
+    /// `prefix` as a [`String`], in lexicographic order.
+    ///
+    /// Returns [`None`] if any key is not valid UTF-8.
+    pub fn iter_prefix_str(&self, prefix: &str) -> Option<impl Iterator<Item = String> + '_> {
+        self.is_utf8()
+            .then(|| self.iter_prefix(prefix).map(into_string))
+    }
+
+    /// Compiles an [`AhoCorasick`] automaton from the trie, which finds every
+    /// key within a sequence of bytes in a single pass.
+    ///
+    /// The automaton is a snapshot, so keys added to the trie later are not
+    /// found by it.
+    pub fn aho_corasick(&self, kind: MatchKind) -> AhoCorasick<u8> {
+        self.trie.aho_corasick(kind)
+    }
+
+    /// Writes the trie to `writer` in a versioned binary format, which can be
+    /// read back with [`ByteTrie::read_from`].
+    ///
+    /// See [`Trie::write_to`].
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        self.trie.write_to(writer)
+    }
+
+    /// Reads a trie written by [`ByteTrie::write_to`].
+    ///
+    /// See [`Trie::read_from`].
+    pub fn read_from(reader: impl Read) -> io::Result<Self> {
+        let trie = Trie::read_from(reader)?;
+        let binary = trie
+            .iter()
+            .filter(|key| std::str::from_utf8(key).is_err())
+            .count();
+        Ok(Self { trie, binary })
+    }
+
+    /// Writes the trie to `writer` in a compact binary form that a
+    /// [`MappedTrie`](crate::mapped::MappedTrie) can query in place.
+    pub fn write_mapped(&self, writer: impl Write) -> io::Result<()> {
+        self.trie.write_mapped(writer)
+    }
+
+    /// Returns the number of nodes within the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.trie.node_count()
+    }
+
+    /// Returns an immutable reference to the root [`TrieNode`].
+    pub fn root(&self) -> &TrieNode<u8, ByteBitmap> {
+        self.trie.root()
+    }
+}
+
+/// Converts a key into a [`String`], once every key is known to be valid
+/// UTF-8.
+fn into_string(key: Vec<u8>) -> String {
+    String::from_utf8(key).expect("every key is valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_trie() {
+        let mut trie = ByteTrie::new();
+        for key in ["日本", "日本語", "tea", "ten", "to"] {
+            assert!(trie.insert(key));
+        }
+        assert!(!trie.insert(b"tea"));
+        assert_eq!(trie.len(), 5);
+        assert!(trie.is_utf8());
+
+        assert!(trie.contains("日本"));
+        assert!(trie.contains("日本".as_bytes()));
+        assert!(!trie.contains("日"));
+        assert!(trie.starts_with([0xe6]));
+        assert_eq!(trie.count_with_prefix("t"), 3);
+        assert_eq!(trie.longest_prefix_of_str("日本語です"), Some("日本語"));
+        assert_eq!(trie.prefixes_of(b"tension").collect::<Vec<_>>(), [b"ten"]);
+
+        let found: Vec<String> = trie.iter_str().unwrap().collect();
+        assert_eq!(found, ["tea", "ten", "to", "日本", "日本語"]);
+        let found: Vec<String> = trie.iter_prefix_str("日").unwrap().collect();
+        assert_eq!(found, ["日本", "日本語"]);
+    }
+
+    #[test]
+    fn test_binary_keys() {
+        let mut trie = ByteTrie::new();
+        assert!(trie.insert("日本"));
+        // The first byte of "日" on its own:
+        assert!(trie.insert([0xe6]));
+        assert!(trie.insert([0x00, 0xff]));
+        assert!(!trie.is_utf8());
+        assert!(trie.iter_str().is_none());
+        assert_eq!(trie.iter().collect::<Vec<_>>(), [&[0x00, 0xff][..], &[0xe6], "日本".as_bytes()]);
+
+        // Keys that end part of the way through a char are skipped:
+        assert_eq!(trie.prefixes_of("日本語".as_bytes()).count(), 2);
+        assert_eq!(trie.prefixes_of_str("日本語").collect::<Vec<_>>(), ["日本"]);
+
+        let mut bytes = Vec::new();
+        trie.write_mapped(&mut bytes).unwrap();
+        let mapped = crate::mapped::MappedTrie::new(&bytes).unwrap();
+        assert_eq!(mapped.iter().collect::<Vec<_>>(), trie.iter().collect::<Vec<_>>());
+
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        let read = ByteTrie::read_from(&bytes[..]).unwrap();
+        assert!(!read.is_utf8());
+        assert_eq!(read.iter().collect::<Vec<_>>(), trie.iter().collect::<Vec<_>>());
+
+        assert!(trie.remove([0xe6]));
+        assert!(!trie.remove([0xe6]));
+        assert!(!trie.is_utf8());
+        assert!(trie.remove([0x00, 0xff]));
+        assert!(trie.is_utf8());
+        assert_eq!(trie.iter_str().unwrap().collect::<Vec<_>>(), ["日本"]);
+    }
+
+    #[test]
+    fn test_aho_corasick() {
+        let mut trie = ByteTrie::new();
+        for key in [&b"\x00\x01"[..], b"\x01\x02\x03", b"\xff"] {
+            assert!(trie.insert(key));
+        }
+        let automaton = trie.aho_corasick(MatchKind::Overlapping);
+        let found: Vec<_> = automaton
+            .find_iter(b"\x00\x01\x02\x03\xff")
+            .map(|found| (found.start(), found.end()))
+            .collect();
+        assert_eq!(found, [(0, 2), (1, 4), (4, 5)]);
+    }
+}
@@ -0,0 +1,736 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    hash::{
+        BuildHasher,
+        Hash,
+        RandomState,
+    },
+    marker::PhantomData,
+};
+
+/// A container mapping each element to the child node it leads to.
+///
+/// Every [`TrieNode`](crate::node::TrieNode) stores its children in one of
+/// these. The container is chosen through a [`ChildStorage`], which is the
+/// last type parameter of the trie types.
+pub trait Children<T, N>: Default {
+    /// Returns the child for `element`, if any.
+    fn get(&self, element: &T) -> Option<&N>;
+
+    /// Returns a mutable reference to the child for `element`, if any.
+    fn get_mut(&mut self, element: &T) -> Option<&mut N>;
+
+    /// Returns the child for `element`, inserting the result of `default` if
+    /// there isn't one.
+    fn get_or_insert_with(&mut self, element: T, default: impl FnOnce() -> N) -> &mut N;
+
+    /// Inserts a child for `element`, returning the child it replaced.
+    fn insert(&mut self, element: T, child: N) -> Option<N>;
+
+    /// Removes and returns the child for `element`, if any.
+    fn remove(&mut self, element: &T) -> Option<N>;
+
+    /// Returns the number of children.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no children.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every child.
+    fn clear(&mut self);
+
+    /// Returns an iterator over every element and its child.
+    ///
+    /// The order depends on the container, and is arbitrary unless the
+    /// container says otherwise.
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a N)>
+    where
+        T: 'a,
+        N: 'a;
+
+    /// Returns an iterator over every child.
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a N>
+    where
+        T: 'a,
+        N: 'a,
+    {
+        self.iter().map(|(_, child)| child)
+    }
+}
+
+/// Chooses the [`Children`] container used by every node of a trie.
+///
+/// Any [`BuildHasher`] stores children in a [`HashMap`] using that hasher,
+/// which is the default. This crate also provides [`SortedVec`], [`BTree`],
+/// [`ByteArray`], [`ByteBitmap`] and [`Adaptive`] storage.
+pub trait ChildStorage<T> {
+    /// The container holding children of type `N`.
+    type Children<N>: Children<T, N>;
+}
+
+impl<T, S> ChildStorage<T> for S
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Children<N> = HashMap<T, N, S>;
+}
+
+impl<T, N, S> Children<T, N> for HashMap<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn get(&self, element: &T) -> Option<&N> {
+        HashMap::get(self, element)
+    }
+
+    fn get_mut(&mut self, element: &T) -> Option<&mut N> {
+        HashMap::get_mut(self, element)
+    }
+
+    fn get_or_insert_with(&mut self, element: T, default: impl FnOnce() -> N) -> &mut N {
+        self.entry(element).or_insert_with(default)
+    }
+
+    fn insert(&mut self, element: T, child: N) -> Option<N> {
+        HashMap::insert(self, element, child)
+    }
+
+    fn remove(&mut self, element: &T) -> Option<N> {
+        HashMap::remove(self, element)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a N)>
+    where
+        T: 'a,
+        N: 'a,
+    {
+        HashMap::iter(self)
+    }
+}
+
+/// Stores children in a [`BTreeMap`], so they are always visited in order.
+#[derive(Default)]
+pub struct BTree;
+
+impl<T> ChildStorage<T> for BTree
+where
+    T: Ord,
+{
+    type Children<N> = BTreeMap<T, N>;
+}
+
+impl<T, N> Children<T, N> for BTreeMap<T, N>
+where
+    T: Ord,
+{
+    fn get(&self, element: &T) -> Option<&N> {
+        BTreeMap::get(self, element)
+    }
+
+    fn get_mut(&mut self, element: &T) -> Option<&mut N> {
+        BTreeMap::get_mut(self, element)
+    }
+
+    fn get_or_insert_with(&mut self, element: T, default: impl FnOnce() -> N) -> &mut N {
+        self.entry(element).or_insert_with(default)
+    }
+
+    fn insert(&mut self, element: T, child: N) -> Option<N> {
+        BTreeMap::insert(self, element, child)
+    }
+
+    fn remove(&mut self, element: &T) -> Option<N> {
+        BTreeMap::remove(self, element)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self);
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a N)>
+    where
+        T: 'a,
+        N: 'a,
+    {
+        BTreeMap::iter(self)
+    }
+}
+
+/// Stores children in a [`Vec`] sorted by element, which is searched with a
+/// binary search.
+///
+/// This only allocates room for the children a node actually has, and
+/// children are always visited in order. Inserting and removing children is
+/// linear in the number of children, so this suits tries with a small
+/// fan-out.
+#[derive(Default)]
+pub struct SortedVec;
+
+impl<T> ChildStorage<T> for SortedVec
+where
+    T: Ord,
+{
+    type Children<N> = SortedVecChildren<T, N>;
+}
+
+/// The [`Children`] container used by [`SortedVec`] storage.
+pub struct SortedVecChildren<T, N>(Vec<(T, N)>);
+
+impl<T, N> Default for SortedVecChildren<T, N> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T, N> SortedVecChildren<T, N>
+where
+    T: Ord,
+{
+    /// Finds the index of the child for `element`, or the index it would be
+    /// inserted at.
+    fn search(&self, element: &T) -> Result<usize, usize> {
+        self.0.binary_search_by(|(other, _)| other.cmp(element))
+    }
+}
+
+impl<T, N> Children<T, N> for SortedVecChildren<T, N>
+where
+    T: Ord,
+{
+    fn get(&self, element: &T) -> Option<&N> {
+        self.search(element)
+            .ok()
+            .map(|index| &self.0[index].1)
+    }
+
+    fn get_mut(&mut self, element: &T) -> Option<&mut N> {
+        self.search(element)
+            .ok()
+            .map(|index| &mut self.0[index].1)
+    }
+
+    fn get_or_insert_with(&mut self, element: T, default: impl FnOnce() -> N) -> &mut N {
+        let index = match self.search(&element) {
+            Ok(index) => index,
+            Err(index) => {
+                self.0.insert(index, (element, default()));
+                index
+            },
+        };
+        &mut self.0[index].1
+    }
+
+    fn insert(&mut self, element: T, child: N) -> Option<N> {
+        match self.search(&element) {
+            Ok(index) => Some(std::mem::replace(&mut self.0[index].1, child)),
+            Err(index) => {
+                self.0.insert(index, (element, child));
+                None
+            },
+        }
+    }
+
+    fn remove(&mut self, element: &T) -> Option<N> {
+        self.search(element)
+            .ok()
+            .map(|index| self.0.remove(index).1)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a N)>
+    where
+        T: 'a,
+        N: 'a,
+    {
+        self.0.iter().map(|(element, child)| (element, child))
+    }
+}
+
+/// Every byte, indexed by itself.
+///
+/// Byte containers don't store the element alongside each child, so this lets
+/// them hand out references to the elements anyway.
+static BYTES: [u8; 256] = {
+    let mut bytes = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        bytes[index] = index as u8;
+        index += 1;
+    }
+    bytes
+};
+
+/// Stores the children of `u8` tries in a 256-entry array indexed by byte.
+///
+/// Finding a child is a single index, and children are always visited in
+/// order. The array is only allocated once a node has a child, but is then
+/// always 256 entries long, so this suits dense tries with a high fan-out.
+#[derive(Default)]
+pub struct ByteArray;
+
+impl ChildStorage<u8> for ByteArray {
+    type Children<N> = ByteArrayChildren<N>;
+}
+
+/// The [`Children`] container used by [`ByteArray`] storage.
+pub struct ByteArrayChildren<N> {
+    /// Either empty, or one slot for every byte.
+    slots: Box<[Option<N>]>,
+    len: usize,
+}
+
+impl<N> Default for ByteArrayChildren<N> {
+    fn default() -> Self {
+        Self {
+            slots: Box::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<N> ByteArrayChildren<N> {
+    /// Returns the slot for `byte`, allocating the array if needed.
+    fn slot(&mut self, byte: u8) -> &mut Option<N> {
+        if self.slots.is_empty() {
+            self.slots = (0..=u8::MAX).map(|_| None).collect();
+        }
+        &mut self.slots[usize::from(byte)]
+    }
+}
+
+impl<N> Children<u8, N> for ByteArrayChildren<N> {
+    fn get(&self, element: &u8) -> Option<&N> {
+        self.slots.get(usize::from(*element))?.as_ref()
+    }
+
+    fn get_mut(&mut self, element: &u8) -> Option<&mut N> {
+        self.slots.get_mut(usize::from(*element))?.as_mut()
+    }
+
+    fn get_or_insert_with(&mut self, element: u8, default: impl FnOnce() -> N) -> &mut N {
+        let slot = self.slot(element);
+        if slot.is_none() {
+            *slot = Some(default());
+            self.len += 1;
+        }
+        // The slot was filled above if it was empty:
+        self.slots[usize::from(element)].as_mut().unwrap()
+    }
+
+    fn insert(&mut self, element: u8, child: N) -> Option<N> {
+        let previous = self.slot(element).replace(child);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    fn remove(&mut self, element: &u8) -> Option<N> {
+        let removed = self.slots.get_mut(usize::from(*element))?.take();
+        if removed.is_some() {
+            self.len -= 1;
+            // Free the array once the last child is gone:
+            if self.len == 0 {
+                self.slots = Box::default();
+            }
+        }
+        removed
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.slots = Box::default();
+        self.len = 0;
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a u8, &'a N)>
+    where
+        N: 'a,
+    {
+        // Every slot index is a byte, so the element can be borrowed from a
+        // static table rather than stored alongside each child:
+        self.slots
+            .iter()
+            .zip(&BYTES)
+            .filter_map(|(slot, byte)| Some((byte, slot.as_ref()?)))
+    }
+}
+
+/// Stores the children of `u8` tries in a [`Vec`] sorted by byte, alongside a
+/// 256-bit bitmap of the bytes that have a child.
+///
+/// The position of a child is the number of set bits below its byte, so
+/// finding a child takes constant time while a node only allocates room for
+/// the children it has. Children are always visited in order. This is the
+/// storage used by [`ByteTrie`](crate::byte::ByteTrie).
+#[derive(Default)]
+pub struct ByteBitmap;
+
+impl ChildStorage<u8> for ByteBitmap {
+    type Children<N> = ByteBitmapChildren<N>;
+}
+
+/// The [`Children`] container used by [`ByteBitmap`] storage.
+pub struct ByteBitmapChildren<N> {
+    /// One bit for each byte, set if the byte has a child.
+    bits: [u64; 4],
+    /// Every child, sorted by byte.
+    children: Vec<N>,
+}
+
+impl<N> Default for ByteBitmapChildren<N> {
+    fn default() -> Self {
+        Self {
+            bits: [0; 4],
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<N> ByteBitmapChildren<N> {
+    /// Returns `true` if `byte` has a child.
+    fn has(&self, byte: u8) -> bool {
+        self.bits[usize::from(byte / 64)] >> (byte % 64) & 1 == 1
+    }
+
+    /// Returns the index of the child for `byte` within `children`, or the
+    /// index it would be inserted at.
+    fn index(&self, byte: u8) -> usize {
+        let word = usize::from(byte / 64);
+        let below = self.bits[word] & ((1 << (byte % 64)) - 1);
+        self.bits[..word]
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum::<usize>()
+            + below.count_ones() as usize
+    }
+
+    /// Sets or clears the bit for `byte`.
+    fn set(&mut self, byte: u8, present: bool) {
+        let bit = 1 << (byte % 64);
+        let word = &mut self.bits[usize::from(byte / 64)];
+        if present {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+}
+
+impl<N> Children<u8, N> for ByteBitmapChildren<N> {
+    fn get(&self, element: &u8) -> Option<&N> {
+        self.has(*element)
+            .then(|| &self.children[self.index(*element)])
+    }
+
+    fn get_mut(&mut self, element: &u8) -> Option<&mut N> {
+        if !self.has(*element) {
+            return None;
+        }
+        let index = self.index(*element);
+        Some(&mut self.children[index])
+    }
+
+    fn get_or_insert_with(&mut self, element: u8, default: impl FnOnce() -> N) -> &mut N {
+        let index = self.index(element);
+        if !self.has(element) {
+            self.children.insert(index, default());
+            self.set(element, true);
+        }
+        &mut self.children[index]
+    }
+
+    fn insert(&mut self, element: u8, child: N) -> Option<N> {
+        let index = self.index(element);
+        if self.has(element) {
+            return Some(std::mem::replace(&mut self.children[index], child));
+        }
+        self.children.insert(index, child);
+        self.set(element, true);
+        None
+    }
+
+    fn remove(&mut self, element: &u8) -> Option<N> {
+        if !self.has(*element) {
+            return None;
+        }
+        self.set(*element, false);
+        Some(self.children.remove(self.index(*element)))
+    }
+
+    fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    fn clear(&mut self) {
+        self.bits = [0; 4];
+        self.children.clear();
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a u8, &'a N)>
+    where
+        N: 'a,
+    {
+        BYTES
+            .iter()
+            .filter(|byte| self.has(**byte))
+            .zip(&self.children)
+    }
+}
+
+/// The number of children a node using [`Adaptive`] storage keeps in a
+/// [`Vec`] before switching to a [`HashMap`].
+const ADAPTIVE_LIMIT: usize = 8;
+
+/// Stores a few children in a small [`Vec`], switching to a [`HashMap`] that
+/// uses the hasher `S` once a node has more than eight children.
+///
+/// Most nodes in a typical trie only have one or two children. Those nodes
+/// only allocate room for the children they have and are searched linearly,
+/// while nodes with a high fan-out keep constant time lookups. A node
+/// switches back to a [`Vec`] once it is down to four children.
+pub struct Adaptive<S = RandomState>(PhantomData<S>);
+
+impl<S> Default for Adaptive<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, S> ChildStorage<T> for Adaptive<S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Children<N> = AdaptiveChildren<T, N, S>;
+}
+
+/// The [`Children`] container used by [`Adaptive`] storage.
+pub enum AdaptiveChildren<T, N, S = RandomState> {
+    /// At most eight children, in no particular order.
+    Small(Vec<(T, N)>),
+    /// More than eight children, or more than four after shrinking.
+    Large(HashMap<T, N, S>),
+}
+
+impl<T, N, S> Default for AdaptiveChildren<T, N, S> {
+    fn default() -> Self {
+        Self::Small(Vec::new())
+    }
+}
+
+impl<T, N, S> AdaptiveChildren<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    /// Switches to a [`HashMap`] if a small node is about to outgrow its
+    /// [`Vec`].
+    fn grow(&mut self) {
+        if let Self::Small(children) = self {
+            if children.len() >= ADAPTIVE_LIMIT {
+                let mut map = HashMap::with_capacity_and_hasher(children.len() + 1, S::default());
+                map.extend(children.drain(..));
+                *self = Self::Large(map);
+            }
+        }
+    }
+}
+
+impl<T, N, S> Children<T, N> for AdaptiveChildren<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn get(&self, element: &T) -> Option<&N> {
+        match self {
+            Self::Small(children) => children
+                .iter()
+                .find(|(other, _)| other == element)
+                .map(|(_, child)| child),
+            Self::Large(children) => children.get(element),
+        }
+    }
+
+    fn get_mut(&mut self, element: &T) -> Option<&mut N> {
+        match self {
+            Self::Small(children) => children
+                .iter_mut()
+                .find(|(other, _)| other == element)
+                .map(|(_, child)| child),
+            Self::Large(children) => children.get_mut(element),
+        }
+    }
+
+    fn get_or_insert_with(&mut self, element: T, default: impl FnOnce() -> N) -> &mut N {
+        if self.get(&element).is_none() {
+            self.grow();
+        }
+        match self {
+            Self::Small(children) => {
+                let index = match children.iter().position(|(other, _)| *other == element) {
+                    Some(index) => index,
+                    None => {
+                        children.push((element, default()));
+                        children.len() - 1
+                    },
+                };
+                &mut children[index].1
+            },
+            Self::Large(children) => children.entry(element).or_insert_with(default),
+        }
+    }
+
+    fn insert(&mut self, element: T, child: N) -> Option<N> {
+        if let Some(existing) = self.get_mut(&element) {
+            return Some(std::mem::replace(existing, child));
+        }
+        self.grow();
+        match self {
+            Self::Small(children) => children.push((element, child)),
+            Self::Large(children) => {
+                children.insert(element, child);
+            },
+        }
+        None
+    }
+
+    fn remove(&mut self, element: &T) -> Option<N> {
+        match self {
+            Self::Small(children) => {
+                let index = children.iter().position(|(other, _)| other == element)?;
+                Some(children.swap_remove(index).1)
+            },
+            Self::Large(children) => {
+                let removed = children.remove(element);
+                if children.len() <= ADAPTIVE_LIMIT / 2 {
+                    *self = Self::Small(children.drain().collect());
+                }
+                removed
+            },
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Small(children) => children.len(),
+            Self::Large(children) => children.len(),
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::Small(Vec::new());
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a N)>
+    where
+        T: 'a,
+        N: 'a,
+    {
+        let (small, large) = match self {
+            Self::Small(children) => (Some(children), None),
+            Self::Large(children) => (None, Some(children)),
+        };
+        let small = small
+            .into_iter()
+            .flatten()
+            .map(|(element, child)| (element, child));
+        small.chain(large.into_iter().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inserts and removes the same children from a container, checking it
+    /// against a [`BTreeMap`] after every step.
+    fn check<C>(elements: impl IntoIterator<Item = u8> + Clone)
+    where
+        C: Children<u8, usize>,
+    {
+        let mut children = C::default();
+        let mut expected = BTreeMap::new();
+        let compare = |children: &C, expected: &BTreeMap<u8, usize>| {
+            let mut found: Vec<(u8, usize)> = children
+                .iter()
+                .map(|(element, child)| (*element, *child))
+                .collect();
+            found.sort_unstable();
+            assert_eq!(found, expected.iter().map(|(e, c)| (*e, *c)).collect::<Vec<_>>());
+            assert_eq!(children.len(), expected.len());
+        };
+
+        for (index, element) in elements.clone().into_iter().enumerate() {
+            assert_eq!(children.insert(element, index), expected.insert(element, index));
+            *children.get_or_insert_with(element.wrapping_add(1), || 0) += 1;
+            *expected.entry(element.wrapping_add(1)).or_insert(0) += 1;
+            compare(&children, &expected);
+        }
+        for element in elements {
+            assert_eq!(children.get(&element), expected.get(&element));
+            assert_eq!(children.remove(&element), expected.remove(&element));
+            compare(&children, &expected);
+        }
+        children.clear();
+        assert!(children.is_empty());
+    }
+
+    #[test]
+    fn test_backends() {
+        let elements = [5, 3, 200, 3, 17, 0, 255, 9, 12, 1, 40, 41, 42, 43, 7];
+        check::<HashMap<u8, usize>>(elements);
+        check::<BTreeMap<u8, usize>>(elements);
+        check::<SortedVecChildren<u8, usize>>(elements);
+        check::<ByteArrayChildren<usize>>(elements);
+        check::<ByteBitmapChildren<usize>>(elements);
+        check::<AdaptiveChildren<u8, usize>>(elements);
+    }
+
+    #[test]
+    fn test_adaptive_switches() {
+        let mut children = AdaptiveChildren::<u8, ()>::default();
+        for element in 0..ADAPTIVE_LIMIT as u8 {
+            children.insert(element, ());
+        }
+        assert!(matches!(children, AdaptiveChildren::Small(..)));
+        children.insert(100, ());
+        assert!(matches!(children, AdaptiveChildren::Large(..)));
+
+        for element in 0..5 {
+            assert!(children.remove(&element).is_some());
+        }
+        assert!(matches!(children, AdaptiveChildren::Small(..)));
+        assert_eq!(children.len(), 4);
+        assert!(children.get(&100).is_some());
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::Hash,
+};
+
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    fuzzy::EditRows,
+    node::TrieNode,
+};
+
+/// Finds the index of an existing node from its end marker and edges.
+type Registry<T> = HashMap<(bool, Box<[(T, usize)]>), usize>;
+
+/// A node within a [`Dawg`].
+struct DawgNode<T> {
+    /// The node reached by each element, sorted by element so they can be
+    /// binary searched.
+    edges: Box<[(T, usize)]>,
+    /// Set if this node is the end of a key.
+    end: bool,
+}
+
+/// An immutable, minimised directed acyclic word graph.
+///
+/// A DAWG is a trie in which equivalent subtrees are stored only once. Two
+/// nodes are equivalent if they complete exactly the same set of suffixes, so
+/// every key ending in "-ing" or "-tion" shares the nodes for that ending. For
+/// natural language word lists this usually needs far fewer nodes than a
+/// [`Trie`](crate::trie::Trie).
+///
+/// Since nodes are shared, a DAWG can only record which keys it contains, not
+/// a value for each key. A DAWG is created with
+/// [`Trie::freeze`](crate::trie::Trie::freeze) and can't be modified
+/// afterwards.
+pub struct Dawg<T> {
+    /// Every node, with children always stored before their parents.
+    nodes: Vec<DawgNode<T>>,
+    /// The index of the root node.
+    root: usize,
+    /// The number of keys within the DAWG.
+    count: usize,
+}
+
+impl<T> Dawg<T>
+where
+    T: Hash + Ord + Copy,
+{
+    /// Builds a minimised [`Dawg`] containing every key at or below `root`.
+    pub fn new<V, H>(root: &TrieNode<T, H, V>) -> Self
+    where
+        H: ChildStorage<T>,
+    {
+        let mut dawg = Self {
+            nodes: Vec::new(),
+            root: 0,
+            count: 0,
+        };
+        let mut registry = HashMap::new();
+        dawg.root = dawg.add(root, &mut registry);
+        dawg
+    }
+
+    /// Adds `node` and every node below it, returning the index of `node`.
+    ///
+    /// Children are added first, so equivalent children already share an
+    /// index. Two nodes are then equivalent exactly when they have the same end
+    /// marker and the same edges, which `registry` looks up.
+    fn add<V, H>(
+        &mut self,
+        node: &TrieNode<T, H, V>,
+        registry: &mut Registry<T>,
+    ) -> usize
+    where
+        H: ChildStorage<T>,
+    {
+        let mut edges: Vec<(T, usize)> = node
+            .children
+            .iter()
+            .map(|(element, child)| (*element, self.add(child, registry)))
+            .collect();
+        edges.sort_unstable_by_key(|(element, _)| *element);
+
+        let end = node.value.is_some();
+        if end {
+            self.count += 1;
+        }
+        let signature = (end, edges.into_boxed_slice());
+        if let Some(index) = registry.get(&signature) {
+            return *index;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(DawgNode {
+            edges: signature.1.clone(),
+            end,
+        });
+        registry.insert(signature, index);
+        index
+    }
+
+    /// Returns the node reached by reading `element` at `node`.
+    fn get(&self, node: usize, element: &T) -> Option<usize> {
+        let edges = &self.nodes[node].edges;
+        edges
+            .binary_search_by(|(other, _)| other.cmp(element))
+            .ok()
+            .map(|index| edges[index].1)
+    }
+
+    /// Walks from the root along the path described by `iter`.
+    fn walk<E>(&self, iter: impl IntoIterator<Item = E>) -> Option<usize>
+    where
+        E: Borrow<T>,
+    {
+        let mut current = self.root;
+        for element in iter {
+            current = self.get(current, element.borrow())?;
+        }
+        Some(current)
+    }
+
+    /// Returns `true` if the DAWG is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of values within the DAWG.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the number of nodes within the DAWG, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the [`Dawg`] contains a value, otherwise returns
+    /// `false`.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.walk(iter)
+            .is_some_and(|node| self.nodes[node].end)
+    }
+
+    /// Returns `true` if any value within the [`Dawg`] starts with `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        // Every node other than the root leads to a value:
+        self.walk(prefix)
+            .is_some_and(|node| node != self.root || !self.is_empty())
+    }
+
+    /// Returns an iterator over every value in the [`Dawg`], in lexicographic
+    /// order.
+    pub fn iter(&self) -> DawgIter<'_, T> {
+        DawgIter::new(self, Some(self.root), Vec::new())
+    }
+
+    /// Returns an iterator over every value in the [`Dawg`] that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> DawgIter<'_, T> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        DawgIter::new(self, self.walk(&prefix), prefix)
+    }
+
+    /// Returns every value in the [`Dawg`] within `max_distance` edits of
+    /// `query`, along with its distance.
+    ///
+    /// See [`Trie::fuzzy_search`](crate::trie::Trie::fuzzy_search).
+    pub fn fuzzy_search(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> DawgFuzzySearch<'_, T> {
+        DawgFuzzySearch::new(self, query.into_iter().collect(), max_distance, false)
+    }
+
+    /// Returns every value in the [`Dawg`] within `max_distance` edits of
+    /// `query`, counting adjacent transpositions as a single edit.
+    ///
+    /// See [`Trie::fuzzy_search_transpositions`](crate::trie::Trie::fuzzy_search_transpositions).
+    pub fn fuzzy_search_transpositions(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> DawgFuzzySearch<'_, T> {
+        DawgFuzzySearch::new(self, query.into_iter().collect(), max_distance, true)
+    }
+}
+
+/// Iterates over every key below a node of a [`Dawg`], in lexicographic
+/// order.
+pub struct DawgIter<'a, T> {
+    dawg: &'a Dawg<T>,
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may be the end of a key itself.
+    start: Option<usize>,
+    stack: Vec<(T, usize, usize)>,
+    buffer: Vec<T>,
+}
+
+impl<'a, T> DawgIter<'a, T>
+where
+    T: Copy,
+{
+    fn new(dawg: &'a Dawg<T>, node: Option<usize>, prefix: Vec<T>) -> Self {
+        Self {
+            dawg,
+            start: node,
+            stack: Vec::new(),
+            buffer: prefix,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack in reverse order, so that
+    /// the smallest child is popped first.
+    fn push_children(&mut self, node: usize, depth: usize) {
+        let edges = self.dawg.nodes[node].edges.iter().rev();
+        self.stack.extend(edges.map(|(element, child)| (*element, *child, depth)));
+    }
+}
+
+impl<T> Iterator for DawgIter<'_, T>
+where
+    T: Copy,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, self.buffer.len());
+            if self.dawg.nodes[start].end {
+                return Some(self.buffer.clone());
+            }
+        }
+
+        while let Some((element, node, depth)) = self.stack.pop() {
+            // If we're backtracking, truncate the prefix to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.push(element);
+            self.push_children(node, depth + 1);
+
+            if self.dawg.nodes[node].end {
+                return Some(self.buffer.clone());
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over every key of a [`Dawg`] within a maximum edit distance of a
+/// query, in lexicographic order.
+///
+/// See [`FuzzySearch`](crate::fuzzy::FuzzySearch).
+pub struct DawgFuzzySearch<'a, T> {
+    dawg: &'a Dawg<T>,
+    /// Set until the root has been visited on the first call to `next`.
+    start: bool,
+    stack: Vec<(T, usize, usize)>,
+    rows: EditRows<T>,
+}
+
+impl<'a, T> DawgFuzzySearch<'a, T>
+where
+    T: Eq + Copy,
+{
+    fn new(dawg: &'a Dawg<T>, query: Vec<T>, max_distance: usize, transpositions: bool) -> Self {
+        Self {
+            dawg,
+            start: true,
+            stack: Vec::new(),
+            rows: EditRows::new(query, max_distance, transpositions),
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack, unless none of them can
+    /// be within the maximum distance.
+    fn push_children(&mut self, node: usize, depth: usize) {
+        if self.rows.is_exhausted() {
+            return;
+        }
+        let edges = self.dawg.nodes[node].edges.iter().rev();
+        self.stack.extend(edges.map(|(element, child)| (*element, *child, depth)));
+    }
+}
+
+impl<T> Iterator for DawgFuzzySearch<'_, T>
+where
+    T: Eq + Copy,
+{
+    type Item = (Vec<T>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The empty key is stored on the root itself:
+        if std::mem::take(&mut self.start) {
+            let root = self.dawg.root;
+            self.push_children(root, 0);
+            if let (true, Some(distance)) = (self.dawg.nodes[root].end, self.rows.distance()) {
+                return Some((Vec::new(), distance));
+            }
+        }
+
+        while let Some((element, node, depth)) = self.stack.pop() {
+            self.rows.push(depth, element);
+            self.push_children(node, depth + 1);
+
+            if let (true, Some(distance)) = (self.dawg.nodes[node].end, self.rows.distance()) {
+                return Some((self.rows.key().to_vec(), distance));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_minimised() {
+        let trie = Trie::<char>::from_iter(["walking", "talking", "walked", "talked", "station", "nation"].map(str::chars));
+        assert_eq!(trie.node_count(), 32);
+
+        let dawg = trie.freeze();
+        assert_eq!(dawg.len(), 6);
+        // Both "-alk" branches share "ing" and "ed", the "ation" tails are
+        // shared and every key ends at the same leaf:
+        assert_eq!(dawg.node_count(), 15);
+
+        for key in ["walking", "talking", "walked", "talked", "station", "nation"] {
+            assert!(dawg.contains(key.chars()));
+        }
+        assert!(!dawg.contains("walk".chars()));
+        assert!(!dawg.contains("stalked".chars()));
+        assert!(!dawg.contains("ation".chars()));
+        assert!(dawg.starts_with("tal".chars()));
+        assert!(!dawg.starts_with("x".chars()));
+    }
+
+    #[test]
+    fn test_empty() {
+        let dawg = Trie::<char>::new().freeze();
+        assert!(dawg.is_empty());
+        assert!(!dawg.contains("".chars()));
+        assert!(!dawg.starts_with("".chars()));
+        assert_eq!(dawg.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_iter() {
+        let keys = ["", "a", "ab", "abc", "b", "bc", "c"];
+        let trie = Trie::<char>::from_iter(keys.map(str::chars));
+        let dawg = trie.freeze();
+
+        let found: Vec<String> = dawg.iter().map(String::from_iter).collect();
+        assert_eq!(found, keys);
+        let found: Vec<String> = dawg.iter_prefix("a".chars()).map(String::from_iter).collect();
+        assert_eq!(found, ["a", "ab", "abc"]);
+        assert_eq!(dawg.iter_prefix("x".chars()).count(), 0);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let trie = Trie::<char>::from_iter(["test", "tests", "text", "tent", "toast", "best", "taste", "tset"].map(str::chars));
+        let dawg = trie.freeze();
+
+        for max_distance in 0..3 {
+            let mut expected: Vec<(Vec<char>, usize)> = trie.fuzzy_search("test".chars(), max_distance).collect();
+            expected.sort();
+            let found: Vec<(Vec<char>, usize)> = dawg.fuzzy_search("test".chars(), max_distance).collect();
+            assert_eq!(found, expected);
+        }
+
+        let found: Vec<(String, usize)> = dawg
+            .fuzzy_search_transpositions("tets".chars(), 1)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+            .collect();
+        assert_eq!(found, [
+            ("test".to_string(), 1),
+            ("tests".to_string(), 1),
+        ]);
+    }
+}
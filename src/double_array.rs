@@ -0,0 +1,543 @@
+use std::{
+    collections::BTreeMap,
+    io::{
+        self,
+        Read,
+        Write,
+    },
+    marker::PhantomData,
+};
+
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// An element that can be stored within a [`DoubleArray`].
+///
+/// Each element is encoded as a short run of bytes, which the double array
+/// follows one byte at a time.
+pub trait Symbol: Copy {
+    /// Encodes the element into `buffer`, returning the bytes written.
+    fn encode(self, buffer: &mut [u8; 4]) -> &[u8];
+
+    /// Decodes an element from the start of `bytes`, returning it and the
+    /// number of bytes read.
+    ///
+    /// Returns [`None`] if `bytes` doesn't start with a valid encoding.
+    fn decode(bytes: &[u8]) -> Option<(Self, usize)>;
+}
+
+impl Symbol for u8 {
+    fn encode(self, buffer: &mut [u8; 4]) -> &[u8] {
+        buffer[0] = self;
+        &buffer[..1]
+    }
+
+    fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        Some((*bytes.first()?, 1))
+    }
+}
+
+impl Symbol for u16 {
+    fn encode(self, buffer: &mut [u8; 4]) -> &[u8] {
+        buffer[..2].copy_from_slice(&self.to_be_bytes());
+        &buffer[..2]
+    }
+
+    fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        let bytes = bytes.get(..2)?;
+        Some((Self::from_be_bytes(bytes.try_into().unwrap()), 2))
+    }
+}
+
+impl Symbol for u32 {
+    fn encode(self, buffer: &mut [u8; 4]) -> &[u8] {
+        *buffer = self.to_be_bytes();
+        &buffer[..]
+    }
+
+    fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        let bytes = bytes.get(..4)?;
+        Some((Self::from_be_bytes(bytes.try_into().unwrap()), 4))
+    }
+}
+
+impl Symbol for char {
+    fn encode(self, buffer: &mut [u8; 4]) -> &[u8] {
+        self.encode_utf8(buffer).as_bytes()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        // The first byte gives the length of the encoding:
+        let length = match bytes.first()? {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return None,
+        };
+        let decoded = std::str::from_utf8(bytes.get(..length)?).ok()?;
+        Some((decoded.chars().next()?, length))
+    }
+}
+
+/// Marks a slot of the double array that isn't used by any state.
+const FREE: u32 = u32::MAX;
+
+/// The code of the transition that marks the end of a key. Every byte `b` is
+/// given the code `b + 1`.
+const END: u32 = 0;
+
+/// Identifies a serialized [`DoubleArray`].
+const MAGIC: &[u8; 4] = b"FTDA";
+
+/// The version of the serialized format.
+const VERSION: u32 = 1;
+
+/// A read-only trie stored in two flat arrays, `base` and `check`.
+///
+/// Every state of the trie is an index into both arrays. The transition from
+/// state `s` on code `c` leads to state `t = base[s] + c`, which exists only if
+/// `check[t] == s`. Each step down the trie is therefore two array reads,
+/// rather than a hash table lookup in a separately allocated node.
+///
+/// Keys are stored as the bytes produced by their [`Symbol`]s. A
+/// [`DoubleArray`] is built with [`Trie::double_array`](crate::trie::Trie::double_array)
+/// and can be written to and read back from a compact binary form.
+pub struct DoubleArray<T> {
+    base: Vec<u32>,
+    check: Vec<u32>,
+    /// The number of keys within the double array.
+    count: usize,
+    marker: PhantomData<T>,
+}
+
+/// A node of the byte-level trie a [`DoubleArray`] is built from.
+#[derive(Default)]
+pub(super) struct ByteNode {
+    /// The index of the node reached by each byte.
+    pub(super) children: BTreeMap<u8, usize>,
+    /// Set if this node is the end of a key.
+    pub(super) end: bool,
+}
+
+impl ByteNode {
+    /// Expands every key at or below `root` into a trie of bytes, returning
+    /// its nodes and the number of keys. The root is the first node.
+    pub(super) fn expand<T, V, H>(root: &TrieNode<T, H, V>) -> (Vec<Self>, usize)
+    where
+        T: Symbol,
+        H: ChildStorage<T>,
+    {
+        // Each stack entry holds an element, its node and the byte node of its
+        // parent:
+        let mut nodes = vec![Self::default()];
+        nodes[0].end = root.value.is_some();
+        let mut count = usize::from(nodes[0].end);
+        let mut stack: Vec<_> = root
+            .children
+            .iter()
+            .map(|(element, child)| (*element, child, 0))
+            .collect();
+        while let Some((element, node, parent)) = stack.pop() {
+            let mut buffer = [0; 4];
+            let mut current = parent;
+            for byte in element.encode(&mut buffer) {
+                current = match nodes[current].children.get(byte) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(Self::default());
+                        let next = nodes.len() - 1;
+                        nodes[current].children.insert(*byte, next);
+                        next
+                    },
+                };
+            }
+            if node.value.is_some() {
+                nodes[current].end = true;
+                count += 1;
+            }
+            for (element, child) in node.children.iter() {
+                stack.push((*element, child, current));
+            }
+        }
+        (nodes, count)
+    }
+}
+
+impl<T> DoubleArray<T>
+where
+    T: Symbol,
+{
+    /// Builds a [`DoubleArray`] containing every key at or below `root`.
+    pub fn new<V, H>(root: &TrieNode<T, H, V>) -> Self
+    where
+        H: ChildStorage<T>,
+    {
+        let (nodes, count) = ByteNode::expand(root);
+        let mut array = Self {
+            base: vec![0],
+            check: vec![FREE],
+            count,
+            marker: PhantomData,
+        };
+        array.place(&nodes);
+        array
+    }
+
+    /// Places every byte node into the arrays, starting with the root at
+    /// state `0`.
+    fn place(&mut self, nodes: &[ByteNode]) {
+        // The first slot that might be free. Every slot before it is in use:
+        let mut first_free = 1;
+        let mut queue = vec![(0, 0)];
+        while let Some((node, state)) = queue.pop() {
+            let node = &nodes[node];
+            let codes: Vec<u32> = node
+                .end
+                .then_some(END)
+                .into_iter()
+                .chain(node.children.keys().map(|byte| u32::from(*byte) + 1))
+                .collect();
+            let Some(&first) = codes.first() else {
+                continue;
+            };
+
+            while self.is_used(first_free) {
+                first_free += 1;
+            }
+            // Find the lowest base where every code lands on a free slot. A
+            // base of `0` is never used, so no transition leads back to the
+            // root:
+            let mut slot = first_free.max(first + 1);
+            let base = loop {
+                let base = slot - first;
+                if codes.iter().all(|code| !self.is_used(base + code)) {
+                    break base;
+                }
+                slot += 1;
+                while self.is_used(slot) {
+                    slot += 1;
+                }
+            };
+
+            self.base[state as usize] = base;
+            for code in &codes {
+                self.reserve(base + code);
+                self.check[(base + code) as usize] = state;
+            }
+            for (byte, child) in &node.children {
+                queue.push((*child, base + u32::from(*byte) + 1));
+            }
+        }
+    }
+
+    /// Returns `true` if `slot` is used by a state.
+    fn is_used(&self, slot: u32) -> bool {
+        self.check
+            .get(slot as usize)
+            .is_some_and(|check| *check != FREE)
+    }
+
+    /// Grows the arrays so that `slot` is within them.
+    fn reserve(&mut self, slot: u32) {
+        let length = slot as usize + 1;
+        if self.check.len() < length {
+            self.base.resize(length, 0);
+            self.check.resize(length, FREE);
+        }
+    }
+}
+
+impl<T> DoubleArray<T> {
+    /// Returns the state reached by reading `code` in `state`.
+    #[inline]
+    fn transition(&self, state: u32, code: u32) -> Option<u32> {
+        let next = self.base[state as usize].checked_add(code)?;
+        match self.check.get(next as usize) {
+            Some(check) if *check == state => Some(next),
+            _ => None,
+        }
+    }
+
+    /// Follows `bytes` from `state`.
+    fn walk_bytes(&self, mut state: u32, bytes: impl IntoIterator<Item = u8>) -> Option<u32> {
+        for byte in bytes {
+            state = self.transition(state, u32::from(byte) + 1)?;
+        }
+        Some(state)
+    }
+
+    /// Returns `true` if `state` is the end of a key.
+    fn is_end(&self, state: u32) -> bool {
+        self.transition(state, END).is_some()
+    }
+
+    /// Returns `true` if any key is at or below `state`.
+    ///
+    /// Every state other than the root is on the way to a key, so only the
+    /// root of an empty double array has no keys below it.
+    fn has_keys(&self, state: u32) -> bool {
+        state != 0 || !self.is_empty()
+    }
+
+    /// Returns `true` if the double array contains the key made of `bytes`.
+    #[cfg(feature = "string")]
+    pub(super) fn contains_bytes(&self, bytes: impl IntoIterator<Item = u8>) -> bool {
+        self.walk_bytes(0, bytes)
+            .is_some_and(|state| self.is_end(state))
+    }
+
+    /// Returns `true` if any key starts with `bytes`.
+    #[cfg(feature = "string")]
+    pub(super) fn starts_with_bytes(&self, bytes: impl IntoIterator<Item = u8>) -> bool {
+        self.walk_bytes(0, bytes)
+            .is_some_and(|state| self.has_keys(state))
+    }
+
+    /// Returns `true` if the double array is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of values within the double array.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the number of slots in each of the `base` and `check` arrays.
+    pub fn slots(&self) -> usize {
+        self.check.len()
+    }
+
+    /// Writes the double array to `writer` in a compact binary form, which can
+    /// be read back with [`DoubleArray::read_from`].
+    ///
+    /// The form is a magic number, a format version, the number of keys and
+    /// slots, then both arrays. Every number is little-endian.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.count as u64).to_le_bytes())?;
+        writer.write_all(&(self.check.len() as u64).to_le_bytes())?;
+        for value in self.base.iter().chain(&self.check) {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a double array written by [`DoubleArray::write_to`].
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if the
+    /// input isn't a double array or refers to slots outside the arrays.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a serialized double array"));
+        }
+        let mut word = [0; 4];
+        reader.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != VERSION {
+            return Err(invalid("unsupported double array version"));
+        }
+        let mut long = [0; 8];
+        reader.read_exact(&mut long)?;
+        let count = usize::try_from(u64::from_le_bytes(long))
+            .map_err(|_| invalid("key count is too large"))?;
+        reader.read_exact(&mut long)?;
+        let slots = u32::try_from(u64::from_le_bytes(long))
+            .map_err(|_| invalid("too many slots"))?;
+        if slots == 0 {
+            return Err(invalid("missing root state"));
+        }
+
+        // The arrays are read without trusting the number of slots, so a
+        // corrupt header can't cause a huge allocation:
+        let mut read_array = || -> io::Result<Vec<u32>> {
+            let length = u64::from(slots) * 4;
+            let mut bytes = Vec::new();
+            reader.by_ref().take(length).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 != length {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect())
+        };
+        let base = read_array()?;
+        let check = read_array()?;
+        if check.iter().any(|check| *check != FREE && *check >= slots) {
+            return Err(invalid("check refers to a slot outside the array"));
+        }
+
+        Ok(Self {
+            base,
+            check,
+            count,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T> DoubleArray<T>
+where
+    T: Symbol,
+{
+    /// Follows `iter` from the root.
+    fn walk(&self, iter: impl IntoIterator<Item = T>) -> Option<u32> {
+        let mut state = 0;
+        let mut buffer = [0; 4];
+        for element in iter {
+            let bytes = element.encode(&mut buffer).iter().copied();
+            state = self.walk_bytes(state, bytes)?;
+        }
+        Some(state)
+    }
+
+    /// Returns `true` if the [`DoubleArray`] contains a value, otherwise
+    /// returns `false`.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.walk(iter)
+            .is_some_and(|state| self.is_end(state))
+    }
+
+    /// Returns `true` if any value within the [`DoubleArray`] starts with
+    /// `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.walk(prefix)
+            .is_some_and(|state| self.has_keys(state))
+    }
+
+    /// Returns an iterator over the length of every value within the
+    /// [`DoubleArray`] that is a prefix of `input`, from shortest to longest.
+    pub fn prefixes_of<'a, I>(&'a self, input: I) -> impl Iterator<Item = usize> + 'a
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+    {
+        let mut input = input.into_iter();
+        let mut state = Some(0);
+        let mut length = 0;
+        std::iter::from_fn(move || loop {
+            let current = state?;
+            let found = self.is_end(current).then_some(length);
+
+            // Step down the trie before yielding, so the iterator is already
+            // positioned for the next call:
+            let mut buffer = [0; 4];
+            state = input.next().and_then(|element| {
+                let bytes = element.encode(&mut buffer).iter().copied();
+                self.walk_bytes(current, bytes)
+            });
+            length += 1;
+
+            if found.is_some() {
+                return found;
+            }
+        })
+    }
+
+    /// Returns the length of the longest value within the [`DoubleArray`]
+    /// that is a prefix of `input`.
+    pub fn longest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<usize> {
+        self.prefixes_of(input).last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    use super::*;
+
+    #[test]
+    fn test_lookups() {
+        let keys = ["", "a", "ab", "abc", "b", "banana", "band", "bandana", "日本", "日本語"];
+        let mut trie = Trie::<char>::new();
+        for key in keys {
+            assert!(trie.insert(key.chars()));
+        }
+        let array = trie.double_array();
+        assert_eq!(array.len(), keys.len());
+
+        for key in keys {
+            assert!(array.contains(key.chars()), "{key}");
+        }
+        for key in ["ba", "bandanas", "c", "日", "語"] {
+            assert!(!array.contains(key.chars()), "{key}");
+        }
+        assert!(array.starts_with("band".chars()));
+        assert!(array.starts_with("日".chars()));
+        assert!(!array.starts_with("x".chars()));
+        assert!(array.starts_with("".chars()));
+        assert_eq!(array.prefixes_of("bandanas".chars()).collect::<Vec<_>>(), [0, 1, 4, 7]);
+        assert_eq!(array.longest_prefix_of("日本語です".chars()), Some(3));
+    }
+
+    #[test]
+    fn test_empty() {
+        let array = Trie::<char>::new().double_array();
+        assert!(array.is_empty());
+        assert!(!array.contains("".chars()));
+        assert!(!array.starts_with("".chars()));
+    }
+
+    #[test]
+    fn test_symbols() {
+        let mut trie = Trie::<u16>::new();
+        assert!(trie.insert([1, 258, 3]));
+        assert!(trie.insert([1, 2]));
+        let array = trie.double_array();
+
+        assert!(array.contains([1, 258, 3]));
+        assert!(array.contains([1, 2]));
+        assert!(!array.contains([1]));
+        assert!(!array.contains([1, 258]));
+        assert!(!array.contains([1, 259, 3]));
+    }
+
+    #[test]
+    fn test_write_read() {
+        let mut trie = Trie::<u8>::new();
+        for key in [&b"hello"[..], b"help", b"world", b"\x00\xff"] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+        let array = trie.double_array();
+
+        let mut bytes = Vec::new();
+        array.write_to(&mut bytes).unwrap();
+        let loaded = DoubleArray::<u8>::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(loaded.slots(), array.slots());
+        for key in [&b"hello"[..], b"help", b"world", b"\x00\xff"] {
+            assert!(loaded.contains(key.iter().copied()));
+        }
+        assert!(!loaded.contains(b"hel".iter().copied()));
+
+        // Corrupt input is rejected:
+        let error = DoubleArray::<u8>::read_from(&b"nope"[..]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = DoubleArray::<u8>::read_from(&bytes[..bytes.len() - 1]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 4;
+        corrupt[last..].copy_from_slice(&(array.slots() as u32).to_le_bytes());
+        let error = DoubleArray::<u8>::read_from(corrupt.as_slice()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // A header claiming a huge number of slots fails without allocating
+        // them:
+        let mut huge = bytes[..16].to_vec();
+        huge.extend_from_slice(&u64::from(u32::MAX).to_le_bytes());
+        let error = DoubleArray::<u8>::read_from(huge.as_slice()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
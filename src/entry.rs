@@ -0,0 +1,359 @@
+use std::marker::PhantomData;
+
+use crate::{
+    children::ChildStorage,
+    node::{
+        NodePath,
+        TrieNode,
+    },
+};
+
+/// A view into a single key within a trie, which may either be occupied or
+/// vacant.
+///
+/// This is created by the `entry` function on each trie type. The path to the
+/// key is only walked once when the entry is created; every operation on the
+/// entry then works directly on the nodes that were found.
+pub enum Entry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The key exists within the trie.
+    Occupied(OccupiedEntry<'a, T, V, H>),
+    /// The key does not exist within the trie.
+    Vacant(VacantEntry<'a, T, V, H>),
+}
+
+/// A view into an occupied key within a trie.
+pub struct OccupiedEntry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The key this entry points to.
+    key: Vec<T>,
+    /// Every node on the path from the root to the end of the key. The first
+    /// node is the root, the last node stores the value.
+    path: NodePath<T, V, H>,
+    /// The number of values within the trie.
+    count: &'a mut usize,
+    marker: PhantomData<&'a mut TrieNode<T, H, V>>,
+}
+
+/// A view into a vacant key within a trie.
+pub struct VacantEntry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The key this entry points to.
+    key: Vec<T>,
+    /// Every node on the path from the root to the deepest existing node for
+    /// the key. The nodes for the rest of the key are created on insertion.
+    path: NodePath<T, V, H>,
+    /// The number of values within the trie.
+    count: &'a mut usize,
+    marker: PhantomData<&'a mut TrieNode<T, H, V>>,
+}
+
+impl<'a, T, V, H> Entry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`Entry`] by walking from `root` along `iter`.
+    pub(super) fn new(
+        root: &'a mut TrieNode<T, H, V>,
+        count: &'a mut usize,
+        iter: impl IntoIterator<Item = T>,
+    ) -> Self {
+        let key: Vec<T> = iter.into_iter().collect();
+
+        // Walk down the trie as far as the existing nodes allow. The path
+        // stems from the mutable borrow of `root` held by this entry:
+        let path = NodePath::walk(root, &key);
+
+        // SAFETY: The last node on the path is live and not otherwise
+        // borrowed.
+        let is_occupied = path.len() == key.len() + 1
+            && unsafe { path.last().as_ref() }.value.is_some();
+        if is_occupied {
+            Entry::Occupied(OccupiedEntry {
+                key,
+                path,
+                count,
+                marker: PhantomData,
+            })
+        } else {
+            Entry::Vacant(VacantEntry {
+                key,
+                path,
+                count,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the key this entry points to.
+    pub fn key(&self) -> &[T] {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if it is
+    /// vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default`
+    /// if it is vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if it
+    /// is vacant, and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` with a mutable reference to the value if the entry is
+    /// occupied.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T, V, H> OccupiedEntry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Returns the key this entry points to.
+    pub fn key(&self) -> &[T] {
+        &self.key
+    }
+
+    /// Returns the node at the end of the key.
+    fn node(&self) -> &TrieNode<T, H, V> {
+        // SAFETY: The path always ends at a live node and this entry holds the
+        // only borrow of the trie.
+        unsafe { self.path.last().as_ref() }
+    }
+
+    /// Returns the node at the end of the key.
+    fn node_mut(&mut self) -> &mut TrieNode<T, H, V> {
+        // SAFETY: As above; `&mut self` guarantees exclusive access.
+        unsafe { self.path.last().as_mut() }
+    }
+
+    /// Returns an immutable reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        self.node().value.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.node_mut().value.as_mut().unwrap()
+    }
+
+    /// Converts the entry into a mutable reference to its value, bound to the
+    /// lifetime of the trie.
+    pub fn into_mut(self) -> &'a mut V {
+        // SAFETY: The entry is consumed, so the returned reference is the only
+        // remaining borrow of the trie for `'a`.
+        let mut node = self.path.last();
+        unsafe { node.as_mut() }.value.as_mut().unwrap()
+    }
+
+    /// Replaces the value in the entry, returning the previous value.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the key from the trie, returning its value.
+    ///
+    /// Any nodes that no longer lead to a value are removed from the trie.
+    pub fn remove(mut self) -> V {
+        let value = self.node_mut().value.take().unwrap();
+        *self.count -= 1;
+
+        // SAFETY: The entry is consumed and holds the only borrow of the
+        // trie, so no other reference to any node on the path is live.
+        unsafe {
+            // Every node along the path loses a value:
+            #[cfg(feature = "counts")]
+            self.path.update(self.path.len(), |node| node.count -= 1);
+
+            // Prune the nodes that no longer lead to a value:
+            self.path.prune(&self.key);
+        }
+        value
+    }
+}
+
+impl<'a, T, V, H> VacantEntry<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Returns the key this entry points to.
+    pub fn key(&self) -> &[T] {
+        &self.key
+    }
+
+    /// Inserts a value into the entry, returning a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let VacantEntry { key, mut path, count, .. } = self;
+        *count += 1;
+
+        // SAFETY: The entry is consumed and holds the only borrow of the
+        // trie, so the node at the end of the path is the only remaining
+        // borrow for `'a`.
+        unsafe {
+            // Create the remaining nodes below the deepest existing node:
+            let depth = path.len() - 1;
+            path.extend(key.into_iter().skip(depth));
+
+            // Every node along the path gains a value:
+            #[cfg(feature = "counts")]
+            path.update(path.len(), |node| node.count += 1);
+
+            let mut current = path.last();
+            let current = current.as_mut();
+            debug_assert!(current.value.is_none());
+            current.value.insert(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::TrieMap;
+
+    use super::*;
+
+    type CharTrieMap = TrieMap<char, usize>;
+
+    #[test]
+    fn test_or_insert() {
+        let mut map = CharTrieMap::new();
+        *map.entry("test".chars()).or_insert(0) += 1;
+        *map.entry("test".chars()).or_insert(0) += 1;
+        *map.entry("tester".chars()).or_insert_with(|| 10) += 1;
+        *map.entry("te".chars()).or_default() += 5;
+
+        assert_eq!(map.get("test".chars()), Some(&2));
+        assert_eq!(map.get("tester".chars()), Some(&11));
+        assert_eq!(map.get("te".chars()), Some(&5));
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn test_and_modify() {
+        let mut map = CharTrieMap::new();
+        map.entry("test".chars()).and_modify(|value| *value += 1).or_insert(1);
+        map.entry("test".chars()).and_modify(|value| *value += 1).or_insert(1);
+        assert_eq!(map.get("test".chars()), Some(&2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_vacant_and_occupied() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("test".chars(), 1), None);
+
+        match map.entry("testing".chars()) {
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), &['t', 'e', 's', 't', 'i', 'n', 'g']);
+                assert_eq!(*entry.insert(2), 2);
+            },
+            Entry::Occupied(..) => panic!("expected a vacant entry"),
+        }
+
+        match map.entry("test".chars()) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(*entry.get(), 1);
+                assert_eq!(entry.insert(3), 1);
+            },
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+
+        // A node on the path of another key is not an occupied entry:
+        assert!(matches!(map.entry("tes".chars()), Entry::Vacant(..)));
+
+        assert_eq!(map.get("test".chars()), Some(&3));
+        assert_eq!(map.get("testing".chars()), Some(&2));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_dropped_vacant_entry() {
+        let mut map = CharTrieMap::new();
+        assert!(matches!(map.entry("test".chars()), Entry::Vacant(..)));
+
+        // Dropping a vacant entry must not create any nodes:
+        assert!(map.is_empty());
+        assert!(map.root().is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("test".chars(), 1), None);
+        assert_eq!(map.insert("testing".chars(), 2), None);
+        assert_eq!(map.insert("tea".chars(), 3), None);
+
+        match map.entry("testing".chars()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+        assert!(!map.contains_key("testing".chars()));
+        assert!(map.root().walk("testi".chars()).is_none());
+        assert_eq!(map.len(), 2);
+
+        match map.entry("test".chars()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 1),
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+        assert!(map.root().walk("tes".chars()).is_none());
+        assert!(map.contains_key("tea".chars()));
+        assert_eq!(map.len(), 1);
+
+        match map.entry("tea".chars()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 3),
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+        assert!(map.is_empty());
+        assert!(map.root().is_empty());
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn test_counts() {
+        let mut map = CharTrieMap::new();
+        *map.entry("test".chars()).or_insert(0) += 1;
+        *map.entry("testing".chars()).or_insert(0) += 1;
+        *map.entry("test".chars()).or_insert(0) += 1;
+        assert_eq!(map.count_with_prefix("".chars()), 2);
+        assert_eq!(map.count_with_prefix("test".chars()), 2);
+        assert_eq!(map.count_with_prefix("testi".chars()), 1);
+
+        match map.entry("test".chars()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(map.count_with_prefix("".chars()), 1);
+        assert_eq!(map.count_with_prefix("test".chars()), 1);
+    }
+}
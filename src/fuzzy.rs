@@ -0,0 +1,295 @@
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// The edit distance table between a query and the key currently visited by
+/// a search.
+///
+/// The table keeps one row for each depth of the current key. Rows are shared
+/// by every key with the same prefix, so moving to a sibling only replaces the
+/// rows below their common parent.
+pub(super) struct EditRows<T> {
+    buffer: Vec<T>,
+    query: Vec<T>,
+    max_distance: usize,
+    transpositions: bool,
+    /// One row of `query.len() + 1` distances for each element in `buffer`,
+    /// plus the row for the empty key.
+    rows: Vec<usize>,
+}
+
+impl<T> EditRows<T>
+where
+    T: Eq + Copy,
+{
+    /// Creates the table for the empty key.
+    pub(super) fn new(query: Vec<T>, max_distance: usize, transpositions: bool) -> Self {
+        // The empty key is `j` edits away from the first `j` query elements:
+        let rows = (0..=query.len()).collect();
+        Self {
+            buffer: Vec::new(),
+            query,
+            max_distance,
+            transpositions,
+            rows,
+        }
+    }
+
+    /// Returns the width of each row.
+    fn width(&self) -> usize {
+        self.query.len() + 1
+    }
+
+    /// Returns the current key.
+    pub(super) fn key(&self) -> &[T] {
+        &self.buffer
+    }
+
+    /// Returns the distance between the current key and the whole query, if
+    /// it is within the maximum distance.
+    pub(super) fn distance(&self) -> Option<usize> {
+        let distance = *self.rows.last().unwrap();
+        (distance <= self.max_distance).then_some(distance)
+    }
+
+    /// Returns `true` if no key that extends the current key can be within the
+    /// maximum distance.
+    ///
+    /// A row can never contain a smaller distance than the row above it, so
+    /// once every distance in a row is too large the whole subtree can be
+    /// skipped.
+    pub(super) fn is_exhausted(&self) -> bool {
+        let start = self.rows.len() - self.width();
+        self.rows[start..].iter().all(|&distance| distance > self.max_distance)
+    }
+
+    /// Moves to the key formed by the first `depth` elements of the current
+    /// key followed by `element`.
+    pub(super) fn push(&mut self, depth: usize, element: T) {
+        // If we're backtracking, drop the rows below the current depth:
+        self.buffer.truncate(depth);
+        self.buffer.push(element);
+        self.rows.truncate((depth + 1) * self.width());
+        self.push_row();
+    }
+
+    /// Appends the row for the last element of `buffer`.
+    fn push_row(&mut self) {
+        let width = self.width();
+        let depth = self.buffer.len();
+        let element = self.buffer[depth - 1];
+        let previous = (depth - 1) * width;
+
+        self.rows.push(depth);
+        for j in 1..width {
+            let cost = usize::from(self.query[j - 1] != element);
+            let mut distance = (self.rows[previous + j] + 1)
+                .min(self.rows[previous + width + j - 1] + 1)
+                .min(self.rows[previous + j - 1] + cost);
+
+            // Two adjacent elements swapped places:
+            if self.transpositions
+                && depth > 1
+                && j > 1
+                && self.query[j - 1] == self.buffer[depth - 2]
+                && self.query[j - 2] == element
+            {
+                distance = distance.min(self.rows[previous - width + j - 2] + 1);
+            }
+            self.rows.push(distance);
+        }
+    }
+}
+
+/// Iterates over every key below a [`TrieNode`] within a maximum edit distance
+/// of a query.
+///
+/// The distance is the Levenshtein distance: the number of single element
+/// insertions, deletions and substitutions needed to turn one key into the
+/// other. Iterators created with [`FuzzySearch::with_transpositions`] also
+/// count swapping two adjacent elements as a single edit, which is the
+/// (restricted) Damerau-Levenshtein distance.
+///
+/// The search keeps one row of the edit distance table for each depth of the
+/// current key. Rows are shared by every key with the same prefix, and a
+/// subtree is skipped entirely once every entry in its row exceeds the maximum
+/// distance. Keys are yielded in an arbitrary order.
+pub struct FuzzySearch<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the search starts from. This is visited on the first call to
+    /// `next`, since it may store a value of its own.
+    start: Option<&'a TrieNode<T, H, V>>,
+    stack: Vec<(T, &'a TrieNode<T, H, V>, usize)>,
+    rows: EditRows<T>,
+}
+
+impl<'a, T, V, H> FuzzySearch<'a, T, V, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`FuzzySearch`] over a root [`TrieNode`], using the
+    /// Levenshtein distance.
+    pub fn new(root: &'a TrieNode<T, H, V>, query: Vec<T>, max_distance: usize) -> Self {
+        Self::create(root, query, max_distance, false)
+    }
+
+    /// Creates a new [`FuzzySearch`] over a root [`TrieNode`] that also counts
+    /// adjacent transpositions as a single edit.
+    pub fn with_transpositions(root: &'a TrieNode<T, H, V>, query: Vec<T>, max_distance: usize) -> Self {
+        Self::create(root, query, max_distance, true)
+    }
+
+    fn create(
+        root: &'a TrieNode<T, H, V>,
+        query: Vec<T>,
+        max_distance: usize,
+        transpositions: bool,
+    ) -> Self {
+        Self {
+            start: Some(root),
+            stack: Vec::with_capacity(root.children.len()),
+            rows: EditRows::new(query, max_distance, transpositions),
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack, unless none of them can
+    /// be within the maximum distance.
+    fn push_children(&mut self, node: &'a TrieNode<T, H, V>, depth: usize) {
+        if self.rows.is_exhausted() {
+            return;
+        }
+        for (key, child) in node.children.iter() {
+            self.stack.push((*key, child, depth));
+        }
+    }
+}
+
+impl<'a, T, V, H> Iterator for FuzzySearch<'a, T, V, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    type Item = (Vec<T>, &'a V, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The empty key is stored on the root itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, 0);
+            if let (Some(value), Some(distance)) = (&start.value, self.rows.distance()) {
+                return Some((Vec::new(), value, distance));
+            }
+        }
+
+        while let Some((key, node, depth)) = self.stack.pop() {
+            self.rows.push(depth, key);
+            self.push_children(node, depth + 1);
+
+            if let (Some(value), Some(distance)) = (&node.value, self.rows.distance()) {
+                return Some((self.rows.key().to_vec(), value, distance));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::TrieMap;
+
+    use super::*;
+
+    fn search(map: &TrieMap<char, usize>, query: &str, max_distance: usize, transpositions: bool) -> Vec<(String, usize)> {
+        let query = query.chars().collect();
+        let search = match transpositions {
+            false => FuzzySearch::new(map.root(), query, max_distance),
+            true => FuzzySearch::with_transpositions(map.root(), query, max_distance),
+        };
+        let mut found: Vec<(String, usize)> = search
+            .map(|(key, _, distance)| (String::from_iter(key), distance))
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        let map = TrieMap::from_iter(["test", "tests", "text", "tent", "toast", "best", "taste", "t", ""].map(str::chars).into_iter().zip(0..));
+
+        assert_eq!(search(&map, "test", 0, false), [("test".to_string(), 0)]);
+        assert_eq!(search(&map, "test", 1, false), [
+            ("best".to_string(), 1),
+            ("tent".to_string(), 1),
+            ("test".to_string(), 0),
+            ("tests".to_string(), 1),
+            ("text".to_string(), 1),
+        ]);
+        assert_eq!(search(&map, "test", 2, false).len(), 7);
+        assert_eq!(search(&map, "", 1, false), [
+            (String::new(), 0),
+            ("t".to_string(), 1),
+        ]);
+        assert!(search(&map, "zzzzzzzz", 2, false).is_empty());
+    }
+
+    #[test]
+    fn test_transpositions() {
+        let map = TrieMap::from_iter(["test", "tset", "etst", "tets"].map(str::chars).into_iter().zip(0..));
+
+        // Each swap is two edits without transpositions:
+        assert_eq!(search(&map, "test", 1, false), [("test".to_string(), 0)]);
+        assert_eq!(search(&map, "test", 1, true), [
+            ("etst".to_string(), 1),
+            ("test".to_string(), 0),
+            ("tets".to_string(), 1),
+            ("tset".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_matches_full_table() {
+        // Compare against a plain edit distance table for every key:
+        fn distance(a: &[char], b: &[char]) -> usize {
+            let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+            for (i, row) in table.iter_mut().enumerate() {
+                row[0] = i;
+            }
+            for (j, distance) in table[0].iter_mut().enumerate() {
+                *distance = j;
+            }
+            for i in 1..=a.len() {
+                for j in 1..=b.len() {
+                    let cost = usize::from(a[i - 1] != b[j - 1]);
+                    table[i][j] = (table[i - 1][j] + 1)
+                        .min(table[i][j - 1] + 1)
+                        .min(table[i - 1][j - 1] + cost);
+                }
+            }
+            table[a.len()][b.len()]
+        }
+
+        let keys = ["kitten", "sitting", "mitten", "kit", "sit", "knitting", "written", "smitten"];
+        let map = TrieMap::from_iter(keys.map(str::chars).into_iter().zip(0..));
+        for query in ["kitten", "sitten", "kiten", "mittens", "x"] {
+            let query_chars: Vec<char> = query.chars().collect();
+            for max_distance in 0..4 {
+                let mut expected: Vec<(String, usize)> = keys
+                    .iter()
+                    .map(|key| {
+                        let key_chars: Vec<char> = key.chars().collect();
+                        (key.to_string(), distance(&key_chars, &query_chars))
+                    })
+                    .filter(|(_, distance)| *distance <= max_distance)
+                    .collect();
+                expected.sort();
+                assert_eq!(search(&map, query, max_distance, false), expected);
+            }
+        }
+    }
+}
@@ -1,50 +1,236 @@
-use crate::node::TrieNode;
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+};
 
-/// Iterates over a [`TrieNode`].
-pub struct TrieIter<'a, T, H> {
-    stack: Vec<(T, &'a TrieNode<T, H>, usize)>,
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// Iterates over every key and value below a [`TrieNode`].
+///
+/// Iterators created with [`TrieMapIter::sorted`], which back `iter()` on
+/// each trie, sort the children of each node as they are visited and so yield
+/// keys in lexicographic order. Iterators created with [`TrieMapIter::new`],
+/// which back the `*_unordered` methods, visit children in the order their
+/// [`Children`] container yields them, which is arbitrary for the default
+/// hash map storage.
+pub struct TrieMapIter<'a, T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may store a value of its own.
+    start: Option<&'a TrieNode<T, H, V>>,
+    stack: Vec<(T, &'a TrieNode<T, H, V>, usize)>,
     buffer: Vec<T>,
+    /// Used to sort the children of each node before they are visited.
+    order: Option<fn(&T, &T) -> Ordering>,
 }
 
-impl<'a, T, H> TrieIter<'a, T, H>
+impl<'a, T, V, H> TrieMapIter<'a, T, V, H>
 where
     T: Copy,
+    H: ChildStorage<T>,
 {
-    /// Creates a new [`TrieIter`] over a root [`TrieNode`].
-    pub fn new(root: &'a TrieNode<T, H>) -> Self {
-        let mut stack = Vec::with_capacity(
-            root.children.len()
-        );
-        for (key, child) in &root.children {
-            stack.push((*key, child, 0));
-        }
+    /// Creates a new [`TrieMapIter`] over a root [`TrieNode`].
+    pub fn new(root: &'a TrieNode<T, H, V>) -> Self {
+        Self::with_prefix(Some(root), Vec::new())
+    }
+
+    /// Creates a new [`TrieMapIter`] over the [`TrieNode`] found at the end of
+    /// `prefix`. Every key yielded by the iterator starts with `prefix`.
+    ///
+    /// If `node` is [`None`], the iterator is empty.
+    pub fn with_prefix(node: Option<&'a TrieNode<T, H, V>>, prefix: Vec<T>) -> Self {
+        Self::create(node, prefix, None)
+    }
+
+    fn create(
+        node: Option<&'a TrieNode<T, H, V>>,
+        prefix: Vec<T>,
+        order: Option<fn(&T, &T) -> Ordering>,
+    ) -> Self {
         Self {
-            stack,
-            buffer: Vec::new(),
+            start: node,
+            stack: Vec::with_capacity(
+                node.map_or(0, |node| node.children.len())
+            ),
+            buffer: prefix,
+            order,
         }
     }
+
+    /// Pushes the children of `node` onto the stack.
+    ///
+    /// The children are pushed in reverse order, so that the first child is
+    /// popped first.
+    fn push_children(&mut self, node: &'a TrieNode<T, H, V>, depth: usize) {
+        let start = self.stack.len();
+        for (key, child) in node.children.iter() {
+            self.stack.push((*key, child, depth));
+        }
+        match self.order {
+            Some(order) => self.stack[start..].sort_unstable_by(|(a, ..), (b, ..)| order(b, a)),
+            None => self.stack[start..].reverse(),
+        }
+    }
+}
+
+impl<'a, T, V, H> TrieMapIter<'a, T, V, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`TrieMapIter`] that yields every key starting with
+    /// `prefix` in lexicographic order.
+    ///
+    /// If `node` is [`None`], the iterator is empty.
+    pub fn sorted(node: Option<&'a TrieNode<T, H, V>>, prefix: Vec<T>) -> Self {
+        Self::create(node, prefix, Some(T::cmp))
+    }
 }
 
-impl<T, H> Iterator for TrieIter<'_, T, H>
+impl<'a, T, V, H> Iterator for TrieMapIter<'a, T, V, H>
 where
     T: Eq + Copy,
+    H: ChildStorage<T>,
 {
-    type Item = Vec<T>;
+    type Item = (Vec<T>, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, self.buffer.len());
+            if let Some(value) = &start.value {
+                return Some((self.buffer.clone(), value));
+            }
+        }
+
         while let Some((key, node, depth)) = self.stack.pop() {
             // If we're backtracking, truncate the prefix to the current depth:
             self.buffer.truncate(depth);
             self.buffer.push(key);
 
-            if node.end_of_value {
-                return Some(self.buffer.clone());
-            }
+            // Children are pushed before yielding, so keys that extend this
+            // one are still visited:
+            self.push_children(node, depth + 1);
 
-            for (key, child) in &node.children {
-                self.stack.push((*key, child, depth + 1));
+            if let Some(value) = &node.value {
+                return Some((self.buffer.clone(), value));
             }
         }
         None
     }
 }
+
+/// Iterates over every key below a [`TrieNode`].
+pub struct TrieIter<'a, T, H, V = ()>(TrieMapIter<'a, T, V, H>)
+where
+    H: ChildStorage<T>;
+
+impl<'a, T, V, H> TrieIter<'a, T, H, V>
+where
+    T: Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`TrieIter`] over a root [`TrieNode`].
+    pub fn new(root: &'a TrieNode<T, H, V>) -> Self {
+        Self(TrieMapIter::new(root))
+    }
+
+    /// Creates a new [`TrieIter`] over the keys starting with `prefix`.
+    ///
+    /// See [`TrieMapIter::with_prefix`].
+    pub fn with_prefix(node: Option<&'a TrieNode<T, H, V>>, prefix: Vec<T>) -> Self {
+        Self(TrieMapIter::with_prefix(node, prefix))
+    }
+}
+
+impl<'a, T, V, H> TrieIter<'a, T, H, V>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`TrieIter`] over the keys starting with `prefix`, in
+    /// lexicographic order.
+    ///
+    /// See [`TrieMapIter::sorted`].
+    pub fn sorted(node: Option<&'a TrieNode<T, H, V>>, prefix: Vec<T>) -> Self {
+        Self(TrieMapIter::sorted(node, prefix))
+    }
+}
+
+impl<T, V, H> Iterator for TrieIter<'_, T, H, V>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+}
+
+/// Iterates over every key stored in a trie that is a prefix of some input.
+///
+/// Each item is the length of the matching key, in elements of the input,
+/// along with the value stored against it. Keys are yielded from shortest to
+/// longest.
+pub struct PrefixesOf<'a, T, V, H, I>
+where
+    H: ChildStorage<T>,
+{
+    /// The node reached by the input consumed so far.
+    node: Option<&'a TrieNode<T, H, V>>,
+    /// The remaining input.
+    iter: I,
+    /// The number of input elements consumed to reach `node`.
+    length: usize,
+}
+
+impl<'a, T, V, H, I> PrefixesOf<'a, T, V, H, I>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`PrefixesOf`] iterator, matching `iter` from `root`.
+    pub fn new(root: &'a TrieNode<T, H, V>, iter: I) -> Self {
+        Self {
+            node: Some(root),
+            iter,
+            length: 0,
+        }
+    }
+}
+
+impl<'a, T, V, H, I, E> Iterator for PrefixesOf<'a, T, V, H, I>
+where
+    H: ChildStorage<T>,
+    I: Iterator<Item = E>,
+    E: Borrow<T>,
+{
+    type Item = (usize, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Step down the trie before checking the current node, so the
+            // iterator is already positioned for the next call:
+            let node = self.node?;
+            let length = self.length;
+            self.node = self
+                .iter
+                .next()
+                .and_then(|element| node.get(element.borrow()));
+            self.length += 1;
+
+            if let Some(value) = &node.value {
+                return Some((length, value));
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Serializes a trie as a flat list of its keys.
+//!
+//! The default serde representation of a trie mirrors its nodes, which is
+//! compact for machines but hard to read or edit by hand. This module can be
+//! used with `#[serde(with = "fast_trie::key_list")]` on a [`Trie`] or
+//! [`StringTrie`] field instead, which writes the keys as a sequence in
+//! lexicographic order and rebuilds the trie from them when reading.
+//!
+#![cfg_attr(feature = "string", doc = "```")]
+#![cfg_attr(not(feature = "string"), doc = "```ignore")]
+//! use fast_trie::string::StringTrie;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "fast_trie::key_list")]
+//!     words: StringTrie,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"words": ["tea", "ten", "to"]}"#).unwrap();
+//! assert!(config.words.contains("ten"));
+//! assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"words":["tea","ten","to"]}"#);
+//! ```
+
+use std::marker::PhantomData;
+
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+    de::{
+        SeqAccess,
+        Visitor,
+    },
+};
+
+use crate::{
+    children::ChildStorage,
+    trie::Trie,
+};
+
+#[cfg(feature = "string")]
+use crate::string::StringTrie;
+
+/// A trie that can be written as, and rebuilt from, a list of its keys.
+pub trait KeyList: Sized {
+    /// The type of each key.
+    type Key;
+
+    /// Returns an iterator over every key, in lexicographic order.
+    fn keys(&self) -> impl Iterator<Item = Self::Key> + '_;
+
+    /// Creates an empty trie.
+    fn empty() -> Self;
+
+    /// Inserts a key into the trie.
+    fn insert_key(&mut self, key: Self::Key);
+}
+
+impl<T, H> KeyList for Trie<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    type Key = Vec<T>;
+
+    fn keys(&self) -> impl Iterator<Item = Vec<T>> + '_ {
+        self.iter()
+    }
+
+    fn empty() -> Self {
+        Self::new()
+    }
+
+    fn insert_key(&mut self, key: Vec<T>) {
+        self.insert(key);
+    }
+}
+
+#[cfg(feature = "string")]
+impl KeyList for StringTrie {
+    type Key = String;
+
+    fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter()
+    }
+
+    fn empty() -> Self {
+        Self::new()
+    }
+
+    fn insert_key(&mut self, key: String) {
+        self.insert(&key);
+    }
+}
+
+/// Serializes `trie` as a sequence of its keys, in lexicographic order.
+pub fn serialize<K, S>(trie: &K, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: KeyList,
+    K::Key: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(trie.keys())
+}
+
+/// Deserializes a trie from a sequence of keys.
+///
+/// Keys may be in any order, and repeated keys are only inserted once.
+pub fn deserialize<'de, K, D>(deserializer: D) -> Result<K, D::Error>
+where
+    K: KeyList,
+    K::Key: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    // Inserts each key as it is read, so the list is never held in memory.
+    struct KeyListVisitor<K> {
+        marker: PhantomData<K>,
+    }
+
+    impl<'de, K> Visitor<'de> for KeyListVisitor<K>
+    where
+        K: KeyList,
+        K::Key: Deserialize<'de>,
+    {
+        type Value = K;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a sequence of keys")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut trie = K::empty();
+            while let Some(key) = seq.next_element()? {
+                trie.insert_key(key);
+            }
+            Ok(trie)
+        }
+    }
+
+    deserializer.deserialize_seq(KeyListVisitor {
+        marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::children::SortedVec;
+
+    #[derive(Serialize, Deserialize)]
+    struct Bytes<H: ChildStorage<u8>> {
+        #[serde(with = "super")]
+        trie: Trie<u8, H>,
+    }
+
+    #[cfg(feature = "string")]
+    #[derive(Serialize, Deserialize)]
+    struct Strings {
+        #[serde(with = "super")]
+        trie: StringTrie,
+    }
+
+    #[test]
+    fn test_trie() {
+        let mut trie = Trie::<u8, SortedVec>::new();
+        for key in [&[2, 1][..], &[1], &[1, 2, 3], &[]] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+
+        let serialized = serde_json::to_string(&Bytes { trie }).unwrap();
+        assert_eq!(serialized, r#"{"trie":[[],[1],[1,2,3],[2,1]]}"#);
+        let deserialized: Bytes<RandomState> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.trie.len(), 4);
+        assert!(deserialized.trie.contains([1, 2, 3]));
+        assert!(!deserialized.trie.contains([1, 2]));
+        assert_eq!(deserialized.trie.validate(), Ok(()));
+    }
+
+    #[cfg(feature = "string")]
+    #[test]
+    fn test_string_trie() {
+        let json = r#"{"trie":["ten","tea","","日本","ten"]}"#;
+        let deserialized: Strings = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.trie.len(), 4);
+        assert!(deserialized.trie.contains(""));
+        assert!(deserialized.trie.contains("日本"));
+
+        let serialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(serialized, r#"{"trie":["","tea","ten","日本"]}"#);
+    }
+}
@@ -1,5 +1,24 @@
+pub mod aho_corasick;
+mod binary;
+mod bits;
+pub mod byte;
+pub mod children;
+pub mod dawg;
+pub mod double_array;
+pub mod entry;
+pub mod fuzzy;
 pub mod iter;
+#[cfg(feature = "serde")]
+pub mod key_list;
+pub mod louds;
+pub mod map;
+pub mod mapped;
+pub mod pattern;
+pub mod radix;
+pub mod subtrie;
 pub mod trie;
+#[cfg(feature = "weights")]
+pub mod weighted;
 pub mod node;
 
 #[cfg(feature = "string")]
@@ -9,11 +28,26 @@ pub mod string;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
+        map::TrieMap,
         trie::Trie,
         node::TrieNode,
+        byte::ByteTrie,
+        radix::RadixTrie,
     };
 
+    #[doc(hidden)]
+    #[cfg(feature = "weights")]
+    pub use crate::weighted::WeightedTrie;
+
     #[doc(hidden)]
     #[cfg(feature = "string")]
-    pub use crate::string::StringTrie;
+    pub use crate::string::{
+        StringTrie,
+        map::StringTrieMap,
+        radix::StringRadixTrie,
+    };
+
+    #[doc(hidden)]
+    #[cfg(all(feature = "string", feature = "weights"))]
+    pub use crate::string::weighted::StringWeightedTrie;
 }
\ No newline at end of file
@@ -0,0 +1,311 @@
+use std::{
+    borrow::Borrow,
+    collections::VecDeque,
+    ops::Range,
+};
+
+use crate::{
+    bits::BitVec,
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// A read-only trie in a succinct LOUDS (level-order unary degree sequence)
+/// encoding.
+///
+/// Nodes are numbered in breadth-first order, with the children of each node
+/// sorted by element. The shape of the trie is stored as a single bit vector:
+/// after a leading `10` for a virtual super root, each node writes one `1` for
+/// each child followed by a `0`. Alongside this, the trie stores the element
+/// leading to each node and a bit vector marking the nodes that end a key.
+///
+/// This needs roughly two bits per node for the shape, one bit per node for
+/// the end markers and one element per node, plus a small directory that makes
+/// rank and select queries fast. Moving from a node to its children is a
+/// single select query, and the children of a node are found by a binary
+/// search over its elements.
+///
+/// A [`Louds`] trie is created with [`Trie::louds`](crate::trie::Trie::louds)
+/// and can't be modified afterwards.
+pub struct Louds<T> {
+    /// The shape of the trie.
+    shape: BitVec,
+    /// The element leading to each node, except the root.
+    labels: Box<[T]>,
+    /// Set for each node that ends a key.
+    ends: BitVec,
+}
+
+impl<T> Louds<T>
+where
+    T: Ord + Copy,
+{
+    /// Builds a [`Louds`] trie containing every key at or below `root`.
+    pub fn new<V, H>(root: &TrieNode<T, H, V>) -> Self
+    where
+        H: ChildStorage<T>,
+    {
+        let mut shape = BitVec::new();
+        let mut labels = Vec::new();
+        let mut ends = BitVec::new();
+
+        // The virtual super root has the root as its only child:
+        shape.push(true);
+        shape.push(false);
+
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            ends.push(node.value.is_some());
+
+            let mut children: Vec<_> = node.children.iter().collect();
+            children.sort_unstable_by_key(|(element, _)| **element);
+            for (element, child) in children {
+                shape.push(true);
+                labels.push(*element);
+                queue.push_back(child);
+            }
+            shape.push(false);
+        }
+
+        Self {
+            shape,
+            labels: labels.into_boxed_slice(),
+            ends,
+        }
+    }
+
+    /// Returns the number of the first child of `node`.
+    ///
+    /// `node` may be one past the last node, in which case this returns one
+    /// past the last node too. This lets whole levels of the trie be mapped
+    /// to the level below.
+    fn first_child(&self, node: usize) -> usize {
+        // The children of `node` follow the `node`th unset bit. Every bit
+        // before them is either an unset bit or a child with a smaller number:
+        self.shape.select0(node) - node
+    }
+
+    /// Returns the numbers of the children of `node`.
+    fn children(&self, node: usize) -> Range<usize> {
+        self.first_child(node)..self.first_child(node + 1)
+    }
+
+    /// Returns the element leading to `node`.
+    fn label(&self, node: usize) -> T {
+        self.labels[node - 1]
+    }
+
+    /// Returns the child of `node` reached by `element`.
+    fn get(&self, node: usize, element: &T) -> Option<usize> {
+        let children = self.children(node);
+        self.labels[children.start - 1..children.end - 1]
+            .binary_search(element)
+            .ok()
+            .map(|index| children.start + index)
+    }
+
+    /// Walks from the root along the path described by `iter`.
+    fn walk<E>(&self, iter: impl IntoIterator<Item = E>) -> Option<usize>
+    where
+        E: Borrow<T>,
+    {
+        let mut current = 0;
+        for element in iter {
+            current = self.get(current, element.borrow())?;
+        }
+        Some(current)
+    }
+
+    /// Returns `true` if the trie is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.ends.rank1(self.ends.len())
+    }
+
+    /// Returns the number of nodes within the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the number of bytes used by the trie, not counting the trie
+    /// itself.
+    pub fn size_in_bytes(&self) -> usize {
+        self.shape.size_in_bytes()
+            + self.ends.size_in_bytes()
+            + std::mem::size_of_val(&*self.labels)
+    }
+
+    /// Returns `true` if the [`Louds`] trie contains a value, otherwise
+    /// returns `false`.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.walk(iter)
+            .is_some_and(|node| self.ends.get(node))
+    }
+
+    /// Returns `true` if any value within the [`Louds`] trie starts with
+    /// `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        // Every node other than the root leads to a value:
+        self.walk(prefix)
+            .is_some_and(|node| node != 0 || !self.is_empty())
+    }
+
+    /// Returns the number of values within the [`Louds`] trie that start with
+    /// `prefix`.
+    ///
+    /// The nodes below the prefix on each level are numbered consecutively,
+    /// so each level is counted with a single rank query rather than visiting
+    /// every node.
+    pub fn count_with_prefix(&self, prefix: impl IntoIterator<Item = T>) -> usize {
+        let Some(node) = self.walk(prefix) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        let mut level = node..node + 1;
+        while !level.is_empty() {
+            count += self.ends.rank1(level.end) - self.ends.rank1(level.start);
+            level = self.first_child(level.start)..self.first_child(level.end);
+        }
+        count
+    }
+
+    /// Returns an iterator over every value in the [`Louds`] trie, in
+    /// lexicographic order.
+    pub fn iter(&self) -> LoudsIter<'_, T> {
+        LoudsIter::new(self, Some(0), Vec::new())
+    }
+
+    /// Returns an iterator over every value in the [`Louds`] trie that starts
+    /// with `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> LoudsIter<'_, T> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        LoudsIter::new(self, self.walk(&prefix), prefix)
+    }
+}
+
+/// Iterates over every key below a node of a [`Louds`] trie, in lexicographic
+/// order.
+pub struct LoudsIter<'a, T> {
+    trie: &'a Louds<T>,
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may be the end of a key itself.
+    start: Option<usize>,
+    stack: Vec<(usize, usize)>,
+    buffer: Vec<T>,
+}
+
+impl<'a, T> LoudsIter<'a, T>
+where
+    T: Ord + Copy,
+{
+    fn new(trie: &'a Louds<T>, node: Option<usize>, prefix: Vec<T>) -> Self {
+        Self {
+            trie,
+            start: node,
+            stack: Vec::new(),
+            buffer: prefix,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack in reverse order, so that
+    /// the smallest child is popped first.
+    fn push_children(&mut self, node: usize, depth: usize) {
+        let children = self.trie.children(node).rev();
+        self.stack.extend(children.map(|child| (child, depth)));
+    }
+}
+
+impl<T> Iterator for LoudsIter<'_, T>
+where
+    T: Ord + Copy,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, self.buffer.len());
+            if self.trie.ends.get(start) {
+                return Some(self.buffer.clone());
+            }
+        }
+
+        while let Some((node, depth)) = self.stack.pop() {
+            // If we're backtracking, truncate the prefix to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.push(self.trie.label(node));
+            self.push_children(node, depth + 1);
+
+            if self.trie.ends.get(node) {
+                return Some(self.buffer.clone());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_lookups() {
+        let keys = ["", "a", "ab", "abc", "b", "banana", "band", "bandana", "日本", "日本語"];
+        let trie = Trie::<char>::from_iter(keys.map(str::chars));
+        let louds = trie.louds();
+        assert_eq!(louds.len(), keys.len());
+        assert_eq!(louds.node_count(), trie.node_count());
+
+        for key in keys {
+            assert!(louds.contains(key.chars()), "{key}");
+        }
+        for key in ["ba", "bandanas", "c", "日", "語"] {
+            assert!(!louds.contains(key.chars()), "{key}");
+        }
+        assert!(louds.starts_with("band".chars()));
+        assert!(!louds.starts_with("x".chars()));
+
+        let found: Vec<String> = louds.iter().map(String::from_iter).collect();
+        assert_eq!(found, keys);
+        let found: Vec<String> = louds.iter_prefix("ban".chars()).map(String::from_iter).collect();
+        assert_eq!(found, ["banana", "band", "bandana"]);
+        assert_eq!(louds.iter_prefix("x".chars()).count(), 0);
+    }
+
+    #[test]
+    fn test_count_with_prefix() {
+        let mut trie = Trie::<u16>::new();
+        for a in 0..40u16 {
+            for b in 0..=a % 7 {
+                assert!(trie.insert([a % 5, a, b]));
+            }
+        }
+        let louds = trie.louds();
+
+        assert_eq!(louds.count_with_prefix([]), trie.len());
+        for a in 0..40u16 {
+            assert_eq!(louds.count_with_prefix([a % 5]), trie.count_with_prefix([a % 5]));
+            assert_eq!(louds.count_with_prefix([a % 5, a]), usize::from(a % 7 + 1));
+        }
+        assert_eq!(louds.count_with_prefix([5]), 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let louds = Trie::<char>::new().louds();
+        assert!(louds.is_empty());
+        assert_eq!(louds.node_count(), 1);
+        assert!(!louds.contains("".chars()));
+        assert!(!louds.starts_with("".chars()));
+        assert_eq!(louds.count_with_prefix("".chars()), 0);
+        assert_eq!(louds.iter().count(), 0);
+    }
+}
@@ -0,0 +1,755 @@
+use std::{
+    fmt,
+    hash::RandomState,
+};
+
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+    ser::SerializeStruct,
+};
+
+use crate::{
+    children::ChildStorage,
+    entry::Entry,
+    fuzzy::FuzzySearch,
+    iter::{PrefixesOf, TrieIter, TrieMapIter},
+    node::TrieNode,
+    pattern::{PatternMatches, Token},
+};
+
+/// An invariant that doesn't hold within a trie, found by
+/// [`TrieMap::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidTrie {
+    /// The trie's length doesn't match the number of values within it.
+    Count {
+        /// The length the trie reports.
+        stored: usize,
+        /// The number of values within the trie.
+        actual: usize,
+    },
+    /// A node other than the root stores no value and has no children, so the
+    /// branch leading to it doesn't lead to a key.
+    DeadBranch,
+    /// A node's count of the values at or below it is wrong.
+    #[cfg(feature = "counts")]
+    NodeCount,
+    /// A [`RadixTrie`](crate::radix::RadixTrie) node has an empty label, or a
+    /// label that doesn't start with the element it is stored under.
+    EdgeLabel,
+    /// A [`RadixTrie`](crate::radix::RadixTrie) node other than the root
+    /// stores no value and has a single child, so it should have been merged
+    /// with that child.
+    UnmergedEdge,
+}
+
+impl fmt::Display for InvalidTrie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Count { stored, actual } => {
+                write!(f, "trie has a length of {stored} but contains {actual} values")
+            }
+            Self::DeadBranch => f.write_str("trie has a branch that does not lead to a key"),
+            #[cfg(feature = "counts")]
+            Self::NodeCount => f.write_str("trie node has the wrong count of values"),
+            Self::EdgeLabel => f.write_str("radix trie node has an invalid label"),
+            Self::UnmergedEdge => f.write_str("radix trie node has a single child and no value"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidTrie {}
+
+/// Stores a value against each key in a trie.
+///
+/// This works in exactly the same way as a [`Trie`](crate::trie::Trie), except
+/// that the node at the end of each key stores a value of type `V` rather than
+/// just a marker. A [`Trie`](crate::trie::Trie) is a thin wrapper over a
+/// `TrieMap<T, ()>`.
+pub struct TrieMap<T, V, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// Root node that tracks every value within the trie.
+    pub(super) root: TrieNode<T, H, V>,
+    /// Tracks the number of values in the trie.
+    pub(super) count: usize,
+}
+
+impl<T, V, H> Default for TrieMap<T, V, H>
+where
+    H: Default,
+    H: ChildStorage<T>,
+{
+    fn default() -> Self {
+        Self {
+            root: TrieNode::default(),
+            count: 0,
+        }
+    }
+}
+
+impl<T, V, H, I> FromIterator<(I, V)> for TrieMap<T, V, H>
+where
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn from_iter<K: IntoIterator<Item = (I, V)>>(entries: K) -> Self {
+        let mut map = Self::new();
+        map.extend(entries);
+        map
+    }
+}
+
+impl<T, V, H, I> Extend<(I, V)> for TrieMap<T, V, H>
+where
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn extend<K: IntoIterator<Item = (I, V)>>(&mut self, entries: K) {
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<T, V, H> TrieMap<T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new empty [`TrieMap`].
+    pub fn new() -> Self {
+        Self {
+            root: TrieNode::empty(),
+            count: 0,
+        }
+    }
+
+    /// Returns `true` if the trie is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.root.clear();
+        self.count = 0;
+    }
+
+    /// Inserts a key and value into the trie.
+    ///
+    /// If the key was not already present, this function returns [`None`].
+    /// Otherwise the value is replaced and the previous value is returned.
+    pub fn insert(&mut self, iter: impl IntoIterator<Item = T>, value: V) -> Option<V> {
+        // Store the value and update the internal value counter if the key is
+        // new:
+        let previous = self.root.insert_branch(iter.into_iter(), value);
+        if previous.is_none() {
+            self.count += 1;
+        }
+        previous
+    }
+
+    /// Removes a key from the trie.
+    ///
+    /// Returns the value that was stored against the key, or [`None`] if the
+    /// key does not exist.
+    pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> Option<V> {
+        let removed = self.root.remove_branch(iter.into_iter());
+        if removed.is_some() {
+            self.count -= 1;
+        }
+        removed
+    }
+
+    /// Returns an immutable reference to the value stored against a key.
+    ///
+    /// If the key does not exist, this function returns [`None`].
+    pub fn get(&self, iter: impl IntoIterator<Item = T>) -> Option<&V> {
+        self.root
+            .walk(iter)?
+            .value
+            .as_ref()
+    }
+
+    /// Returns a mutable reference to the value stored against a key.
+    ///
+    /// If the key does not exist, this function returns [`None`].
+    pub fn get_mut(&mut self, iter: impl IntoIterator<Item = T>) -> Option<&mut V> {
+        self.root
+            .walk_mut(iter)?
+            .value
+            .as_mut()
+    }
+
+    /// Returns the [`Entry`] for a key, allowing it to be inspected and
+    /// modified in place.
+    ///
+    /// The path to the key is only walked once.
+    pub fn entry(&mut self, iter: impl IntoIterator<Item = T>) -> Entry<'_, T, V, H> {
+        Entry::new(&mut self.root, &mut self.count, iter)
+    }
+
+    /// Returns `true` if the [`TrieMap`] contains a key, otherwise returns
+    /// `false`.
+    pub fn contains_key(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.get(iter).is_some()
+    }
+
+    /// Returns `true` if any key within the [`TrieMap`] starts with `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.root
+            .walk(prefix)
+            .is_some_and(TrieNode::has_values)
+    }
+
+    /// Returns the number of keys within the [`TrieMap`] that start with
+    /// `prefix`.
+    ///
+    /// With the `counts` feature enabled, this only walks the prefix.
+    /// Otherwise every node below the prefix is visited.
+    pub fn count_with_prefix(&self, prefix: impl IntoIterator<Item = T>) -> usize {
+        self.root
+            .walk(prefix)
+            .map_or(0, TrieNode::count_values)
+    }
+
+    /// Returns an iterator over every key within the [`TrieMap`] that is a
+    /// prefix of `input`, from shortest to longest.
+    ///
+    /// Each item is the length of the key along with its value.
+    pub fn prefixes_of<I>(&self, input: I) -> PrefixesOf<'_, T, V, H, I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.root.iter_prefixes_of(input)
+    }
+
+    /// Returns the longest key within the [`TrieMap`] that is a prefix of
+    /// `input`, as its length along with its value.
+    ///
+    /// If no key is a prefix of `input`, this function returns [`None`].
+    pub fn longest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<(usize, &V)> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns the shortest key within the [`TrieMap`] that is a prefix of
+    /// `input`, as its length along with its value.
+    ///
+    /// If no key is a prefix of `input`, this function returns [`None`].
+    pub fn shortest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<(usize, &V)> {
+        self.prefixes_of(input).next()
+    }
+
+    /// Returns an immutable reference to the root [`TrieNode`].
+    ///
+    /// This is the node that contains every value.
+    pub fn root(&self) -> &TrieNode<T, H, V> {
+        &self.root
+    }
+
+    /// Checks the invariants of the [`TrieMap`], visiting every node.
+    ///
+    /// The length must match the number of values, and every node other than
+    /// the root must store a value or have children. With the `counts` feature
+    /// enabled, the count of each node is checked too. These always hold for
+    /// tries built through this API, so this is mostly useful for debugging.
+    pub fn validate(&self) -> Result<(), InvalidTrie> {
+        let actual = self.root.validate()?;
+        if actual != self.count {
+            return Err(InvalidTrie::Count {
+                stored: self.count,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T, V, H> TrieMap<T, V, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every key and value in the [`TrieMap`], in
+    /// the order the child storage keeps them.
+    ///
+    /// This skips sorting the children of each node, so it is faster than
+    /// [`TrieMap::iter`], but the order is arbitrary for hash maps.
+    pub fn iter_unordered(&self) -> TrieMapIter<'_, T, V, H> {
+        self.root.iter_values()
+    }
+
+    /// Returns an iterator over every key and value whose key starts with
+    /// `prefix`, in the order the child storage keeps them.
+    ///
+    /// See [`TrieMap::iter_unordered`].
+    pub fn iter_prefix_unordered(&self, prefix: impl IntoIterator<Item = T>) -> TrieMapIter<'_, T, V, H> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        TrieMapIter::with_prefix(self.root.walk(&prefix), prefix)
+    }
+
+    /// Returns an iterator over every key and value whose key is within
+    /// `max_distance` edits of `query`, along with the distance.
+    ///
+    /// See [`FuzzySearch`].
+    pub fn fuzzy_search(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> FuzzySearch<'_, T, V, H> {
+        FuzzySearch::new(&self.root, query.into_iter().collect(), max_distance)
+    }
+
+    /// Returns an iterator over every key and value whose key is within
+    /// `max_distance` edits of `query`, where swapping two adjacent elements
+    /// counts as a single edit.
+    ///
+    /// See [`FuzzySearch::with_transpositions`].
+    pub fn fuzzy_search_transpositions(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> FuzzySearch<'_, T, V, H> {
+        FuzzySearch::with_transpositions(&self.root, query.into_iter().collect(), max_distance)
+    }
+
+    /// Returns an iterator over every key and value whose key matches
+    /// `pattern`.
+    ///
+    /// See [`PatternMatches`].
+    pub fn matches<P>(&self, pattern: impl IntoIterator<Item = Token<P>>) -> PatternMatches<'_, T, V, H, P>
+    where
+        P: Fn(&T) -> bool,
+    {
+        PatternMatches::new(&self.root, pattern.into_iter().collect())
+    }
+}
+
+impl<T, V, H> TrieMap<T, V, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every key and value in the [`TrieMap`], in
+    /// lexicographic order of the keys.
+    ///
+    /// The children of each node are sorted as they are visited. Use
+    /// [`TrieMap::iter_unordered`] if the order doesn't matter.
+    pub fn iter(&self) -> TrieMapIter<'_, T, V, H> {
+        TrieMapIter::sorted(Some(&self.root), Vec::new())
+    }
+
+    /// Returns an iterator over every key in the [`TrieMap`], in lexicographic
+    /// order.
+    pub fn keys(&self) -> TrieIter<'_, T, H, V> {
+        TrieIter::sorted(Some(&self.root), Vec::new())
+    }
+
+    /// Returns an iterator over every value in the [`TrieMap`], in
+    /// lexicographic order of their keys.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over every key and value whose key starts with
+    /// `prefix`, in lexicographic order of the keys.
+    ///
+    /// The iterator yields full keys, including the prefix.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> TrieMapIter<'_, T, V, H> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        TrieMapIter::sorted(self.root.walk(&prefix), prefix)
+    }
+
+    /// Returns an iterator over every key that starts with `prefix`, in
+    /// lexicographic order.
+    ///
+    /// The iterator yields full keys, including the prefix.
+    pub fn keys_prefix(&self, prefix: impl IntoIterator<Item = T>) -> TrieIter<'_, T, H, V> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        TrieIter::sorted(self.root.walk(&prefix), prefix)
+    }
+}
+
+#[cfg(feature = "counts")]
+impl<T, V, H> TrieMap<T, V, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the `n`th key and value in the [`TrieMap`], in lexicographic
+    /// order of the keys.
+    ///
+    /// If `n` is out of bounds, this function returns [`None`].
+    pub fn nth(&self, n: usize) -> Option<(Vec<T>, &V)> {
+        self.root.nth(n)
+    }
+
+    /// Returns the number of keys in the [`TrieMap`] that sort before `key`
+    /// in lexicographic order.
+    ///
+    /// The key does not need to exist within the [`TrieMap`].
+    pub fn rank(&self, key: impl IntoIterator<Item = T>) -> usize {
+        self.root.rank(key)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, V, H> Serialize for TrieMap<T, V, H>
+where
+    T: Serialize,
+    V: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.serialize_named("TrieMap", serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, V, H> TrieMap<T, V, H>
+where
+    T: Serialize,
+    V: Serialize,
+    H: ChildStorage<T>,
+{
+    /// Serializes the [`TrieMap`] as a struct called `name`, so wrappers such
+    /// as [`Trie`](crate::trie::Trie) can keep their own name.
+    pub(super) fn serialize_named<S>(&self, name: &'static str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize as a struct with two fields: `root` and `count`
+        let mut state = serializer.serialize_struct(name, 2)?;
+        state.serialize_field("root", &self.root)?;
+        state.serialize_field("count", &self.count)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, V, H> Deserialize<'de> for TrieMap<T, V, H>
+where
+    T: Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: ChildStorage<T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::deserialize_named("TrieMap", deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, V, H> TrieMap<T, V, H>
+where
+    T: Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: ChildStorage<T>,
+{
+    /// Deserializes a [`TrieMap`] written by [`TrieMap::serialize_named`].
+    pub(super) fn deserialize_named<D>(name: &'static str, deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Visitor for deserializing a `TrieMap`.
+        struct TrieMapVisitor<T, V, H> {
+            marker: std::marker::PhantomData<(T, V, H)>,
+        }
+
+        impl<'de, T, V, H> serde::de::Visitor<'de> for TrieMapVisitor<T, V, H>
+        where
+            T: Deserialize<'de>,
+            V: Deserialize<'de>,
+            H: ChildStorage<T>,
+        {
+            type Value = TrieMap<T, V, H>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a TrieMap with root and count fields")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: serde::de::MapAccess<'de>,
+            {
+                let mut root = None;
+                let mut count = None;
+
+                while let Some(key) = map.next_key::<&str>()? {
+                    match key {
+                        "root" => {
+                            if root.is_some() {
+                                return Err(serde::de::Error::duplicate_field("root"));
+                            }
+                            root = Some(map.next_value()?);
+                        }
+                        "count" => {
+                            if count.is_some() {
+                                return Err(serde::de::Error::duplicate_field("count"));
+                            }
+                            count = Some(map.next_value()?);
+                        }
+                        _ => {
+                            return Err(serde::de::Error::unknown_field(key, &["root", "count"]));
+                        }
+                    }
+                }
+
+                let root: TrieNode<T, H, V> = root.ok_or_else(|| serde::de::Error::missing_field("root"))?;
+
+                // Dead branches are pruned as the nodes are read, so only the
+                // count needs checking. A missing count is rebuilt instead:
+                let actual = root.count_values();
+                match count {
+                    Some(stored) if stored != actual => {
+                        Err(serde::de::Error::custom(InvalidTrie::Count { stored, actual }))
+                    }
+                    _ => Ok(TrieMap { root, count: actual }),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct(
+            name,
+            &["root", "count"],
+            TrieMapVisitor {
+                marker: std::marker::PhantomData,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CharTrieMap = TrieMap<char, usize>;
+
+    #[test]
+    fn test_empty() {
+        let map = CharTrieMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+        assert_eq!(map.get("test".chars()), None);
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("test".chars(), 1), None);
+        assert_eq!(map.insert("tester".chars(), 2), None);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get("test".chars()), Some(&1));
+        assert_eq!(map.get("tester".chars()), Some(&2));
+        assert_eq!(map.get("tes".chars()), None);
+        assert_eq!(map.get("testing".chars()), None);
+
+        // Inserting an existing key should replace the value and return the
+        // previous one:
+        assert_eq!(map.insert("test".chars(), 3), Some(1));
+        assert_eq!(map.get("test".chars()), Some(&3));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("test".chars(), 1), None);
+
+        *map.get_mut("test".chars()).unwrap() += 10;
+        assert_eq!(map.get("test".chars()), Some(&11));
+        assert!(map.get_mut("te".chars()).is_none());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("test".chars(), 1), None);
+        assert_eq!(map.insert("testing".chars(), 2), None);
+
+        assert_eq!(map.remove("test".chars()), Some(1));
+        assert_eq!(map.remove("test".chars()), None);
+        assert_eq!(map.get("testing".chars()), Some(&2));
+        assert_eq!(map.len(), 1);
+
+        assert_eq!(map.remove("testing".chars()), Some(2));
+        assert!(map.is_empty());
+        assert!(map.root().is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("a".chars(), 1), None);
+        assert_eq!(map.insert("b".chars(), 2), None);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+        assert!(!map.contains_key("a".chars()));
+    }
+
+    #[test]
+    fn test_from_iter_and_extend() {
+        let mut map: CharTrieMap = [("a".chars(), 1), ("ab".chars(), 2), ("a".chars(), 3)].into_iter().collect();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("a".chars()), Some(&3));
+
+        map.extend([("b".chars(), 4)]);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("b".chars()), Some(&4));
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut values = std::collections::HashMap::new();
+        values.insert("test", 1);
+        values.insert("other", 2);
+        values.insert("hello world", 3);
+
+        let mut map = CharTrieMap::new();
+        for (key, value) in values.iter() {
+            assert_eq!(map.insert(key.chars(), *value), None);
+        }
+
+        for (key, value) in map.iter() {
+            let key = String::from_iter(key.iter());
+            assert_eq!(values.remove(key.as_str()), Some(*value));
+        }
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_iter_nested() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("".chars(), 0), None);
+        assert_eq!(map.insert("a".chars(), 1), None);
+        assert_eq!(map.insert("ab".chars(), 2), None);
+        assert_eq!(map.insert("abc".chars(), 3), None);
+
+        let found: Vec<(String, usize)> = map
+            .iter()
+            .map(|(key, value)| (String::from_iter(key), *value))
+            .collect();
+        assert_eq!(found, [
+            (String::new(), 0),
+            ("a".to_string(), 1),
+            ("ab".to_string(), 2),
+            ("abc".to_string(), 3),
+        ]);
+        assert_eq!(map.values().sum::<usize>(), 6);
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn test_nth_and_rank() {
+        let mut map = CharTrieMap::new();
+        let keys = ["tea", "te", "test", "tesla", "a", "zebra", "testing"];
+        for (index, key) in keys.iter().enumerate() {
+            assert_eq!(map.insert(key.chars(), index), None);
+        }
+
+        let mut sorted = keys.to_vec();
+        sorted.sort();
+        for (n, key) in sorted.iter().enumerate() {
+            let (found, value) = map.nth(n).unwrap();
+            assert_eq!(String::from_iter(found), *key);
+            assert_eq!(keys[*value], *key);
+            assert_eq!(map.rank(key.chars()), n);
+        }
+        assert_eq!(map.nth(sorted.len()), None);
+
+        // Keys that are not stored are ranked by where they would be
+        // inserted:
+        assert_eq!(map.rank("".chars()), 0);
+        assert_eq!(map.rank("b".chars()), 1);
+        assert_eq!(map.rank("tes".chars()), 3);
+        assert_eq!(map.rank("zz".chars()), sorted.len());
+
+        // Counts must stay correct after removals:
+        assert_eq!(map.remove("te".chars()), Some(1));
+        assert_eq!(map.remove("zebra".chars()), Some(5));
+        assert_eq!(map.count_with_prefix("te".chars()), 4);
+        assert_eq!(String::from_iter(map.nth(1).unwrap().0), "tea");
+        assert_eq!(map.nth(5), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_deserialize() {
+        use serde_json;
+
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.insert("hello".chars(), 1), None);
+        assert_eq!(map.insert("world".chars(), 2), None);
+
+        let serialized = serde_json::to_string(&map).unwrap();
+        let deserialized: CharTrieMap = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.get("hello".chars()), Some(&1));
+        assert_eq!(deserialized.get("world".chars()), Some(&2));
+        assert_eq!(deserialized.len(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut map = CharTrieMap::new();
+        assert_eq!(map.validate(), Ok(()));
+        for (index, key) in ["test", "tea", "ten", "to"].into_iter().enumerate() {
+            assert_eq!(map.insert(key.chars(), index), None);
+        }
+        assert_eq!(map.remove("tea".chars()), Some(1));
+        assert_eq!(map.validate(), Ok(()));
+
+        map.count = 5;
+        assert_eq!(map.validate(), Err(InvalidTrie::Count { stored: 5, actual: 3 }));
+        map.count = 3;
+
+        #[cfg(feature = "counts")]
+        {
+            map.root.get_mut(&'t').unwrap().count += 1;
+            assert_eq!(map.validate(), Err(InvalidTrie::NodeCount));
+            map.root.get_mut(&'t').unwrap().count -= 1;
+        }
+
+        let _ = map.root.get_or_create('x');
+        assert_eq!(map.validate(), Err(InvalidTrie::DeadBranch));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_invalid() {
+        use serde_json;
+
+        // A hand-written trie storing "a" and "b", with the wrong count:
+        let leaf = r#"{"children": {}, "end_of_value": true, "value": 1}"#;
+        let root = format!(r#"{{"children": {{"a": {leaf}, "b": {leaf}}}, "end_of_value": false}}"#);
+        let wrong = format!(r#"{{"root": {root}, "count": 3}}"#);
+        let error = serde_json::from_str::<CharTrieMap>(&wrong).err().unwrap();
+        assert!(error.to_string().contains("contains 2 values"));
+
+        // A missing count is rebuilt:
+        let missing = format!(r#"{{"root": {root}}}"#);
+        let map: CharTrieMap = serde_json::from_str(&missing).unwrap();
+        assert_eq!(map.len(), 2);
+
+        // Branches that don't lead to a value are dropped:
+        let dead = r#"{"children": {"c": {"children": {}, "end_of_value": false}}, "end_of_value": false}"#;
+        let root = format!(r#"{{"children": {{"a": {leaf}, "b": {dead}}}, "end_of_value": false}}"#);
+        let map: CharTrieMap = serde_json::from_str(&format!(r#"{{"root": {root}, "count": 1}}"#)).unwrap();
+        assert_eq!(map.validate(), Ok(()));
+        assert!(!map.starts_with("b".chars()));
+        assert_eq!(map.root().len(), 1);
+
+        // Two children for the same element are rejected:
+        let root = format!(r#"{{"children": {{"a": {leaf}, "a": {leaf}}}, "end_of_value": false}}"#);
+        let error = serde_json::from_str::<CharTrieMap>(&format!(r#"{{"root": {root}}}"#)).err().unwrap();
+        assert!(error.to_string().contains("duplicate child"));
+    }
+}
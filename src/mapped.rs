@@ -0,0 +1,423 @@
+use std::{
+    collections::VecDeque,
+    io::{
+        self,
+        Write,
+    },
+};
+
+use crate::{
+    children::ChildStorage,
+    double_array::{
+        ByteNode,
+        Symbol,
+    },
+    node::TrieNode,
+};
+
+/// Identifies a trie written by [`MappedTrie::write_to`].
+const MAGIC: &[u8; 4] = b"FTMT";
+
+/// The version of the format.
+const VERSION: u32 = 1;
+
+/// The size of the header in bytes. The root node follows straight after it.
+const HEADER: usize = 16;
+
+/// The size of a node before its labels: a flags byte and a `u16` child
+/// count.
+const NODE_HEADER: usize = 3;
+
+/// Set in the flags of a node that ends a key.
+const END_FLAG: u8 = 1;
+
+/// A read-only trie queried in place from a borrowed byte buffer.
+///
+/// The buffer holds a trie written by [`MappedTrie::write_to`], and may come
+/// straight from a file mapped into memory. Creating a [`MappedTrie`] only
+/// checks the header, so it takes constant time and allocates nothing no
+/// matter how large the trie is. Every query then reads the nodes it needs
+/// directly from the buffer.
+///
+/// Keys are stored as the bytes produced by their [`Symbol`]s, so a string
+/// trie stores the UTF-8 bytes of each string. Keys are always visited in
+/// lexicographic order of their bytes.
+///
+/// # Format
+/// The buffer starts with a 16 byte header: the magic number `FTMT`, a `u32`
+/// format version and the `u64` number of keys. The root node follows, then
+/// every other node in breadth-first order. Each node is a flags byte, a `u16`
+/// child count, the byte leading to each child in ascending order and then
+/// the `u32` offset of each child from the start of the buffer. Every number
+/// is little-endian.
+///
+/// Since nodes are only read when a query reaches them, a corrupt buffer is
+/// not detected up front. Reads outside the buffer are treated as missing
+/// nodes rather than panicking, and a child must always come after its
+/// parent, so every query finishes even on corrupt input.
+#[derive(Clone, Copy)]
+pub struct MappedTrie<'a> {
+    bytes: &'a [u8],
+    /// The number of keys within the trie.
+    count: usize,
+}
+
+/// A node read from the buffer of a [`MappedTrie`].
+#[derive(Clone, Copy)]
+struct Node<'a> {
+    /// The offset of the node within the buffer.
+    offset: usize,
+    end: bool,
+    /// The byte leading to each child, in ascending order.
+    labels: &'a [u8],
+    /// The little-endian `u32` offset of each child.
+    offsets: &'a [u8],
+}
+
+impl Node<'_> {
+    /// Returns the offset of the child at `index`, if it comes after this
+    /// node.
+    fn child_at(&self, index: usize) -> Option<usize> {
+        let bytes = self.offsets.get(index * 4..index * 4 + 4)?;
+        let offset = u32::from_le_bytes(bytes.try_into().unwrap()) as usize;
+        (offset > self.offset).then_some(offset)
+    }
+
+    /// Returns the offset of the child reached by `byte`.
+    fn child(&self, byte: u8) -> Option<usize> {
+        let index = self.labels.binary_search(&byte).ok()?;
+        self.child_at(index)
+    }
+}
+
+impl<'a> MappedTrie<'a> {
+    /// Creates a [`MappedTrie`] over `bytes`, checking only the header.
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if the
+    /// header is missing, isn't from a [`MappedTrie`] or has an unsupported
+    /// version.
+    pub fn new(bytes: &'a [u8]) -> io::Result<Self> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        if bytes.len() < HEADER + NODE_HEADER {
+            return Err(invalid("mapped trie is too short"));
+        }
+        if &bytes[..4] != MAGIC {
+            return Err(invalid("not a mapped trie"));
+        }
+        if u32::from_le_bytes(bytes[4..8].try_into().unwrap()) != VERSION {
+            return Err(invalid("unsupported mapped trie version"));
+        }
+        let count = usize::try_from(u64::from_le_bytes(bytes[8..16].try_into().unwrap()))
+            .map_err(|_| invalid("key count is too large"))?;
+
+        Ok(Self {
+            bytes,
+            count,
+        })
+    }
+
+    /// Writes every key at or below `root` to `writer`, in the format read by
+    /// [`MappedTrie::new`].
+    ///
+    /// Returns an [`InvalidInput`](io::ErrorKind::InvalidInput) error if the
+    /// trie needs more than 4 GiB, since offsets are stored as `u32`s.
+    pub fn write_to<T, V, H>(root: &TrieNode<T, H, V>, mut writer: impl Write) -> io::Result<()>
+    where
+        T: Symbol,
+        H: ChildStorage<T>,
+    {
+        let (nodes, count) = ByteNode::expand(root);
+
+        // Lay out the nodes in breadth-first order, so every child comes
+        // after its parent:
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut offsets = vec![0; nodes.len()];
+        let mut queue = VecDeque::from([0]);
+        let mut offset = HEADER;
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            offsets[node] = u32::try_from(offset).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "trie is too large to map")
+            })?;
+            offset += NODE_HEADER + nodes[node].children.len() * 5;
+            queue.extend(nodes[node].children.values());
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(count as u64).to_le_bytes())?;
+        for node in order {
+            let node = &nodes[node];
+            writer.write_all(&[if node.end { END_FLAG } else { 0 }])?;
+            writer.write_all(&(node.children.len() as u16).to_le_bytes())?;
+            for byte in node.children.keys() {
+                writer.write_all(&[*byte])?;
+            }
+            for child in node.children.values() {
+                writer.write_all(&offsets[*child].to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the node at `offset`.
+    fn node(&self, offset: usize) -> Option<Node<'a>> {
+        let header = self.bytes.get(offset..offset + NODE_HEADER)?;
+        let children = usize::from(u16::from_le_bytes([header[1], header[2]]));
+        let labels = offset + NODE_HEADER;
+        let offsets = labels + children;
+        Some(Node {
+            offset,
+            end: header[0] & END_FLAG != 0,
+            labels: self.bytes.get(labels..offsets)?,
+            offsets: self.bytes.get(offsets..offsets + children * 4)?,
+        })
+    }
+
+    /// Walks from the root along `bytes`.
+    fn walk(&self, bytes: &[u8]) -> Option<Node<'a>> {
+        let mut current = self.node(HEADER)?;
+        for byte in bytes {
+            current = self.node(current.child(*byte)?)?;
+        }
+        Some(current)
+    }
+
+    /// Returns `true` if the trie is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the trie contains the key, otherwise returns `false`.
+    pub fn contains(&self, key: impl AsRef<[u8]>) -> bool {
+        self.walk(key.as_ref())
+            .is_some_and(|node| node.end)
+    }
+
+    /// Returns `true` if any key within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: impl AsRef<[u8]>) -> bool {
+        // Every node other than the root leads to a value:
+        self.walk(prefix.as_ref())
+            .is_some_and(|node| node.offset != HEADER || !self.is_empty())
+    }
+
+    /// Returns the length of every key within the trie that is a prefix of
+    /// `input`, from shortest to longest.
+    fn prefix_lengths(&self, input: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let trie = *self;
+        let mut node = trie.node(HEADER);
+        let mut bytes = input.iter().enumerate();
+        std::iter::from_fn(move || {
+            while let Some(current) = node {
+                let length = match bytes.next() {
+                    Some((index, byte)) => {
+                        node = current.child(*byte).and_then(|child| trie.node(child));
+                        index
+                    },
+                    None => {
+                        node = None;
+                        input.len()
+                    },
+                };
+                if current.end {
+                    return Some(length);
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns an iterator over every key within the trie that is a prefix of
+    /// `input`, from shortest to longest.
+    pub fn prefixes_of(&self, input: &'a [u8]) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.prefix_lengths(input)
+            .map(|length| &input[..length])
+    }
+
+    /// Returns the longest key within the trie that is a prefix of `input`.
+    pub fn longest_prefix_of(&self, input: &'a [u8]) -> Option<&'a [u8]> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns an iterator over every key within the trie that is a prefix of
+    /// `input`, from shortest to longest.
+    ///
+    /// Keys that end part of the way through a [`char`] of `input` are
+    /// skipped.
+    pub fn prefixes_of_str(&self, input: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.prefix_lengths(input.as_bytes())
+            .filter(|length| input.is_char_boundary(*length))
+            .map(|length| &input[..length])
+    }
+
+    /// Returns the longest key within the trie that is a prefix of `input`.
+    ///
+    /// See [`MappedTrie::prefixes_of_str`].
+    pub fn longest_prefix_of_str(&self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of_str(input).last()
+    }
+
+    /// Returns an iterator over every key in the trie, in lexicographic order.
+    pub fn iter(&self) -> MappedIter<'a> {
+        MappedIter::new(*self, self.node(HEADER), Vec::new())
+    }
+
+    /// Returns an iterator over every key in the trie that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: impl AsRef<[u8]>) -> MappedIter<'a> {
+        let prefix = prefix.as_ref();
+        MappedIter::new(*self, self.walk(prefix), prefix.to_vec())
+    }
+}
+
+/// Iterates over every key below a node of a [`MappedTrie`], in
+/// lexicographic order.
+pub struct MappedIter<'a> {
+    trie: MappedTrie<'a>,
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may be the end of a key itself.
+    start: Option<Node<'a>>,
+    stack: Vec<(u8, usize, usize)>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> MappedIter<'a> {
+    fn new(trie: MappedTrie<'a>, node: Option<Node<'a>>, prefix: Vec<u8>) -> Self {
+        Self {
+            trie,
+            start: node,
+            stack: Vec::new(),
+            buffer: prefix,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack in reverse order, so that
+    /// the smallest child is popped first.
+    fn push_children(&mut self, node: &Node<'a>, depth: usize) {
+        for (index, byte) in node.labels.iter().enumerate().rev() {
+            if let Some(child) = node.child_at(index) {
+                self.stack.push((*byte, child, depth));
+            }
+        }
+    }
+}
+
+impl Iterator for MappedIter<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(&start, self.buffer.len());
+            if start.end {
+                return Some(self.buffer.clone());
+            }
+        }
+
+        while let Some((byte, offset, depth)) = self.stack.pop() {
+            let Some(node) = self.trie.node(offset) else {
+                continue;
+            };
+            // If we're backtracking, truncate the prefix to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.push(byte);
+            self.push_children(&node, depth + 1);
+
+            if node.end {
+                return Some(self.buffer.clone());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::Trie;
+
+    fn write(trie: &Trie<char>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        trie.write_mapped(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_lookups() {
+        let keys = ["", "a", "ab", "abc", "b", "banana", "band", "日本", "日本語"];
+        let bytes = write(&Trie::from_iter(keys.map(str::chars)));
+        let trie = MappedTrie::new(&bytes).unwrap();
+        assert_eq!(trie.len(), keys.len());
+
+        for key in keys {
+            assert!(trie.contains(key), "{key}");
+        }
+        for key in ["ba", "bandana", "c", "日"] {
+            assert!(!trie.contains(key), "{key}");
+        }
+        assert!(trie.starts_with("ban"));
+        assert!(trie.starts_with([0xe6]));
+        assert!(!trie.starts_with("x"));
+
+        assert_eq!(trie.longest_prefix_of(b"abcd"), Some(&b"abc"[..]));
+        assert_eq!(trie.prefixes_of(b"abx").collect::<Vec<_>>(), [&b""[..], b"a", b"ab"]);
+        assert_eq!(trie.longest_prefix_of_str("日本語です"), Some("日本語"));
+
+        let found: Vec<String> = trie
+            .iter()
+            .map(|key| String::from_utf8(key).unwrap())
+            .collect();
+        assert_eq!(found, keys);
+        let found: Vec<Vec<u8>> = trie.iter_prefix("ban").collect();
+        assert_eq!(found, [&b"banana"[..], b"band"]);
+        assert_eq!(trie.iter_prefix("x").count(), 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let bytes = write(&Trie::new());
+        let trie = MappedTrie::new(&bytes).unwrap();
+        assert!(trie.is_empty());
+        assert!(!trie.contains(""));
+        assert!(!trie.starts_with(""));
+        assert_eq!(trie.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let bytes = write(&Trie::from_iter(["a"].map(str::chars)));
+        assert!(MappedTrie::new(&bytes[..HEADER]).is_err());
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(MappedTrie::new(&corrupt).err().unwrap().kind(), io::ErrorKind::InvalidData);
+
+        let mut corrupt = bytes.clone();
+        corrupt[4] = 2;
+        assert!(MappedTrie::new(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_nodes() {
+        // Truncated nodes are treated as missing:
+        let bytes = write(&Trie::from_iter(["abc", "abd"].map(str::chars)));
+        let trie = MappedTrie::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(trie.contains("abc"));
+        assert!(!trie.contains("abd"));
+
+        // A child pointing back at its parent is ignored, rather than looping
+        // forever:
+        let mut corrupt = bytes.clone();
+        let offset = HEADER + NODE_HEADER + 1;
+        corrupt[offset..offset + 4].copy_from_slice(&(HEADER as u32).to_le_bytes());
+        let trie = MappedTrie::new(&corrupt).unwrap();
+        assert!(!trie.starts_with("a"));
+        assert_eq!(trie.iter().count(), 0);
+    }
+}
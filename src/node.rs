@@ -1,11 +1,7 @@
 use std::{
     borrow::Borrow,
-    collections::HashMap,
-    hash::{
-        BuildHasher,
-        Hash,
-        RandomState,
-    },
+    hash::RandomState,
+    ptr::NonNull,
 };
 
 #[cfg(feature = "serde")]
@@ -15,27 +11,74 @@ use serde::{
     ser::SerializeStruct,
 };
 
-use crate::iter::TrieIter;
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    iter::{PrefixesOf, TrieIter, TrieMapIter},
+    map::InvalidTrie,
+};
 
 /// A node within a trie.
-#[derive(Default)]
-pub struct TrieNode<T, H = RandomState> {
+///
+/// Each node optionally stores a value of type `V`. A node that stores a value
+/// marks the end of a key within the trie. For plain tries that only track
+/// membership, `V` is `()`.
+///
+/// Children are stored in the container chosen by `H`. Any [`BuildHasher`]
+/// stores them in a [`HashMap`] using that hasher, and the
+/// [`children`](crate::children) module provides other containers.
+///
+/// [`BuildHasher`]: std::hash::BuildHasher
+/// [`HashMap`]: std::collections::HashMap
+pub struct TrieNode<T, H = RandomState, V = ()>
+where
+    H: ChildStorage<T>,
+{
     /// Child nodes for each character.
-    pub(super) children: HashMap<T, Self, H>,
-    /// Tracks if the current character is the end of a word.
-    pub(super) end_of_value: bool,
+    pub(super) children: H::Children<Self>,
+    /// The value stored at this node, if the node is the end of a key.
+    pub(super) value: Option<V>,
+    /// The number of values stored at or below this node.
+    #[cfg(feature = "counts")]
+    pub(super) count: usize,
+    /// The greatest value stored at or below this node. This is only kept up
+    /// to date by a [`WeightedTrie`](crate::weighted::WeightedTrie), and is
+    /// [`None`] for every other trie.
+    #[cfg(feature = "weights")]
+    pub(super) max: Option<V>,
 }
 
-impl<T, H> TrieNode<T, H>
+impl<T, V, H> Default for TrieNode<T, H, V>
 where
-    T: Hash + Eq,
-    H: BuildHasher + Default,
+    H: ChildStorage<T>,
+{
+    fn default() -> Self {
+        Self {
+            children: Default::default(),
+            value: None,
+            #[cfg(feature = "counts")]
+            count: 0,
+            #[cfg(feature = "weights")]
+            max: None,
+        }
+    }
+}
+
+impl<T, V, H> TrieNode<T, H, V>
+where
+    H: ChildStorage<T>,
 {
     /// Creates a new empty [`TrieNode`].
     pub fn empty() -> Self {
         Self {
-            children: HashMap::with_hasher(Default::default()),
-            end_of_value: false,
+            children: Default::default(),
+            value: None,
+            #[cfg(feature = "counts")]
+            count: 0,
+            #[cfg(feature = "weights")]
+            max: None,
         }
     }
 
@@ -52,12 +95,20 @@ where
     /// Clears the [`TrieNode`].
     pub(super) fn clear(&mut self) {
         self.children.clear();
-        self.end_of_value = false;
+        self.value = None;
+        #[cfg(feature = "counts")]
+        {
+            self.count = 0;
+        }
+        #[cfg(feature = "weights")]
+        {
+            self.max = None;
+        }
     }
 
     /// Returns an immutable reference to the child [`TrieNode`] for the given
     /// `value`.
-    /// 
+    ///
     /// If no child node is found, this function returns [`None`].
     pub fn get(&self, value: &T) -> Option<&Self> {
         self
@@ -65,8 +116,18 @@ where
             .get(value)
     }
 
+    /// Returns a mutable reference to the child [`TrieNode`] for the given
+    /// `value`.
+    ///
+    /// If no child node is found, this function returns [`None`].
+    pub(super) fn get_mut(&mut self, value: &T) -> Option<&mut Self> {
+        self
+            .children
+            .get_mut(value)
+    }
+
     /// Gets or creates a child node for a given character.
-    /// 
+    ///
     /// This function assumes that the value passed into it is a lowercase
     /// alphabetic English character.
     #[inline]
@@ -74,16 +135,137 @@ where
     pub(super) fn get_or_create(&mut self, value: T) -> &mut Self {
         self
             .children
-            .entry(value)
-            .or_insert_with(Self::empty)
+            .get_or_insert_with(value, Self::empty)
+    }
+
+    /// Walks from this node along the path described by `iter`.
+    ///
+    /// Returns the [`TrieNode`] at the end of the path, or [`None`] if the path
+    /// leaves the trie.
+    pub(super) fn walk<E>(&self, iter: impl IntoIterator<Item = E>) -> Option<&Self>
+    where
+        E: Borrow<T>,
+    {
+        let mut current = self;
+        for element in iter {
+            current = current.get(element.borrow())?;
+        }
+        Some(current)
+    }
+
+    /// Mutable variant of [`TrieNode::walk`].
+    pub(super) fn walk_mut<E>(&mut self, iter: impl IntoIterator<Item = E>) -> Option<&mut Self>
+    where
+        E: Borrow<T>,
+    {
+        let mut current = self;
+        for element in iter {
+            current = current.get_mut(element.borrow())?;
+        }
+        Some(current)
+    }
+
+    /// Returns an iterator over every key at or below this node that is a
+    /// prefix of `input`. See [`PrefixesOf`].
+    pub fn iter_prefixes_of<I>(&self, input: I) -> PrefixesOf<'_, T, V, H, I::IntoIter>
+    where
+        I: IntoIterator,
+    {
+        PrefixesOf::new(self, input.into_iter())
+    }
+
+    /// Returns `true` if at least one value is stored at or below this node.
+    pub(super) fn has_values(&self) -> bool {
+        // Branches that no longer lead to a value are always pruned, so any
+        // child implies a value further down:
+        self.value.is_some() || !self.is_empty()
+    }
+
+    /// Counts the values stored at or below this node.
+    #[cfg(feature = "counts")]
+    pub(super) fn count_values(&self) -> usize {
+        self.count
+    }
+
+    /// Counts the values stored at or below this node.
+    #[cfg(not(feature = "counts"))]
+    pub(super) fn count_values(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            if node.value.is_some() {
+                count += 1;
+            }
+            stack.extend(node.children.values());
+        }
+        count
+    }
+
+    /// Checks that every node below this one stores a value or has children,
+    /// and with the `counts` feature that every count is correct.
+    ///
+    /// Returns the number of values stored at or below this node.
+    pub(super) fn validate(&self) -> Result<usize, InvalidTrie> {
+        let mut values = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            if node.value.is_some() {
+                values += 1;
+            } else if node.is_empty() && !std::ptr::eq(node, self) {
+                return Err(InvalidTrie::DeadBranch);
+            }
+            // Each count only needs checking against the counts of its
+            // children, since those are checked in turn:
+            #[cfg(feature = "counts")]
+            {
+                let expected = usize::from(node.value.is_some()) + node.children
+                    .values()
+                    .map(|child| child.count)
+                    .sum::<usize>();
+                if node.count != expected {
+                    return Err(InvalidTrie::NodeCount);
+                }
+            }
+            stack.extend(node.children.values());
+        }
+        Ok(values)
+    }
+
+    /// Counts the nodes at or below this node, including this node.
+    pub(super) fn count_nodes(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values());
+        }
+        count
+    }
+
+    /// Inserts a branch of children into this [`TrieNode`], storing `value`
+    /// at the end of the branch.
+    ///
+    /// Returns the value previously stored at the end of the branch, if any.
+    pub(super) fn insert_branch(&mut self, mut iter: impl Iterator<Item = T>, value: V) -> Option<V> {
+        let previous = match iter.next() {
+            Some(element) => self.get_or_create(element).insert_branch(iter, value),
+            None => self.value.replace(value),
+        };
+
+        // Every node along the branch gains a value if the key is new:
+        #[cfg(feature = "counts")]
+        if previous.is_none() {
+            self.count += 1;
+        }
+        previous
     }
 
     /// Removes a branch of children from this [`TrieNode`].
-    /// 
-    /// Returns `true` if the branch was successfully removed; otherwise, if no
-    /// child branch existed for the given `value`, this function returns
-    /// `false`.
-    pub(super) fn remove_branch<E>(&mut self, iter: impl Iterator<Item = E>) -> bool
+    ///
+    /// Returns the value stored at the end of the branch if it was
+    /// successfully removed; otherwise, if no value existed for the given
+    /// branch, this function returns [`None`].
+    pub(super) fn remove_branch<E>(&mut self, iter: impl Iterator<Item = E>) -> Option<V>
     where
         E: Borrow<T>,
     {
@@ -98,15 +280,15 @@ where
     }
 
     /// Returns `true` if this node can be safely removed from its parent.
-    fn can_remove(&self) -> bool {
-        !self.end_of_value && self.is_empty()
+    pub(super) fn can_remove(&self) -> bool {
+        self.value.is_none() && self.is_empty()
     }
 
     fn remove_internal<E>(
         &mut self,
         mut iter: impl Iterator<Item = E>,
         remove_fn: &impl Fn(&mut Self, E),
-    ) -> bool
+    ) -> Option<V>
     where
         E: Borrow<T>,
     {
@@ -118,71 +300,336 @@ where
                     Some(next_node) => {
                         // We found the next node this element points to, we
                         // should try remove the remaining branch from it and
-                        // return the success state:
-                        let is_removed = next_node.remove_internal(iter, remove_fn);
+                        // return the removed value:
+                        let removed = next_node.remove_internal(iter, remove_fn);
                         remove_fn(self, element);
-                        is_removed
+                        #[cfg(feature = "counts")]
+                        if removed.is_some() {
+                            self.count -= 1;
+                        }
+                        removed
                     },
                     None => {
                         // There was no next node, this value therefore doesn't
                         // exist in the node.
-                        false
+                        None
                     },
                 }
             },
             None => {
                 // We have reached the end of the iterator, therefore we must be
-                // at the end of the value. If this current node is the end of a
-                // value, we have successfully found the end and can return true
-                // to indicate the value was removed. We need to ensure this
-                // node is no longer marked as the end of a value.
-                let is_end_of_value = self.end_of_value;
-                if is_end_of_value {
-                    self.end_of_value = false;
+                // at the end of the value. Taking the value both returns it to
+                // the caller and ensures this node is no longer marked as the
+                // end of a value.
+                let removed = self.value.take();
+                #[cfg(feature = "counts")]
+                if removed.is_some() {
+                    self.count -= 1;
+                }
+                removed
+            }
+        }
+    }
+}
+
+#[cfg(feature = "counts")]
+impl<T, V, H> TrieNode<T, H, V>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the `n`th key and value at or below this node, in
+    /// lexicographic order.
+    ///
+    /// Each step down the trie skips whole subtrees using their counts, so
+    /// this only visits the nodes along the returned key.
+    pub(super) fn nth(&self, mut n: usize) -> Option<(Vec<T>, &V)> {
+        if n >= self.count {
+            return None;
+        }
+
+        let mut key = Vec::new();
+        let mut current = self;
+        loop {
+            // A key sorts before every key that extends it:
+            if let Some(value) = &current.value {
+                if n == 0 {
+                    return Some((key, value));
                 }
-                is_end_of_value
+                n -= 1;
             }
+
+            let mut children: Vec<_> = current.children.iter().collect();
+            children.sort_unstable_by_key(|(element, _)| **element);
+            let (element, child) = children
+                .into_iter()
+                .find(|(_, child)| {
+                    if n < child.count {
+                        true
+                    } else {
+                        n -= child.count;
+                        false
+                    }
+                })?;
+            key.push(*element);
+            current = child;
         }
     }
 
+    /// Returns the number of keys at or below this node that sort before the
+    /// key described by `iter`, in lexicographic order.
+    pub(super) fn rank<E>(&self, iter: impl IntoIterator<Item = E>) -> usize
+    where
+        E: Borrow<T>,
+    {
+        let mut rank = 0;
+        let mut current = self;
+        for element in iter {
+            let element = element.borrow();
+
+            // The current key is a proper prefix, so it sorts first. So does
+            // every subtree under a smaller element:
+            if current.value.is_some() {
+                rank += 1;
+            }
+            rank += current
+                .children
+                .iter()
+                .filter(|(other, _)| *other < element)
+                .map(|(_, child)| child.count)
+                .sum::<usize>();
+
+            match current.get(element) {
+                Some(next) => current = next,
+                None => return rank,
+            }
+        }
+        rank
+    }
+}
+
+impl<T, V, H> TrieNode<T, H, V>
+where
+    H: ChildStorage<T>,
+{
     /// Returns `true` if this node forms the end of a word; otherwise returns
     /// `false`.
     pub fn is_end_of_word(&self) -> bool {
-        self.end_of_value
+        self.value.is_some()
+    }
+
+    /// Returns an immutable reference to the value stored at this node.
+    ///
+    /// If this node is not the end of a key, this function returns [`None`].
+    pub fn value(&self) -> Option<&V> {
+        self.value.as_ref()
     }
 }
 
-impl<T, H> TrieNode<T, H>
+impl<T, V, H> TrieNode<T, H, V>
 where
-    T: Eq + Copy,
+    T: Copy,
+    H: ChildStorage<T>,
 {
-    /// Returns an iterator over the [`Trie`].
-    pub fn iter(&self) -> TrieIter<'_, T, H> {
+    /// Returns an iterator over every key below this [`TrieNode`].
+    pub fn iter(&self) -> TrieIter<'_, T, H, V> {
         TrieIter::new(self)
     }
+
+    /// Returns an iterator over every key and value below this [`TrieNode`].
+    pub fn iter_values(&self) -> TrieMapIter<'_, T, V, H> {
+        TrieMapIter::new(self)
+    }
+}
+
+/// Pointers to every node along a path from a root [`TrieNode`].
+///
+/// This lets entries and views update or prune the nodes above the one they
+/// point to without walking the trie a second time. Every pointer is derived
+/// from the previous one, so they all stem from a single mutable borrow of the
+/// root. The owner of the path must hold that borrow for as long as the path
+/// is used.
+pub(super) struct NodePath<T, V, H>(Vec<NonNull<TrieNode<T, H, V>>>)
+where
+    H: ChildStorage<T>;
+
+impl<T, V, H> NodePath<T, V, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Walks from `root` along `key` as far as the existing nodes allow.
+    ///
+    /// The path always contains the root, followed by one node for each
+    /// element of `key` that was found.
+    pub(super) fn walk(root: &mut TrieNode<T, H, V>, key: &[T]) -> Self {
+        let mut path = Vec::with_capacity(key.len() + 1);
+        let mut current = NonNull::from(root);
+        path.push(current);
+        for element in key {
+            // SAFETY: `current` points to a live node that is exclusively
+            // borrowed through `root`, and no other reference to it exists.
+            match unsafe { current.as_mut() }.get_mut(element) {
+                Some(next) => {
+                    current = NonNull::from(next);
+                    path.push(current);
+                },
+                None => break,
+            }
+        }
+        Self(path)
+    }
+
+    /// Returns the number of nodes on the path, including the root.
+    pub(super) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns a pointer to the last node on the path.
+    pub(super) fn last(&self) -> NonNull<TrieNode<T, H, V>> {
+        *self.0.last().unwrap()
+    }
+
+    /// Creates a node below the last node on the path for each element of
+    /// `rest`, adding each of them to the path.
+    ///
+    /// # Safety
+    /// The borrow of the root must still be held, and no other reference to
+    /// any node on the path may be live.
+    pub(super) unsafe fn extend(&mut self, rest: impl IntoIterator<Item = T>) {
+        let mut current = self.last();
+        for element in rest {
+            current = NonNull::from(unsafe { current.as_mut() }.get_or_create(element));
+            self.0.push(current);
+        }
+    }
+
+    /// Calls `f` on each of the first `end` nodes on the path, starting from
+    /// the root.
+    ///
+    /// # Safety
+    /// The borrow of the root must still be held, and no other reference to
+    /// any node on the path may be live.
+    #[cfg(feature = "counts")]
+    pub(super) unsafe fn update(&self, end: usize, mut f: impl FnMut(&mut TrieNode<T, H, V>)) {
+        for mut node in self.0[..end].iter().copied() {
+            f(unsafe { node.as_mut() });
+        }
+    }
+
+    /// Calls `f` on every node on the path, starting from the deepest, so each
+    /// node is visited after the node below it.
+    ///
+    /// # Safety
+    /// The borrow of the root must still be held, and no other reference to
+    /// any node on the path may be live.
+    #[cfg(feature = "weights")]
+    pub(super) unsafe fn update_up(&self, mut f: impl FnMut(&mut TrieNode<T, H, V>)) {
+        for mut node in self.0.iter().rev().copied() {
+            f(unsafe { node.as_mut() });
+        }
+    }
+
+    /// Removes every node from the bottom of the path that no longer leads to
+    /// a value, stopping at the first node that does.
+    ///
+    /// `key` must be the key the path was walked with. The root is never
+    /// removed. Pointers to removed nodes are dangling afterwards, so the path
+    /// must not be used again.
+    ///
+    /// # Safety
+    /// The borrow of the root must still be held, and no other reference to
+    /// any node on the path may be live.
+    pub(super) unsafe fn prune(&self, key: &[T]) {
+        for index in (1..self.0.len()).rev() {
+            // Each node is only removed from its parent after its last use
+            // here, so every node read is still live:
+            let node = unsafe { self.0[index].as_ref() };
+            if !node.can_remove() {
+                break;
+            }
+            let mut parent = self.0[index - 1];
+            let result = unsafe { parent.as_mut() }.children.remove(&key[index - 1]);
+            debug_assert!(matches!(result, Some(..)));
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
-impl<'de, T, H> Deserialize<'de> for TrieNode<T, H>
+impl<'de, T, V, H> Deserialize<'de> for TrieNode<T, H, V>
 where
-    T: std::hash::Hash + Eq + Deserialize<'de>,
-    H: std::hash::BuildHasher + Default,
+    T: Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: ChildStorage<T>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         // TrieNode visitor used for deserialization.
-        struct TrieNodeVisitor<T, H> {
-            marker: std::marker::PhantomData<(T, H)>,
+        struct TrieNodeVisitor<T, V, H> {
+            marker: std::marker::PhantomData<(T, V, H)>,
+        }
+
+        // Reads the children of a node into whichever container `H` uses.
+        struct ChildrenVisitor<T, V, H> {
+            marker: std::marker::PhantomData<(T, V, H)>,
         }
 
-        impl<'de, T, H> serde::de::Visitor<'de> for TrieNodeVisitor<T, H>
+        impl<'de, T, V, H> serde::de::DeserializeSeed<'de> for ChildrenVisitor<T, V, H>
         where
-            T: std::hash::Hash + Eq + Deserialize<'de>,
-            H: std::hash::BuildHasher + Default,
+            T: Deserialize<'de>,
+            V: Deserialize<'de>,
+            H: ChildStorage<T>,
         {
-            type Value = TrieNode<T, H>;
+            type Value = H::Children<TrieNode<T, H, V>>;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_map(self)
+            }
+        }
+
+        impl<'de, T, V, H> serde::de::Visitor<'de> for ChildrenVisitor<T, V, H>
+        where
+            T: Deserialize<'de>,
+            V: Deserialize<'de>,
+            H: ChildStorage<T>,
+        {
+            type Value = H::Children<TrieNode<T, H, V>>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of child nodes")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: serde::de::MapAccess<'de>,
+            {
+                let mut children = Self::Value::default();
+                while let Some((element, child)) = map.next_entry::<T, TrieNode<T, H, V>>()? {
+                    // Children are read before their parent, so a child
+                    // without values is the end of a dead branch. It is pruned
+                    // here, which may leave its parent to be pruned in turn:
+                    if !child.has_values() {
+                        continue;
+                    }
+                    if children.insert(element, child).is_some() {
+                        return Err(serde::de::Error::custom("trie node has a duplicate child"));
+                    }
+                }
+                Ok(children)
+            }
+        }
+
+        impl<'de, T, V, H> serde::de::Visitor<'de> for TrieNodeVisitor<T, V, H>
+        where
+            T: Deserialize<'de>,
+            V: Deserialize<'de>,
+            H: ChildStorage<T>,
+        {
+            type Value = TrieNode<T, H, V>;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
                 formatter.write_str("struct TrieNode")
@@ -194,6 +641,7 @@ where
             {
                 let mut children = None;
                 let mut end_of_value = None;
+                let mut value = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -201,7 +649,9 @@ where
                             if children.is_some() {
                                 return Err(serde::de::Error::duplicate_field("children"));
                             }
-                            children = Some(map.next_value()?);
+                            children = Some(map.next_value_seed(ChildrenVisitor::<T, V, H> {
+                                marker: std::marker::PhantomData,
+                            })?);
                         }
                         "end_of_value" => {
                             if end_of_value.is_some() {
@@ -209,10 +659,16 @@ where
                             }
                             end_of_value = Some(map.next_value()?);
                         }
+                        "value" => {
+                            if value.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value = Some(map.next_value()?);
+                        }
                         _ => {
                             return Err(serde::de::Error::unknown_field(
                                 key,
-                                &["children", "end_of_value"],
+                                &["children", "end_of_value", "value"],
                             ));
                         }
                     }
@@ -221,16 +677,41 @@ where
                 let children = children.unwrap_or_default();
                 let end_of_value = end_of_value.unwrap_or_default();
 
+                // Nodes written by a value-less trie only record
+                // `end_of_value`. In that case the value is read from a unit,
+                // which succeeds for `()` and fails for any real value type:
+                let value = match (end_of_value, value) {
+                    (true, Some(value)) => Some(value),
+                    (true, None) => Some(V::deserialize(
+                        serde::de::value::UnitDeserializer::new(),
+                    )?),
+                    (false, _) => None,
+                };
+
+                // Counts are not serialized, they are rebuilt from the
+                // children instead:
+                #[cfg(feature = "counts")]
+                let count = value.is_some() as usize + children
+                    .values()
+                    .map(|child| child.count)
+                    .sum::<usize>();
+
+                // Maxima can't be rebuilt without ordering the values, so a
+                // weighted trie rebuilds them once every node is read:
                 Ok(TrieNode {
                     children,
-                    end_of_value,
+                    value,
+                    #[cfg(feature = "counts")]
+                    count,
+                    #[cfg(feature = "weights")]
+                    max: None,
                 })
             }
         }
 
         deserializer.deserialize_struct(
             "TrieNode",
-            &["children", "end_of_value"],
+            &["children", "end_of_value", "value"],
             TrieNodeVisitor {
                 marker: std::marker::PhantomData,
             },
@@ -239,27 +720,58 @@ where
 }
 
 #[cfg(feature = "serde")]
-impl<T, H> Serialize for TrieNode<T, H>
+impl<T, V, H> Serialize for TrieNode<T, H, V>
 where
-    T: std::hash::Hash + Eq + Serialize,
-    H: std::hash::BuildHasher,
+    T: Serialize,
+    V: Serialize,
+    H: ChildStorage<T>,
 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
+        // Zero-sized values, such as the `()` stored by a `Trie`, carry no
+        // data. Only `end_of_value` is written for them, so these nodes keep
+        // the form they had before values could be stored:
+        let values = std::mem::size_of::<V>() != 0;
         let mut state = serializer.serialize_struct(
             "TrieNode",
-            2,
+            2 + usize::from(values),
         )?;
         state.serialize_field(
             "children",
-            &self.children,
+            &SerializeChildren(self),
         )?;
         state.serialize_field(
             "end_of_value",
-            &self.end_of_value,
+            &self.value.is_some(),
         )?;
+        match &self.value {
+            Some(value) if values => state.serialize_field("value", value)?,
+            _ => state.skip_field("value")?,
+        }
         state.end()
     }
 }
+
+/// Serializes the children of a [`TrieNode`] as a map, whichever container
+/// they are stored in.
+#[cfg(feature = "serde")]
+struct SerializeChildren<'a, T, V, H>(&'a TrieNode<T, H, V>)
+where
+    H: ChildStorage<T>;
+
+#[cfg(feature = "serde")]
+impl<T, V, H> Serialize for SerializeChildren<'_, T, V, H>
+where
+    T: Serialize,
+    V: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.0.children.iter())
+    }
+}
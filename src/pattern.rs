@@ -0,0 +1,215 @@
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    node::TrieNode,
+};
+
+/// A single position within a pattern.
+pub enum Token<P> {
+    /// Matches exactly one element for which the predicate returns `true`.
+    One(P),
+    /// Matches any run of elements, including an empty one.
+    Many,
+}
+
+/// Returns the positions reached by the empty key, before any element is
+/// matched.
+pub(super) fn start_positions<P>(pattern: &[Token<P>]) -> Vec<usize> {
+    let mut positions = vec![0];
+    close(pattern, &mut positions);
+    positions
+}
+
+/// Adds every position that can be reached from `positions` by matching
+/// [`Token::Many`] against an empty run.
+fn close<P>(pattern: &[Token<P>], positions: &mut Vec<usize>) {
+    let mut index = 0;
+    while index < positions.len() {
+        let position = positions[index];
+        if matches!(pattern.get(position), Some(Token::Many)) && !positions.contains(&(position + 1)) {
+            positions.push(position + 1);
+        }
+        index += 1;
+    }
+}
+
+/// Returns the positions reached by matching `element` from `positions`.
+pub(super) fn step<T, P>(pattern: &[Token<P>], positions: &[usize], element: &T) -> Vec<usize>
+where
+    P: Fn(&T) -> bool,
+{
+    let mut next = Vec::with_capacity(positions.len());
+    for &position in positions {
+        let reached = match pattern.get(position) {
+            Some(Token::One(predicate)) if predicate(element) => position + 1,
+            Some(Token::Many) => position,
+            _ => continue,
+        };
+        if !next.contains(&reached) {
+            next.push(reached);
+        }
+    }
+    close(pattern, &mut next);
+    next
+}
+
+/// Returns `true` if a key that reached `positions` matches the whole
+/// pattern.
+pub(super) fn is_match<P>(pattern: &[Token<P>], positions: &[usize]) -> bool {
+    positions.contains(&pattern.len())
+}
+
+/// An element on the stack of a [`PatternMatches`] iterator: the last element
+/// of a key, the node it leads to, the depth of the node, and the pattern
+/// positions reached by the key.
+type Frame<'a, T, V, H> = (T, &'a TrieNode<T, H, V>, usize, Vec<usize>);
+
+/// Iterates over every key below a [`TrieNode`] that matches a pattern of
+/// [`Token`]s.
+///
+/// The pattern is matched while walking down the trie, tracking every pattern
+/// position the current key could have reached. A subtree is skipped as soon
+/// as no position is left, so only the parts of the trie that can still match
+/// are visited. Each matching key is yielded once, in an arbitrary order.
+pub struct PatternMatches<'a, T, V, H, P>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may store a value of its own.
+    start: Option<&'a TrieNode<T, H, V>>,
+    stack: Vec<Frame<'a, T, V, H>>,
+    buffer: Vec<T>,
+    pattern: Vec<Token<P>>,
+}
+
+impl<'a, T, V, H, P> PatternMatches<'a, T, V, H, P>
+where
+    T: Copy,
+    P: Fn(&T) -> bool,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`PatternMatches`] over a root [`TrieNode`].
+    pub fn new(root: &'a TrieNode<T, H, V>, pattern: Vec<Token<P>>) -> Self {
+        Self {
+            start: Some(root),
+            stack: Vec::new(),
+            buffer: Vec::new(),
+            pattern,
+        }
+    }
+
+    /// Pushes the children of `node` that can still match onto the stack.
+    fn push_children(&mut self, node: &'a TrieNode<T, H, V>, depth: usize, positions: &[usize]) {
+        for (key, child) in node.children.iter() {
+            let next = step(&self.pattern, positions, key);
+            if !next.is_empty() {
+                self.stack.push((*key, child, depth, next));
+            }
+        }
+    }
+}
+
+impl<'a, T, V, H, P> Iterator for PatternMatches<'a, T, V, H, P>
+where
+    T: Copy,
+    P: Fn(&T) -> bool,
+    H: ChildStorage<T>,
+{
+    type Item = (Vec<T>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The empty key is stored on the root itself:
+        if let Some(start) = self.start.take() {
+            let positions = start_positions(&self.pattern);
+            self.push_children(start, 0, &positions);
+            if let Some(value) = &start.value {
+                if is_match(&self.pattern, &positions) {
+                    return Some((Vec::new(), value));
+                }
+            }
+        }
+
+        while let Some((key, node, depth, positions)) = self.stack.pop() {
+            // If we're backtracking, truncate the prefix to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.push(key);
+
+            self.push_children(node, depth + 1, &positions);
+
+            if let Some(value) = &node.value {
+                if is_match(&self.pattern, &positions) {
+                    return Some((self.buffer.clone(), value));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    use super::*;
+
+    type Predicate = fn(&u8) -> bool;
+
+    fn matches(trie: &Trie<u8>, pattern: Vec<Token<Predicate>>) -> Vec<Vec<u8>> {
+        let mut found: Vec<Vec<u8>> = trie.matches(pattern).collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_predicates() {
+        let mut trie = Trie::new();
+        for key in [&[1, 2, 3][..], &[1, 4, 3], &[1, 2], &[2, 2, 3], &[1, 2, 3, 4], &[]] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+
+        let one: Predicate = |element| *element == 1;
+        let even: Predicate = |element| element % 2 == 0;
+        let any: Predicate = |_| true;
+
+        assert_eq!(matches(&trie, vec![Token::One(one), Token::One(even), Token::One(any)]), [
+            vec![1, 2, 3],
+            vec![1, 4, 3],
+        ]);
+        assert_eq!(matches(&trie, vec![Token::One(any), Token::One(any)]), [vec![1, 2]]);
+        assert!(matches(&trie, vec![Token::One(even), Token::One(one)]).is_empty());
+    }
+
+    #[test]
+    fn test_many() {
+        let mut trie = Trie::new();
+        for key in [&[1, 2, 3][..], &[1, 3], &[1], &[2, 3], &[]] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+
+        let one: Predicate = |element| *element == 1;
+        let three: Predicate = |element| *element == 3;
+
+        // Every key matches a lone run, including the empty key:
+        assert_eq!(matches(&trie, vec![Token::Many]).len(), 5);
+        assert_eq!(matches(&trie, vec![Token::One(one), Token::Many]), [
+            vec![1],
+            vec![1, 2, 3],
+            vec![1, 3],
+        ]);
+        assert_eq!(matches(&trie, vec![Token::Many, Token::One(three)]), [
+            vec![1, 2, 3],
+            vec![1, 3],
+            vec![2, 3],
+        ]);
+
+        // Keys that can match in several ways are only yielded once:
+        assert_eq!(
+            matches(&trie, vec![Token::Many, Token::Many, Token::One(three), Token::Many]),
+            [vec![1, 2, 3], vec![1, 3], vec![2, 3]],
+        );
+        assert_eq!(matches(&trie, vec![]), [Vec::<u8>::new()]);
+    }
+}
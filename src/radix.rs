@@ -0,0 +1,2202 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    hash::{
+        Hash,
+        RandomState,
+    },
+    io::{
+        self,
+        Read,
+        Write,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+    ser::SerializeStruct,
+};
+
+use crate::{
+    aho_corasick::{
+        AhoCorasick,
+        MatchKind,
+    },
+    binary,
+    children::{
+        ChildStorage,
+        Children,
+    },
+    dawg::Dawg,
+    double_array::{
+        DoubleArray,
+        Symbol,
+    },
+    fuzzy::EditRows,
+    louds::Louds,
+    map::InvalidTrie,
+    mapped::MappedTrie,
+    node::TrieNode,
+    pattern::{
+        self,
+        Token,
+    },
+};
+
+/// A node within a [`RadixTrie`].
+///
+/// Each node is reached by an edge labelled with a run of elements, rather
+/// than a single element. The label of every node except the root is never
+/// empty, and the first element of a label is the key of the node within its
+/// parent's children.
+///
+/// Children are stored in the container chosen by `H`, in the same way as the
+/// children of a [`TrieNode`].
+pub struct RadixNode<T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// The elements along the edge leading to this node.
+    label: Box<[T]>,
+    /// Child nodes, keyed by the first element of their label.
+    children: H::Children<Self>,
+    /// Set if this node is the end of a key.
+    end: bool,
+    /// The number of keys that end at or below this node.
+    #[cfg(feature = "counts")]
+    count: usize,
+}
+
+impl<T, H> RadixNode<T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`RadixNode`] with no children.
+    fn leaf(label: Box<[T]>, end: bool) -> Self {
+        Self {
+            label,
+            children: Default::default(),
+            end,
+            #[cfg(feature = "counts")]
+            count: usize::from(end),
+        }
+    }
+
+    /// Returns the elements along the edge leading to this node.
+    pub fn label(&self) -> &[T] {
+        &self.label
+    }
+
+    /// Returns the child [`RadixNode`] whose label starts with `value`.
+    ///
+    /// If no child node is found, this function returns [`None`].
+    pub fn get(&self, value: &T) -> Option<&Self> {
+        self.children.get(value)
+    }
+
+    /// Returns `true` if this node forms the end of a key; otherwise returns
+    /// `false`.
+    pub fn is_end_of_word(&self) -> bool {
+        self.end
+    }
+
+    /// Returns the number of child [`RadixNode`]s in this node.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if the [`RadixNode`] contains no child nodes.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns `true` if at least one key ends at or below this node.
+    fn has_values(&self) -> bool {
+        // Nodes that no longer lead to a key are always pruned, so any child
+        // implies a key further down:
+        self.end || !self.is_empty()
+    }
+
+    /// Counts the keys stored at or below this node.
+    #[cfg(feature = "counts")]
+    fn count_values(&self) -> usize {
+        self.count
+    }
+
+    /// Counts the keys stored at or below this node.
+    #[cfg(not(feature = "counts"))]
+    fn count_values(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            if node.end {
+                count += 1;
+            }
+            stack.extend(node.children.values());
+        }
+        count
+    }
+}
+
+impl<T, H> RadixNode<T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Splits the label of this node after `at` elements, moving the rest of
+    /// the label, the children and the end marker into a new child.
+    fn split(&mut self, at: usize) {
+        let tail = Self {
+            label: self.label[at..].into(),
+            children: std::mem::take(&mut self.children),
+            end: std::mem::take(&mut self.end),
+            // Every key below this node is now below the new child:
+            #[cfg(feature = "counts")]
+            count: self.count,
+        };
+        self.label = self.label[..at].into();
+        self.children.insert(tail.label[0], tail);
+    }
+
+    /// Merges the only child of this node into it, joining their labels.
+    fn merge(&mut self) {
+        debug_assert!(!self.end && self.children.len() == 1);
+        let key = *self.children.iter().next().unwrap().0;
+        let child = self.children.remove(&key).unwrap();
+        self.label = self.label.iter().chain(child.label.iter()).copied().collect();
+        self.children = child.children;
+        self.end = child.end;
+    }
+
+    /// Inserts `key` below this node, splitting an edge if the key leaves it
+    /// part way along.
+    ///
+    /// Returns `true` if the key was not already present.
+    fn insert(&mut self, key: &[T]) -> bool {
+        let inserted = match key.first() {
+            None => !std::mem::replace(&mut self.end, true),
+            Some(first) => match self.children.get_mut(first) {
+                Some(child) => {
+                    let common = common_prefix(&child.label, key);
+                    if common < child.label.len() {
+                        child.split(common);
+                    }
+                    child.insert(&key[common..])
+                },
+                None => {
+                    self.children.insert(*first, Self::leaf(key.into(), true));
+                    true
+                },
+            },
+        };
+
+        // Every node along the key gains a value if the key is new:
+        #[cfg(feature = "counts")]
+        if inserted {
+            self.count += 1;
+        }
+        inserted
+    }
+
+    /// Removes `key` from below this node, pruning any node that no longer
+    /// leads to a key and merging any node left with a single child.
+    ///
+    /// Returns `true` if the key was removed.
+    fn remove(&mut self, key: &[T]) -> bool {
+        let removed = match key.first() {
+            None => std::mem::take(&mut self.end),
+            Some(first) => {
+                let Some(child) = self.children.get_mut(first) else {
+                    return false;
+                };
+                if !key.starts_with(&child.label) {
+                    return false;
+                }
+
+                let removed = child.remove(&key[child.label.len()..]);
+                if removed && !child.end {
+                    match child.children.len() {
+                        0 => {
+                            self.children.remove(first);
+                        },
+                        1 => child.merge(),
+                        _ => {},
+                    }
+                }
+                removed
+            },
+        };
+
+        // Every node along the key loses a value:
+        #[cfg(feature = "counts")]
+        if removed {
+            self.count -= 1;
+        }
+        removed
+    }
+
+    /// Follows `key` from this node.
+    ///
+    /// Returns the node whose edge the key ends on, along with the elements of
+    /// its label that follow the end of the key. If the key leaves the trie,
+    /// this function returns [`None`].
+    fn find<E>(&self, key: impl IntoIterator<Item = E>) -> Option<(&Self, &[T])>
+    where
+        E: Borrow<T>,
+    {
+        self.find_from(self.label.len(), key)
+    }
+
+    /// Follows `key` from `offset` elements along the label of this node.
+    ///
+    /// See [`RadixNode::find`].
+    fn find_from<E>(&self, mut offset: usize, key: impl IntoIterator<Item = E>) -> Option<(&Self, &[T])>
+    where
+        E: Borrow<T>,
+    {
+        let mut current = self;
+        for element in key {
+            let element = element.borrow();
+            if offset == current.label.len() {
+                current = current.get(element)?;
+                offset = 1;
+            } else if current.label[offset] == *element {
+                offset += 1;
+            } else {
+                return None;
+            }
+        }
+        Some((current, &current.label[offset..]))
+    }
+
+    /// Expands this node and every node below it into [`TrieNode`]s, with one
+    /// node for each element of each label.
+    ///
+    /// Returns the [`TrieNode`] at the end of the label of this node.
+    fn expand(&self) -> TrieNode<T, H> {
+        let mut node = TrieNode::<T, H>::empty();
+        node.value = self.end.then_some(());
+        #[cfg(feature = "counts")]
+        {
+            node.count = self.count;
+        }
+        for child in self.children.values() {
+            // Build the chain of nodes along the label from the bottom up:
+            let mut expanded = child.expand();
+            for element in child.label[1..].iter().rev() {
+                let mut parent = TrieNode::<T, H>::empty();
+                #[cfg(feature = "counts")]
+                {
+                    parent.count = expanded.count;
+                }
+                parent.children.insert(*element, expanded);
+                expanded = parent;
+            }
+            node.children.insert(child.label[0], expanded);
+        }
+        node
+    }
+
+    /// Compresses `node` and every node below it into [`RadixNode`]s, where
+    /// `label` holds the elements along the edge leading to `node`.
+    ///
+    /// Any chain of nodes that don't store a value and have a single child is
+    /// merged into one edge, and branches without values are dropped.
+    fn compress<V>(mut label: Vec<T>, mut node: &TrieNode<T, H, V>) -> Self {
+        // The root is never merged with its child, since its label is always
+        // empty:
+        while !label.is_empty() && node.value.is_none() && node.len() == 1 {
+            let (element, child) = node.children.iter().next().unwrap();
+            label.push(*element);
+            node = child;
+        }
+
+        let mut compressed = Self::leaf(label.into(), node.value.is_some());
+        for (element, child) in node.children.iter() {
+            if child.has_values() {
+                let child = Self::compress(vec![*element], child);
+                #[cfg(feature = "counts")]
+                {
+                    compressed.count += child.count;
+                }
+                compressed.children.insert(*element, child);
+            }
+        }
+        compressed
+    }
+}
+
+#[cfg(feature = "counts")]
+impl<T, H> RadixNode<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the `n`th key at or below this node, in lexicographic order.
+    ///
+    /// Each step down the trie skips whole subtrees using their counts, so
+    /// this only visits the nodes along the returned key.
+    fn nth(&self, mut n: usize) -> Option<Vec<T>> {
+        if n >= self.count {
+            return None;
+        }
+
+        let mut key = Vec::new();
+        let mut current = self;
+        loop {
+            // A key sorts before every key that extends it:
+            if current.end {
+                if n == 0 {
+                    return Some(key);
+                }
+                n -= 1;
+            }
+
+            let mut children: Vec<_> = current.children.values().collect();
+            children.sort_unstable_by_key(|child| child.label[0]);
+            let child = children
+                .into_iter()
+                .find(|child| {
+                    if n < child.count {
+                        true
+                    } else {
+                        n -= child.count;
+                        false
+                    }
+                })?;
+            key.extend_from_slice(&child.label);
+            current = child;
+        }
+    }
+
+    /// Returns the number of keys at or below this node that sort before
+    /// `key`, in lexicographic order.
+    fn rank(&self, mut key: &[T]) -> usize {
+        let mut rank = 0;
+        let mut current = self;
+        while let Some(first) = key.first() {
+            // The current key is a proper prefix, so it sorts first. So does
+            // every subtree under a smaller element:
+            if current.end {
+                rank += 1;
+            }
+            rank += current
+                .children
+                .iter()
+                .filter(|(other, _)| *other < first)
+                .map(|(_, child)| child.count)
+                .sum::<usize>();
+
+            let Some(child) = current.get(first) else {
+                return rank;
+            };
+
+            // The key may leave the edge part way along, in which case the
+            // whole subtree sorts either before or after it:
+            let common = common_prefix(&child.label, key);
+            if common < child.label.len() {
+                if common < key.len() && child.label[common] < key[common] {
+                    rank += child.count;
+                }
+                return rank;
+            }
+            key = &key[common..];
+            current = child;
+        }
+        rank
+    }
+}
+
+/// Returns the number of elements at the start of `a` and `b` that are equal.
+fn common_prefix<T: Eq>(a: &[T], b: &[T]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Stores a list of keys in a compressed radix (Patricia) trie.
+///
+/// This works like a [`Trie`](crate::trie::Trie), except that any chain of
+/// nodes with a single child is merged into one node whose edge is labelled
+/// with every element along the chain. For example, inserting "test",
+/// "teach" and "testing" creates the following nodes:
+/// te -> ST -> inG
+///       acH
+///
+/// This needs far fewer nodes than a [`Trie`](crate::trie::Trie) when keys
+/// have long unique tails. Edges are split when a key leaves them part way
+/// along, and merged again when keys are removed.
+///
+/// Every method of a [`Trie`](crate::trie::Trie) is available. Conversions
+/// into other representations, such as [`RadixTrie::freeze`] or
+/// [`RadixTrie::write_to`], first expand the edges into the nodes of a
+/// [`Trie`](crate::trie::Trie), so they produce exactly the same output.
+pub struct RadixTrie<T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// Root node that tracks every key within the trie. Its label is always
+    /// empty.
+    root: RadixNode<T, H>,
+    /// Tracks the number of keys in the trie.
+    count: usize,
+}
+
+impl<T, H> Default for RadixTrie<T, H>
+where
+    H: Default,
+    H: ChildStorage<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, H, I> FromIterator<I> for RadixTrie<T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn from_iter<K: IntoIterator<Item = I>>(keys: K) -> Self {
+        let mut trie = Self::new();
+        trie.extend(keys);
+        trie
+    }
+}
+
+impl<T, H, I> Extend<I> for RadixTrie<T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn extend<K: IntoIterator<Item = I>>(&mut self, keys: K) {
+        for key in keys {
+            self.insert(key);
+        }
+    }
+}
+
+impl<T, H> RadixTrie<T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new empty [`RadixTrie`].
+    pub fn new() -> Self {
+        Self {
+            root: RadixNode::leaf(Box::default(), false),
+            count: 0,
+        }
+    }
+
+    /// Returns `true` if the trie is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.root = RadixNode::leaf(Box::default(), false);
+        self.count = 0;
+    }
+
+    /// Returns the number of nodes within the [`RadixTrie`], including the
+    /// root.
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values());
+        }
+        count
+    }
+
+    /// Returns an immutable reference to the root [`RadixNode`].
+    pub fn root(&self) -> &RadixNode<T, H> {
+        &self.root
+    }
+}
+
+impl<T, H> RadixTrie<T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Inserts a value into the trie.
+    ///
+    /// Returns `true` if the value was not already present.
+    pub fn insert(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let key: Vec<T> = iter.into_iter().collect();
+        let inserted = self.root.insert(&key);
+        if inserted {
+            self.count += 1;
+        }
+        inserted
+    }
+
+    /// Removes a value from the trie.
+    ///
+    /// Returns `true` if the value was removed.
+    pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let key: Vec<T> = iter.into_iter().collect();
+        let removed = self.root.remove(&key);
+        if removed {
+            self.count -= 1;
+        }
+        removed
+    }
+
+    /// Returns the [`RadixEntry`] for a value, which can be used to insert or
+    /// remove it.
+    pub fn entry(&mut self, iter: impl IntoIterator<Item = T>) -> RadixEntry<'_, T, H> {
+        let key: Vec<T> = iter.into_iter().collect();
+        if self.contains(key.iter().copied()) {
+            RadixEntry::Occupied(OccupiedRadixEntry {
+                trie: self,
+                key,
+            })
+        } else {
+            RadixEntry::Vacant(VacantRadixEntry {
+                trie: self,
+                key,
+            })
+        }
+    }
+
+    /// Returns `true` if the [`RadixTrie`] contains a value, otherwise returns
+    /// `false`.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.root
+            .find(iter)
+            .is_some_and(|(node, rest)| rest.is_empty() && node.end)
+    }
+
+    /// Returns `true` if any value within the [`RadixTrie`] starts with
+    /// `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.root
+            .find(prefix)
+            .is_some_and(|(node, _)| node.has_values())
+    }
+
+    /// Returns the number of values within the [`RadixTrie`] that start with
+    /// `prefix`.
+    ///
+    /// With the `counts` feature enabled, this only walks the prefix.
+    /// Otherwise every node below the prefix is visited.
+    pub fn count_with_prefix(&self, prefix: impl IntoIterator<Item = T>) -> usize {
+        self.root
+            .find(prefix)
+            .map_or(0, |(node, _)| node.count_values())
+    }
+
+    /// Returns a view of every value within the [`RadixTrie`] that starts
+    /// with `prefix`.
+    ///
+    /// If no value starts with `prefix`, this function returns [`None`].
+    pub fn subtrie(&self, prefix: impl IntoIterator<Item = T>) -> Option<RadixSubTrie<'_, T, H>> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        let (node, rest) = self.root.find(&prefix)?;
+        Some(RadixSubTrie {
+            node,
+            rest,
+            prefix,
+        })
+    }
+
+    /// Returns a mutable view of every value within the [`RadixTrie`] that
+    /// starts with `prefix`.
+    ///
+    /// If no value starts with `prefix`, this function returns [`None`].
+    pub fn subtrie_mut(&mut self, prefix: impl IntoIterator<Item = T>) -> Option<RadixSubTrieMut<'_, T, H>> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        // The view walks the prefix again for each operation, so it only
+        // needs checking here:
+        self.root.find(&prefix)?;
+        Some(RadixSubTrieMut {
+            trie: self,
+            prefix,
+        })
+    }
+
+    /// Returns an iterator over the length of every value within the
+    /// [`RadixTrie`] that is a prefix of `input`, from shortest to longest.
+    pub fn prefixes_of<'a, I>(&'a self, input: I) -> impl Iterator<Item = usize> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: Borrow<T>,
+    {
+        let mut input = input.into_iter();
+        let mut node = Some(&self.root);
+        let mut length = 0;
+        std::iter::from_fn(move || loop {
+            let current = node?;
+            let found = current.end.then_some(length);
+
+            // Step down the trie before yielding, so the iterator is already
+            // positioned for the next call. A child is only reached if the
+            // input follows its whole label:
+            node = input
+                .next()
+                .and_then(|element| current.get(element.borrow()))
+                .filter(|child| {
+                    child.label[1..]
+                        .iter()
+                        .all(|label| input.next().is_some_and(|element| element.borrow() == label))
+                });
+            if let Some(child) = node {
+                length += child.label.len();
+            }
+
+            if found.is_some() {
+                return found;
+            }
+        })
+    }
+
+    /// Returns the length of the longest value within the [`RadixTrie`] that
+    /// is a prefix of `input`.
+    pub fn longest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<usize> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns the length of the shortest value within the [`RadixTrie`] that
+    /// is a prefix of `input`.
+    pub fn shortest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<usize> {
+        self.prefixes_of(input).next()
+    }
+
+    /// Returns an iterator over the [`RadixTrie`] that yields values in the
+    /// order the child storage keeps them.
+    ///
+    /// This skips sorting the children of each node, so it is faster than
+    /// [`RadixTrie::iter`], but the order is arbitrary for hash maps.
+    pub fn iter_unordered(&self) -> RadixIter<'_, T, H> {
+        RadixIter::new(Some(&self.root), Vec::new(), None)
+    }
+
+    /// Returns an iterator over every value in the [`RadixTrie`] that starts
+    /// with `prefix`, in the order the child storage keeps them.
+    ///
+    /// See [`RadixTrie::iter_unordered`].
+    pub fn iter_prefix_unordered(&self, prefix: impl IntoIterator<Item = T>) -> RadixIter<'_, T, H> {
+        self.iter_prefix_ordered(prefix, None)
+    }
+
+    /// Returns an iterator over the values that start with `prefix`, sorting
+    /// children with `order` if it is set.
+    fn iter_prefix_ordered(
+        &self,
+        prefix: impl IntoIterator<Item = T>,
+        order: Option<fn(&T, &T) -> Ordering>,
+    ) -> RadixIter<'_, T, H> {
+        let mut prefix: Vec<T> = prefix.into_iter().collect();
+        match self.root.find(&prefix) {
+            Some((node, rest)) => {
+                // The prefix may end part way along an edge, in which case the
+                // rest of the edge is part of every value:
+                prefix.extend_from_slice(rest);
+                RadixIter::new(Some(node), prefix, order)
+            },
+            None => RadixIter::new(None, prefix, order),
+        }
+    }
+
+    /// Returns an iterator over every value within `max_distance` edits of
+    /// `query`, along with the distance.
+    ///
+    /// See [`RadixFuzzySearch`].
+    pub fn fuzzy_search(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> RadixFuzzySearch<'_, T, H> {
+        RadixFuzzySearch::new(&self.root, query.into_iter().collect(), max_distance, false)
+    }
+
+    /// Returns an iterator over every value within `max_distance` edits of
+    /// `query`, where swapping two adjacent elements counts as a single edit.
+    ///
+    /// See [`RadixFuzzySearch`].
+    pub fn fuzzy_search_transpositions(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> RadixFuzzySearch<'_, T, H> {
+        RadixFuzzySearch::new(&self.root, query.into_iter().collect(), max_distance, true)
+    }
+
+    /// Returns an iterator over every value that matches `pattern`.
+    ///
+    /// See [`RadixMatches`].
+    pub fn matches<P>(&self, pattern: impl IntoIterator<Item = Token<P>>) -> RadixMatches<'_, T, H, P>
+    where
+        P: Fn(&T) -> bool,
+    {
+        RadixMatches::new(&self.root, pattern.into_iter().collect())
+    }
+
+    /// Expands the [`RadixTrie`] into [`TrieNode`]s, with one node for each
+    /// element of each label.
+    pub(super) fn expand(&self) -> TrieNode<T, H> {
+        self.root.expand()
+    }
+
+    /// Compiles the [`RadixTrie`] into an [`AhoCorasick`] automaton, which
+    /// finds every value within a sequence in a single pass.
+    ///
+    /// See [`Trie::aho_corasick`](crate::trie::Trie::aho_corasick).
+    pub fn aho_corasick(&self, kind: MatchKind) -> AhoCorasick<T>
+    where
+        T: Hash,
+    {
+        AhoCorasick::new(&self.expand(), kind)
+    }
+
+    /// Builds a read-only [`DoubleArray`] from the [`RadixTrie`].
+    ///
+    /// See [`Trie::double_array`](crate::trie::Trie::double_array).
+    pub fn double_array(&self) -> DoubleArray<T>
+    where
+        T: Symbol,
+    {
+        DoubleArray::new(&self.expand())
+    }
+
+    /// Writes the [`RadixTrie`] to `writer` in the same versioned binary
+    /// format as [`Trie::write_to`](crate::trie::Trie::write_to), so it can
+    /// be read back as either kind of trie.
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()>
+    where
+        T: Symbol,
+    {
+        binary::write(&self.expand(), self.len(), writer)
+    }
+
+    /// Reads a [`RadixTrie`] written by [`RadixTrie::write_to`] or
+    /// [`Trie::write_to`](crate::trie::Trie::write_to).
+    ///
+    /// See [`Trie::read_from`](crate::trie::Trie::read_from).
+    pub fn read_from(reader: impl Read) -> io::Result<Self>
+    where
+        T: Symbol,
+    {
+        let (root, count) = binary::read::<T, H>(reader)?;
+        Ok(Self {
+            root: RadixNode::compress(Vec::new(), &root),
+            count,
+        })
+    }
+
+    /// Writes the [`RadixTrie`] to `writer` in a compact binary form that a
+    /// [`MappedTrie`] can query in place.
+    ///
+    /// See [`MappedTrie::write_to`].
+    pub fn write_mapped(&self, writer: impl Write) -> io::Result<()>
+    where
+        T: Symbol,
+    {
+        MappedTrie::write_to(&self.expand(), writer)
+    }
+
+    /// Checks the invariants of the [`RadixTrie`], visiting every node.
+    ///
+    /// Besides the checks of [`TrieMap::validate`](crate::map::TrieMap::validate),
+    /// every label other than the root's must be non-empty and start with
+    /// the element it is stored under, and every node other than the root
+    /// that doesn't store a value must have at least two children.
+    pub fn validate(&self) -> Result<(), InvalidTrie> {
+        let mut values = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            let is_root = std::ptr::eq(node, &self.root);
+            if node.label.is_empty() != is_root {
+                return Err(InvalidTrie::EdgeLabel);
+            }
+            if node.end {
+                values += 1;
+            } else if !is_root {
+                match node.len() {
+                    0 => return Err(InvalidTrie::DeadBranch),
+                    1 => return Err(InvalidTrie::UnmergedEdge),
+                    _ => {},
+                }
+            }
+            #[cfg(feature = "counts")]
+            {
+                let expected = usize::from(node.end) + node.children
+                    .values()
+                    .map(|child| child.count)
+                    .sum::<usize>();
+                if node.count != expected {
+                    return Err(InvalidTrie::NodeCount);
+                }
+            }
+            for (element, child) in node.children.iter() {
+                if child.label.first() != Some(element) {
+                    return Err(InvalidTrie::EdgeLabel);
+                }
+                stack.push(child);
+            }
+        }
+
+        match values == self.count {
+            true => Ok(()),
+            false => Err(InvalidTrie::Count {
+                stored: self.count,
+                actual: values,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "counts")]
+impl<T, H> RadixTrie<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the `n`th value in the [`RadixTrie`], in lexicographic order.
+    ///
+    /// If the trie holds `n` values or fewer, this function returns [`None`].
+    pub fn nth(&self, n: usize) -> Option<Vec<T>> {
+        self.root.nth(n)
+    }
+
+    /// Returns the number of values in the [`RadixTrie`] that sort before
+    /// `value` in lexicographic order.
+    pub fn rank(&self, value: impl IntoIterator<Item = T>) -> usize {
+        let key: Vec<T> = value.into_iter().collect();
+        self.root.rank(&key)
+    }
+}
+
+impl<T, H> RadixTrie<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over the [`RadixTrie`] that yields values in
+    /// lexicographic order.
+    ///
+    /// The children of each node are sorted as they are visited. Use
+    /// [`RadixTrie::iter_unordered`] if the order doesn't matter.
+    pub fn iter(&self) -> RadixIter<'_, T, H> {
+        RadixIter::new(Some(&self.root), Vec::new(), Some(T::cmp))
+    }
+
+    /// Returns an iterator over every value in the [`RadixTrie`] that starts
+    /// with `prefix`, in lexicographic order.
+    ///
+    /// The iterator yields full values, including the prefix.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> RadixIter<'_, T, H> {
+        self.iter_prefix_ordered(prefix, Some(T::cmp))
+    }
+
+    /// Freezes the [`RadixTrie`] into an immutable, minimised [`Dawg`].
+    ///
+    /// See [`Trie::freeze`](crate::trie::Trie::freeze).
+    pub fn freeze(&self) -> Dawg<T>
+    where
+        T: Hash,
+    {
+        Dawg::new(&self.expand())
+    }
+
+    /// Builds a read-only, succinct [`Louds`] trie from the [`RadixTrie`].
+    ///
+    /// See [`Trie::louds`](crate::trie::Trie::louds).
+    pub fn louds(&self) -> Louds<T> {
+        Louds::new(&self.expand())
+    }
+}
+
+/// A view into a single value within a [`RadixTrie`], which may either be
+/// occupied or vacant.
+///
+/// This is created by [`RadixTrie::entry`]. Unlike an
+/// [`Entry`](crate::entry::Entry), inserting or removing the value walks the
+/// trie again, since either may split or merge the edges along the way.
+pub enum RadixEntry<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The value exists within the trie.
+    Occupied(OccupiedRadixEntry<'a, T, H>),
+    /// The value does not exist within the trie.
+    Vacant(VacantRadixEntry<'a, T, H>),
+}
+
+/// A view into an occupied value within a [`RadixTrie`].
+pub struct OccupiedRadixEntry<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    trie: &'a mut RadixTrie<T, H>,
+    /// The value this entry points to.
+    key: Vec<T>,
+}
+
+/// A view into a vacant value within a [`RadixTrie`].
+pub struct VacantRadixEntry<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    trie: &'a mut RadixTrie<T, H>,
+    /// The value this entry points to.
+    key: Vec<T>,
+}
+
+/// Returns a mutable reference to the unit value of a [`RadixTrie`], which
+/// mirrors the values of an [`Entry`](crate::entry::Entry).
+fn unit<'a>() -> &'a mut () {
+    // `()` is zero-sized, so leaking it doesn't allocate:
+    Box::leak(Box::new(()))
+}
+
+impl<'a, T, H> RadixEntry<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the value this entry points to.
+    pub fn key(&self) -> &[T] {
+        match self {
+            RadixEntry::Occupied(entry) => entry.key(),
+            RadixEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures the value is in the trie by inserting it if the entry is
+    /// vacant.
+    pub fn or_insert(self, default: ()) -> &'a mut () {
+        match self {
+            RadixEntry::Occupied(entry) => entry.into_mut(),
+            RadixEntry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures the value is in the trie by inserting it if the entry is
+    /// vacant. `default` is only called in that case.
+    pub fn or_insert_with(self, default: impl FnOnce()) -> &'a mut () {
+        match self {
+            RadixEntry::Occupied(entry) => entry.into_mut(),
+            RadixEntry::Vacant(entry) => {
+                default();
+                entry.insert(())
+            },
+        }
+    }
+
+    /// Ensures the value is in the trie by inserting it if the entry is
+    /// vacant.
+    pub fn or_default(self) -> &'a mut () {
+        self.or_insert(())
+    }
+
+    /// Calls `f` if the entry is occupied.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut ())) -> Self {
+        if let RadixEntry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T, H> OccupiedRadixEntry<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the value this entry points to.
+    pub fn key(&self) -> &[T] {
+        &self.key
+    }
+
+    /// Returns an immutable reference to the unit value in the entry.
+    pub fn get(&self) -> &() {
+        &()
+    }
+
+    /// Returns a mutable reference to the unit value in the entry.
+    pub fn get_mut(&mut self) -> &mut () {
+        unit()
+    }
+
+    /// Converts the entry into a mutable reference to its unit value, bound
+    /// to the lifetime of the trie.
+    pub fn into_mut(self) -> &'a mut () {
+        unit()
+    }
+
+    /// Replaces the unit value in the entry, which leaves the trie unchanged.
+    pub fn insert(&mut self, value: ()) {
+        *self.get_mut() = value;
+    }
+
+    /// Removes the value from the trie.
+    ///
+    /// The edges along the way are merged wherever the value was the only
+    /// thing keeping them apart.
+    pub fn remove(self) {
+        let removed = self.trie.remove(self.key);
+        debug_assert!(removed);
+    }
+}
+
+impl<'a, T, H> VacantRadixEntry<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the value this entry points to.
+    pub fn key(&self) -> &[T] {
+        &self.key
+    }
+
+    /// Inserts the value into the trie, splitting an edge if needed, and
+    /// returns a mutable reference to its unit value.
+    pub fn insert(self, value: ()) -> &'a mut () {
+        let inserted = self.trie.insert(self.key);
+        debug_assert!(inserted);
+        let unit = unit();
+        *unit = value;
+        unit
+    }
+}
+
+/// An immutable view of the values within a [`RadixTrie`] that start with a
+/// given prefix.
+///
+/// Every value passed to or returned from a [`RadixSubTrie`] is relative to
+/// the prefix, as with a [`SubTrie`](crate::subtrie::SubTrie). The prefix may
+/// end part way along an edge.
+pub struct RadixSubTrie<'a, T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// The node whose edge the prefix ends on.
+    node: &'a RadixNode<T, H>,
+    /// The elements of the label of `node` that follow the end of the prefix.
+    rest: &'a [T],
+    /// The prefix this view is rooted at.
+    prefix: Vec<T>,
+}
+
+impl<'a, T, H> RadixSubTrie<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the prefix this view is rooted at.
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
+    }
+
+    /// Returns `true` if no values start with the prefix.
+    pub fn is_empty(&self) -> bool {
+        !self.node.has_values()
+    }
+
+    /// Returns the number of values that start with the prefix.
+    pub fn len(&self) -> usize {
+        self.node.count_values()
+    }
+
+    /// Follows `key` from the end of the prefix.
+    fn find<E>(&self, key: impl IntoIterator<Item = E>) -> Option<(&'a RadixNode<T, H>, &'a [T])>
+    where
+        E: Borrow<T>,
+    {
+        let node: &'a RadixNode<T, H> = self.node;
+        node.find_from(node.label.len() - self.rest.len(), key)
+    }
+
+    /// Checks if the view contains a value, relative to the prefix.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.find(iter)
+            .is_some_and(|(node, rest)| rest.is_empty() && node.end)
+    }
+
+    /// Returns `true` if any value within the view starts with `prefix`,
+    /// relative to the prefix of the view.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.find(prefix)
+            .is_some_and(|(node, _)| node.has_values())
+    }
+
+    /// Returns an iterator over every value within the view, relative to the
+    /// prefix.
+    pub fn iter(&self) -> RadixIter<'a, T, H> {
+        RadixIter::new(Some(self.node), self.rest.to_vec(), None)
+    }
+
+    /// Returns an immutable reference to the [`RadixNode`] whose edge the
+    /// prefix ends on.
+    pub fn root(&self) -> &'a RadixNode<T, H> {
+        self.node
+    }
+}
+
+/// A mutable view of the values within a [`RadixTrie`] that start with a
+/// given prefix.
+///
+/// Values inserted into or removed from the view are relative to the prefix,
+/// as with a [`SubTrieMut`](crate::subtrie::SubTrieMut). Each operation walks
+/// the prefix again, since inserting or removing a value may split or merge
+/// the edge the prefix ends on.
+pub struct RadixSubTrieMut<'a, T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    trie: &'a mut RadixTrie<T, H>,
+    /// The prefix this view is rooted at.
+    prefix: Vec<T>,
+}
+
+impl<T, H> RadixSubTrieMut<'_, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the prefix this view is rooted at.
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
+    }
+
+    /// Returns an immutable view of the values within this view.
+    fn view(&self) -> Option<RadixSubTrie<'_, T, H>> {
+        self.trie.subtrie(self.prefix.iter().copied())
+    }
+
+    /// Returns `true` if no values start with the prefix.
+    pub fn is_empty(&self) -> bool {
+        self.view().is_none_or(|view| view.is_empty())
+    }
+
+    /// Returns the number of values that start with the prefix.
+    pub fn len(&self) -> usize {
+        self.view().map_or(0, |view| view.len())
+    }
+
+    /// Checks if the view contains a value, relative to the prefix.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.view().is_some_and(|view| view.contains(iter))
+    }
+
+    /// Returns `true` if any value within the view starts with `prefix`,
+    /// relative to the prefix of the view.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.view().is_some_and(|view| view.starts_with(prefix))
+    }
+
+    /// Inserts a value into the view, relative to the prefix.
+    ///
+    /// Returns `true` if the value was not already present.
+    pub fn insert(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let key = self.prefix.iter().copied().chain(iter);
+        self.trie.insert(key.collect::<Vec<T>>())
+    }
+
+    /// Removes a value from the view, relative to the prefix.
+    ///
+    /// Returns `true` if the value was removed.
+    pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let key = self.prefix.iter().copied().chain(iter);
+        self.trie.remove(key.collect::<Vec<T>>())
+    }
+
+    /// Returns an iterator over every value within the view, relative to the
+    /// prefix.
+    pub fn iter(&self) -> RadixIter<'_, T, H> {
+        match self.view() {
+            Some(view) => view.iter(),
+            None => RadixIter::new(None, Vec::new(), None),
+        }
+    }
+}
+
+/// Iterates over every key below a [`RadixNode`].
+///
+/// Iterators created by [`RadixTrie::iter`] and [`RadixTrie::iter_prefix`]
+/// yield keys in lexicographic order. Those created by the unordered methods
+/// and by a [`RadixSubTrie`] yield keys in an arbitrary order.
+pub struct RadixIter<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may be the end of a key itself.
+    start: Option<&'a RadixNode<T, H>>,
+    /// Nodes to visit, along with the length of the key above their edge.
+    stack: Vec<(&'a RadixNode<T, H>, usize)>,
+    buffer: Vec<T>,
+    /// Used to sort the children of each node before they are visited.
+    order: Option<fn(&T, &T) -> Ordering>,
+}
+
+impl<'a, T, H> RadixIter<'a, T, H>
+where
+    T: Copy,
+    H: ChildStorage<T>,
+{
+    fn new(
+        node: Option<&'a RadixNode<T, H>>,
+        prefix: Vec<T>,
+        order: Option<fn(&T, &T) -> Ordering>,
+    ) -> Self {
+        Self {
+            start: node,
+            stack: Vec::new(),
+            buffer: prefix,
+            order,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack.
+    ///
+    /// When the iterator is sorted, the children are pushed in reverse order
+    /// so that the smallest child is popped first.
+    fn push_children(&mut self, node: &'a RadixNode<T, H>, depth: usize) {
+        let start = self.stack.len();
+        self.stack.extend(node.children.values().map(|child| (child, depth)));
+        if let Some(order) = self.order {
+            self.stack[start..].sort_unstable_by(|(a, _), (b, _)| order(&b.label[0], &a.label[0]));
+        }
+    }
+}
+
+impl<T, H> Iterator for RadixIter<'_, T, H>
+where
+    T: Copy,
+    H: ChildStorage<T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, self.buffer.len());
+            if start.end {
+                return Some(self.buffer.clone());
+            }
+        }
+
+        while let Some((node, depth)) = self.stack.pop() {
+            // If we're backtracking, truncate the key to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.extend_from_slice(&node.label);
+
+            // Children are pushed before yielding, so keys that extend this
+            // one are still visited:
+            self.push_children(node, self.buffer.len());
+
+            if node.end {
+                return Some(self.buffer.clone());
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over every key below a [`RadixNode`] within a maximum edit
+/// distance of a query.
+///
+/// This measures distances in the same way as a
+/// [`FuzzySearch`](crate::fuzzy::FuzzySearch). The edit distance table is
+/// extended one element at a time along each edge, so a subtree is skipped as
+/// soon as no key below it can be within the maximum distance, even part way
+/// along an edge. Keys are yielded in an arbitrary order.
+pub struct RadixFuzzySearch<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the search starts from. This is visited on the first call to
+    /// `next`, since it may be the end of a key itself.
+    start: Option<&'a RadixNode<T, H>>,
+    /// Nodes to visit, along with the length of the key above their edge.
+    stack: Vec<(&'a RadixNode<T, H>, usize)>,
+    rows: EditRows<T>,
+}
+
+impl<'a, T, H> RadixFuzzySearch<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    fn new(root: &'a RadixNode<T, H>, query: Vec<T>, max_distance: usize, transpositions: bool) -> Self {
+        Self {
+            start: Some(root),
+            stack: Vec::new(),
+            rows: EditRows::new(query, max_distance, transpositions),
+        }
+    }
+}
+
+impl<T, H> Iterator for RadixFuzzySearch<'_, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    type Item = (Vec<T>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The empty key is stored on the root itself:
+        if let Some(start) = self.start.take() {
+            self.stack.extend(start.children.values().map(|child| (child, 0)));
+            if let (true, Some(distance)) = (start.end, self.rows.distance()) {
+                return Some((Vec::new(), distance));
+            }
+        }
+
+        'nodes: while let Some((node, depth)) = self.stack.pop() {
+            // A row never contains a smaller distance than the row above it,
+            // so once a row is exhausted neither this key nor any key below it
+            // can be close enough:
+            for (offset, element) in node.label.iter().enumerate() {
+                self.rows.push(depth + offset, *element);
+                if self.rows.is_exhausted() {
+                    continue 'nodes;
+                }
+            }
+
+            let depth = depth + node.label.len();
+            self.stack.extend(node.children.values().map(|child| (child, depth)));
+            if let (true, Some(distance)) = (node.end, self.rows.distance()) {
+                return Some((self.rows.key().to_vec(), distance));
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over every key below a [`RadixNode`] that matches a pattern of
+/// [`Token`]s.
+///
+/// This matches patterns in the same way as a
+/// [`PatternMatches`](crate::pattern::PatternMatches), stepping the pattern
+/// one element at a time along each edge. A subtree is skipped as soon as no
+/// pattern position is left. Each matching key is yielded once, in an
+/// arbitrary order.
+pub struct RadixMatches<'a, T, H, P>
+where
+    H: ChildStorage<T>,
+{
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may be the end of a key itself.
+    start: Option<&'a RadixNode<T, H>>,
+    /// Nodes to visit, along with the length of the key above their edge and
+    /// the pattern positions reached by that key.
+    stack: Vec<(&'a RadixNode<T, H>, usize, Vec<usize>)>,
+    buffer: Vec<T>,
+    pattern: Vec<Token<P>>,
+}
+
+impl<'a, T, H, P> RadixMatches<'a, T, H, P>
+where
+    T: Copy,
+    P: Fn(&T) -> bool,
+    H: ChildStorage<T>,
+{
+    fn new(root: &'a RadixNode<T, H>, pattern: Vec<Token<P>>) -> Self {
+        Self {
+            start: Some(root),
+            stack: Vec::new(),
+            buffer: Vec::new(),
+            pattern,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack.
+    fn push_children(&mut self, node: &'a RadixNode<T, H>, depth: usize, positions: &[usize]) {
+        self.stack.extend(
+            node.children
+                .values()
+                .map(|child| (child, depth, positions.to_vec())),
+        );
+    }
+}
+
+impl<T, H, P> Iterator for RadixMatches<'_, T, H, P>
+where
+    T: Copy,
+    P: Fn(&T) -> bool,
+    H: ChildStorage<T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The empty key is stored on the root itself:
+        if let Some(start) = self.start.take() {
+            let positions = pattern::start_positions(&self.pattern);
+            self.push_children(start, 0, &positions);
+            if start.end && pattern::is_match(&self.pattern, &positions) {
+                return Some(Vec::new());
+            }
+        }
+
+        'nodes: while let Some((node, depth, mut positions)) = self.stack.pop() {
+            // Follow the edge, giving up on the subtree once no position is
+            // left:
+            for element in node.label.iter() {
+                positions = pattern::step(&self.pattern, &positions, element);
+                if positions.is_empty() {
+                    continue 'nodes;
+                }
+            }
+
+            // If we're backtracking, truncate the key to the current depth:
+            self.buffer.truncate(depth);
+            self.buffer.extend_from_slice(&node.label);
+            self.push_children(node, self.buffer.len(), &positions);
+
+            if node.end && pattern::is_match(&self.pattern, &positions) {
+                return Some(self.buffer.clone());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, H> Serialize for RadixNode<T, H>
+where
+    T: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Children are written as a sequence, since the key of each child is
+        // the first element of its label:
+        let mut state = serializer.serialize_struct("RadixNode", 3)?;
+        state.serialize_field("label", &self.label)?;
+        state.serialize_field("children", &SerializeRadixChildren(self))?;
+        state.serialize_field("end", &self.end)?;
+        state.end()
+    }
+}
+
+/// Serializes the children of a [`RadixNode`] as a sequence, whichever
+/// container they are stored in.
+#[cfg(feature = "serde")]
+struct SerializeRadixChildren<'a, T, H>(&'a RadixNode<T, H>)
+where
+    H: ChildStorage<T>;
+
+#[cfg(feature = "serde")]
+impl<T, H> Serialize for SerializeRadixChildren<'_, T, H>
+where
+    T: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.0.children.values())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, H> Deserialize<'de> for RadixNode<T, H>
+where
+    T: Deserialize<'de> + Eq + Copy,
+    H: ChildStorage<T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // RadixNode visitor used for deserialization.
+        struct RadixNodeVisitor<T, H> {
+            marker: std::marker::PhantomData<(T, H)>,
+        }
+
+        // Reads the children of a node into whichever container `H` uses.
+        struct ChildrenVisitor<T, H> {
+            marker: std::marker::PhantomData<(T, H)>,
+        }
+
+        impl<'de, T, H> serde::de::DeserializeSeed<'de> for ChildrenVisitor<T, H>
+        where
+            T: Deserialize<'de> + Eq + Copy,
+            H: ChildStorage<T>,
+        {
+            type Value = H::Children<RadixNode<T, H>>;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        impl<'de, T, H> serde::de::Visitor<'de> for ChildrenVisitor<T, H>
+        where
+            T: Deserialize<'de> + Eq + Copy,
+            H: ChildStorage<T>,
+        {
+            type Value = H::Children<RadixNode<T, H>>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a sequence of child nodes")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut children = Self::Value::default();
+                while let Some(mut child) = seq.next_element::<RadixNode<T, H>>()? {
+                    let Some(&first) = child.label.first() else {
+                        return Err(serde::de::Error::custom("radix node has an empty label"));
+                    };
+
+                    // Children are read before their parent, so a child
+                    // without values is the end of a dead branch and a child
+                    // with a single child of its own ends an unmerged chain.
+                    // Both are fixed here, as they would be on removal:
+                    if !child.end {
+                        match child.children.len() {
+                            0 => continue,
+                            1 => child.merge(),
+                            _ => {},
+                        }
+                    }
+                    if children.insert(first, child).is_some() {
+                        return Err(serde::de::Error::custom("radix node has a duplicate child"));
+                    }
+                }
+                Ok(children)
+            }
+        }
+
+        impl<'de, T, H> serde::de::Visitor<'de> for RadixNodeVisitor<T, H>
+        where
+            T: Deserialize<'de> + Eq + Copy,
+            H: ChildStorage<T>,
+        {
+            type Value = RadixNode<T, H>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("struct RadixNode")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: serde::de::MapAccess<'de>,
+            {
+                let mut label = None;
+                let mut children = None;
+                let mut end = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        "label" => {
+                            if label.is_some() {
+                                return Err(serde::de::Error::duplicate_field("label"));
+                            }
+                            label = Some(map.next_value::<Vec<T>>()?);
+                        }
+                        "children" => {
+                            if children.is_some() {
+                                return Err(serde::de::Error::duplicate_field("children"));
+                            }
+                            children = Some(map.next_value_seed(ChildrenVisitor::<T, H> {
+                                marker: std::marker::PhantomData,
+                            })?);
+                        }
+                        "end" => {
+                            if end.is_some() {
+                                return Err(serde::de::Error::duplicate_field("end"));
+                            }
+                            end = Some(map.next_value()?);
+                        }
+                        _ => {
+                            return Err(serde::de::Error::unknown_field(
+                                key,
+                                &["label", "children", "end"],
+                            ));
+                        }
+                    }
+                }
+
+                let children: H::Children<RadixNode<T, H>> = children.unwrap_or_default();
+                let end = end.unwrap_or_default();
+
+                // Counts are not serialized, they are rebuilt from the
+                // children instead:
+                #[cfg(feature = "counts")]
+                let count = usize::from(end) + children
+                    .values()
+                    .map(|child| child.count)
+                    .sum::<usize>();
+
+                Ok(RadixNode {
+                    label: label.unwrap_or_default().into(),
+                    children,
+                    end,
+                    #[cfg(feature = "counts")]
+                    count,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "RadixNode",
+            &["label", "children", "end"],
+            RadixNodeVisitor {
+                marker: std::marker::PhantomData,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, H> Serialize for RadixTrie<T, H>
+where
+    T: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize as a struct with two fields: `root` and `count`
+        let mut state = serializer.serialize_struct("RadixTrie", 2)?;
+        state.serialize_field("root", &self.root)?;
+        state.serialize_field("count", &self.count)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, H> Deserialize<'de> for RadixTrie<T, H>
+where
+    T: Deserialize<'de> + Eq + Copy,
+    H: ChildStorage<T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Visitor for deserializing a `RadixTrie`.
+        struct RadixTrieVisitor<T, H> {
+            marker: std::marker::PhantomData<(T, H)>,
+        }
+
+        impl<'de, T, H> serde::de::Visitor<'de> for RadixTrieVisitor<T, H>
+        where
+            T: Deserialize<'de> + Eq + Copy,
+            H: ChildStorage<T>,
+        {
+            type Value = RadixTrie<T, H>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a RadixTrie with root and count fields")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: serde::de::MapAccess<'de>,
+            {
+                let mut root = None;
+                let mut count = None;
+
+                while let Some(key) = map.next_key::<&str>()? {
+                    match key {
+                        "root" => {
+                            if root.is_some() {
+                                return Err(serde::de::Error::duplicate_field("root"));
+                            }
+                            root = Some(map.next_value()?);
+                        }
+                        "count" => {
+                            if count.is_some() {
+                                return Err(serde::de::Error::duplicate_field("count"));
+                            }
+                            count = Some(map.next_value()?);
+                        }
+                        _ => {
+                            return Err(serde::de::Error::unknown_field(key, &["root", "count"]));
+                        }
+                    }
+                }
+
+                let root: RadixNode<T, H> = root.ok_or_else(|| serde::de::Error::missing_field("root"))?;
+                if !root.label.is_empty() {
+                    return Err(serde::de::Error::custom("radix trie root has a label"));
+                }
+
+                // Dead branches and chains are fixed as the nodes are read, so
+                // only the count needs checking. A missing count is rebuilt
+                // instead:
+                let actual = root.count_values();
+                match count {
+                    Some(stored) if stored != actual => {
+                        Err(serde::de::Error::custom(InvalidTrie::Count { stored, actual }))
+                    }
+                    _ => Ok(RadixTrie { root, count: actual }),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "RadixTrie",
+            &["root", "count"],
+            RadixTrieVisitor {
+                marker: std::marker::PhantomData,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::children::SortedVec;
+
+    type CharRadixTrie = RadixTrie<char>;
+
+    fn sorted(iter: impl Iterator<Item = Vec<char>>) -> Vec<String> {
+        iter.map(String::from_iter).collect()
+    }
+
+    #[test]
+    fn test_insert_contains() {
+        let mut trie = CharRadixTrie::new();
+        assert!(trie.insert("internationalization".chars()));
+        assert!(trie.insert("internet".chars()));
+        assert!(trie.insert("inter".chars()));
+        assert!(!trie.insert("inter".chars()));
+        assert_eq!(trie.len(), 3);
+
+        assert!(trie.contains("inter".chars()));
+        assert!(trie.contains("internet".chars()));
+        assert!(trie.contains("internationalization".chars()));
+        assert!(!trie.contains("intern".chars()));
+        assert!(!trie.contains("in".chars()));
+        assert!(!trie.contains("internets".chars()));
+
+        // root -> inter -> n -> ationalization
+        //                    -> et
+        assert_eq!(trie.node_count(), 5);
+        assert_eq!(trie.root().get(&'i').unwrap().label(), ['i', 'n', 't', 'e', 'r']);
+    }
+
+    #[test]
+    fn test_remove_merges() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["test", "testing", "tester", "team"] {
+            assert!(trie.insert(value.chars()));
+        }
+        assert_eq!(trie.node_count(), 6);
+
+        assert!(trie.remove("test".chars()));
+        assert!(!trie.remove("test".chars()));
+        assert!(!trie.remove("tes".chars()));
+        assert!(trie.contains("testing".chars()));
+        assert!(trie.contains("tester".chars()));
+
+        // "test" no longer ends a key, but still has two children:
+        assert_eq!(trie.node_count(), 6);
+
+        // Removing "tester" leaves "test" with a single child, so "test" and
+        // "ing" are merged:
+        assert!(trie.remove("tester".chars()));
+        assert_eq!(trie.node_count(), 4);
+        let te = trie.root().get(&'t').unwrap();
+        assert_eq!(te.get(&'s').unwrap().label(), ['s', 't', 'i', 'n', 'g']);
+
+        // Removing "team" merges everything back into a single edge:
+        assert!(trie.remove("team".chars()));
+        assert_eq!(trie.node_count(), 2);
+        assert_eq!(trie.root().get(&'t').unwrap().label(), ['t', 'e', 's', 't', 'i', 'n', 'g']);
+
+        assert!(trie.remove("testing".chars()));
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_prefix_queries() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["testing", "tester", "tesla", "tech", "other"] {
+            assert!(trie.insert(value.chars()));
+        }
+
+        assert!(trie.starts_with("tes".chars()));
+        assert!(trie.starts_with("testi".chars()));
+        assert!(!trie.starts_with("testx".chars()));
+        assert_eq!(trie.count_with_prefix("".chars()), 5);
+        assert_eq!(trie.count_with_prefix("te".chars()), 4);
+        assert_eq!(trie.count_with_prefix("test".chars()), 2);
+        assert_eq!(trie.count_with_prefix("x".chars()), 0);
+
+        // Prefixes can end part way along an edge:
+        assert_eq!(sorted(trie.iter_prefix("test".chars())), ["tester", "testing"]);
+        assert_eq!(sorted(trie.iter_prefix("o".chars())), ["other"]);
+        assert_eq!(trie.iter_prefix("x".chars()).count(), 0);
+        assert_eq!(trie.iter_unordered().count(), 5);
+        assert_eq!(
+            sorted(trie.iter()),
+            ["other", "tech", "tesla", "tester", "testing"],
+        );
+    }
+
+    #[test]
+    fn test_prefixes_of() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["", "new", "newyork", "newyorktimes"] {
+            assert!(trie.insert(value.chars()));
+        }
+
+        assert_eq!(trie.prefixes_of("newyorkcity".chars()).collect::<Vec<_>>(), [0, 3, 7]);
+        assert_eq!(trie.longest_prefix_of("newyorktimes!".chars()), Some(12));
+        assert_eq!(trie.longest_prefix_of("newyo".chars()), Some(3));
+        assert_eq!(trie.shortest_prefix_of("x".chars()), Some(0));
+        assert_eq!(sorted(trie.iter()), ["", "new", "newyork", "newyorktimes"]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let trie = CharRadixTrie::new();
+        assert!(!trie.starts_with("".chars()));
+        assert_eq!(trie.count_with_prefix("".chars()), 0);
+        assert_eq!(trie.iter().count(), 0);
+        assert!(trie.subtrie("".chars()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sorted_vec_storage() {
+        let mut trie = RadixTrie::<u8, SortedVec>::new();
+        for key in [&b"internet"[..], b"inter", b"interval"] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+        assert!(trie.remove(b"inter".iter().copied()));
+        assert_eq!(trie.iter().collect::<Vec<_>>(), [b"internet".to_vec(), b"interval".to_vec()]);
+
+        // root -> inter -> net
+        //               -> val
+        assert_eq!(trie.node_count(), 4);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut trie = CharRadixTrie::new();
+        assert!(trie.insert("test".chars()));
+
+        match trie.entry("tes".chars()) {
+            RadixEntry::Vacant(entry) => {
+                assert_eq!(entry.key(), ['t', 'e', 's']);
+                entry.insert(());
+            },
+            RadixEntry::Occupied(_) => panic!("entry should be vacant"),
+        }
+        assert!(trie.contains("tes".chars()));
+        assert_eq!(trie.len(), 2);
+
+        match trie.entry("test".chars()) {
+            RadixEntry::Occupied(entry) => entry.remove(),
+            RadixEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+        trie.entry("tes".chars()).or_insert(());
+        trie.entry("team".chars()).or_default();
+        assert_eq!(sorted(trie.iter()), ["team", "tes"]);
+
+        let mut called = false;
+        trie.entry("tea".chars()).or_insert_with(|| called = true);
+        assert!(called);
+        called = false;
+        trie.entry("tea".chars()).or_insert_with(|| called = true);
+        assert!(!called);
+
+        let mut modified = false;
+        trie.entry("tes".chars()).and_modify(|_| modified = true).or_default();
+        assert!(modified);
+        match trie.entry("team".chars()) {
+            RadixEntry::Occupied(mut entry) => {
+                assert_eq!(entry.get(), &());
+                entry.insert(());
+                entry.remove();
+            },
+            RadixEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+        assert_eq!(sorted(trie.iter()), ["tea", "tes"]);
+        assert_eq!(trie.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let mut trie: CharRadixTrie = ["tea", "ten", "tea"].map(str::chars).into_iter().collect();
+        assert_eq!(trie.len(), 2);
+        trie.extend(["to", "inn"].map(str::chars));
+        assert_eq!(sorted(trie.iter()), ["inn", "tea", "ten", "to"]);
+        assert_eq!(sorted(trie.iter_prefix("te".chars())), ["tea", "ten"]);
+        assert_eq!(trie.iter_unordered().count(), 4);
+        assert_eq!(trie.iter_prefix_unordered("t".chars()).count(), 3);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut trie: CharRadixTrie = ["testing", "tester", "tea", ""].map(str::chars).into_iter().collect();
+        assert_eq!(trie.validate(), Ok(()));
+        assert!(trie.remove("tea".chars()));
+        assert_eq!(trie.validate(), Ok(()));
+
+        trie.count += 1;
+        assert_eq!(trie.validate(), Err(InvalidTrie::Count { stored: 4, actual: 3 }));
+        trie.count -= 1;
+
+        // Add a branch without a value below the edge for "test":
+        let test = trie.root.children.get_mut(&'t').unwrap();
+        test.children.insert('x', RadixNode::leaf(['x'].into(), false));
+        assert_eq!(trie.validate(), Err(InvalidTrie::DeadBranch));
+        trie.root.children.get_mut(&'t').unwrap().children.remove(&'x');
+
+        // Add an edge stored under the wrong element:
+        let test = trie.root.children.get_mut(&'t').unwrap();
+        test.children.insert('y', RadixNode::leaf(['z'].into(), true));
+        #[cfg(feature = "counts")]
+        {
+            test.count += 1;
+            trie.root.count += 1;
+        }
+        trie.count += 1;
+        assert_eq!(trie.validate(), Err(InvalidTrie::EdgeLabel));
+        let test = trie.root.children.get_mut(&'t').unwrap();
+        test.children.remove(&'y');
+        #[cfg(feature = "counts")]
+        {
+            test.count -= 1;
+            trie.root.count -= 1;
+        }
+        trie.count -= 1;
+        assert_eq!(trie.validate(), Ok(()));
+
+        // Split an edge without adding a value:
+        trie.root.children.get_mut(&'t').unwrap().split(2);
+        assert_eq!(trie.validate(), Err(InvalidTrie::UnmergedEdge));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let keys = ["", "tea", "ten", "to", "inn", "in", "international"];
+        let radix: RadixTrie<char, SortedVec> = keys.map(str::chars).into_iter().collect();
+        let trie: crate::trie::Trie<char, SortedVec> = keys.map(str::chars).into_iter().collect();
+
+        // Every conversion gives the same result as for a `Trie`. Sorted
+        // children keep the order of the written nodes the same:
+        let (mut binary, mut written) = (Vec::new(), Vec::new());
+        trie.write_to(&mut binary).unwrap();
+        radix.write_to(&mut written).unwrap();
+        assert_eq!(written, binary);
+
+        let (mut expected, mut written) = (Vec::new(), Vec::new());
+        trie.write_mapped(&mut expected).unwrap();
+        radix.write_mapped(&mut written).unwrap();
+        assert_eq!(written, expected);
+
+        assert_eq!(radix.freeze().iter().collect::<Vec<_>>(), trie.freeze().iter().collect::<Vec<_>>());
+        assert_eq!(radix.louds().iter().collect::<Vec<_>>(), trie.louds().iter().collect::<Vec<_>>());
+
+        let array = radix.double_array();
+        assert_eq!(array.len(), keys.len());
+        for key in keys {
+            assert!(array.contains(key.chars()));
+        }
+        assert!(!array.contains("te".chars()));
+
+        let text: Vec<char> = "internet tea".chars().collect();
+        for kind in [MatchKind::Overlapping, MatchKind::LeftmostLongest] {
+            let found: Vec<_> = radix.aho_corasick(kind).find_iter(&text).map(|found| found.range()).collect();
+            let expected: Vec<_> = trie.aho_corasick(kind).find_iter(&text).map(|found| found.range()).collect();
+            assert_eq!(found, expected);
+        }
+
+        // Reading the trie back merges the expanded nodes into edges again:
+        let read = RadixTrie::<char, SortedVec>::read_from(binary.as_slice()).unwrap();
+        assert_eq!(read.validate(), Ok(()));
+        assert_eq!(read.node_count(), radix.node_count());
+        assert_eq!(read.iter().collect::<Vec<_>>(), radix.iter().collect::<Vec<_>>());
+        assert!(RadixTrie::<char>::read_from(&b"oops"[..]).is_err());
+    }
+
+    #[test]
+    fn test_subtrie() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["test", "tea", "teach", "other"] {
+            assert!(trie.insert(value.chars()));
+        }
+        assert!(trie.subtrie("x".chars()).is_none());
+
+        // The prefix ends part way along the "oth" edge:
+        let subtrie = trie.subtrie("ot".chars()).unwrap();
+        assert_eq!(subtrie.prefix(), ['o', 't']);
+        assert_eq!(subtrie.len(), 1);
+        assert!(subtrie.contains("her".chars()));
+        assert!(!subtrie.contains("he".chars()));
+        assert!(subtrie.starts_with("h".chars()));
+        assert_eq!(sorted(subtrie.iter()), ["her"]);
+
+        let subtrie = trie.subtrie("te".chars()).unwrap();
+        assert_eq!(subtrie.len(), 3);
+        assert!(subtrie.contains("a".chars()));
+        assert!(!subtrie.contains("".chars()));
+        let mut values = sorted(subtrie.iter());
+        values.sort();
+        assert_eq!(values, ["a", "ach", "st"]);
+
+        let mut subtrie = trie.subtrie_mut("tea".chars()).unwrap();
+        assert!(subtrie.insert("m".chars()));
+        assert!(!subtrie.insert("ch".chars()));
+        assert!(subtrie.remove("".chars()));
+        assert!(subtrie.remove("ch".chars()));
+        assert_eq!(subtrie.len(), 1);
+        assert!(subtrie.remove("m".chars()));
+        assert!(subtrie.is_empty());
+        assert_eq!(subtrie.iter().count(), 0);
+        assert_eq!(trie.len(), 2);
+        assert!(trie.subtrie_mut("tea".chars()).is_none());
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn test_nth_rank() {
+        let mut trie = CharRadixTrie::new();
+        let mut values = ["", "tea", "teach", "ten", "test", "tester", "to"];
+        for value in values {
+            assert!(trie.insert(value.chars()));
+        }
+        values.sort();
+
+        for (n, value) in values.iter().enumerate() {
+            assert_eq!(trie.nth(n).map(String::from_iter).as_deref(), Some(*value));
+            assert_eq!(trie.rank(value.chars()), n);
+        }
+        assert_eq!(trie.nth(values.len()), None);
+
+        // Keys that leave an edge part way along:
+        assert_eq!(trie.rank("tes".chars()), 4);
+        assert_eq!(trie.rank("tesz".chars()), 6);
+        assert_eq!(trie.rank("teb".chars()), 3);
+        assert_eq!(trie.rank("z".chars()), 7);
+        assert_eq!(trie.count_with_prefix("te".chars()), 5);
+
+        assert!(trie.remove("tea".chars()));
+        assert_eq!(trie.nth(1).map(String::from_iter).as_deref(), Some("teach"));
+        assert_eq!(trie.count_with_prefix("te".chars()), 4);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["kitten", "sitting", "mitten", "knitting", "kit"] {
+            assert!(trie.insert(value.chars()));
+        }
+
+        let search = |query: &str, max_distance, transpositions| {
+            let found = match transpositions {
+                false => trie.fuzzy_search(query.chars(), max_distance),
+                true => trie.fuzzy_search_transpositions(query.chars(), max_distance),
+            };
+            let mut found: Vec<(String, usize)> = found
+                .map(|(key, distance)| (String::from_iter(key), distance))
+                .collect();
+            found.sort();
+            found
+        };
+
+        assert_eq!(search("kitten", 1, false), [("kitten".to_string(), 0), ("mitten".to_string(), 1)]);
+        assert_eq!(search("sitten", 2, false), [
+            ("kitten".to_string(), 1),
+            ("mitten".to_string(), 1),
+            ("sitting".to_string(), 2),
+        ]);
+
+        // Results match those of a `Trie` holding the same keys:
+        let mut plain = crate::trie::Trie::<char>::new();
+        for value in trie.iter() {
+            plain.insert(value);
+        }
+        for (query, max_distance) in [("knit", 4), ("mittens", 2), ("", 3), ("xyz", 1)] {
+            for transpositions in [false, true] {
+                let found = match transpositions {
+                    false => plain.fuzzy_search(query.chars(), max_distance).collect::<Vec<_>>(),
+                    true => plain.fuzzy_search_transpositions(query.chars(), max_distance).collect(),
+                };
+                let mut expected: Vec<(String, usize)> = found
+                    .into_iter()
+                    .map(|(key, distance)| (String::from_iter(key), distance))
+                    .collect();
+                expected.sort();
+                assert_eq!(search(query, max_distance, transpositions), expected);
+            }
+        }
+        assert_eq!(search("ikt", 1, false), []);
+        assert_eq!(search("ikt", 1, true), [("kit".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut trie = CharRadixTrie::new();
+        for value in ["", "test", "tester", "toast", "tea"] {
+            assert!(trie.insert(value.chars()));
+        }
+
+        type Predicate = fn(&char) -> bool;
+        let t: Predicate = |c| *c == 't';
+        let any: Predicate = |_| true;
+
+        let mut found = sorted(trie.matches([Token::One(t), Token::Many, Token::One(t)]));
+        found.sort();
+        assert_eq!(found, ["test", "toast"]);
+        assert_eq!(sorted(trie.matches([Token::One(any), Token::One(any), Token::One(any)])), ["tea"]);
+        assert_eq!(trie.matches(Vec::<Token<Predicate>>::new()).count(), 1);
+        assert_eq!(trie.matches([Token::<Predicate>::Many]).count(), 5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use serde_json;
+
+        let mut trie = CharRadixTrie::new();
+        for value in ["test", "team", "teach"] {
+            assert!(trie.insert(value.chars()));
+        }
+
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: RadixTrie<char, SortedVec> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.len(), 3);
+        assert_eq!(deserialized.node_count(), trie.node_count());
+        assert_eq!(deserialized.count_with_prefix("tea".chars()), 2);
+        assert!(deserialized.contains("teach".chars()));
+
+        // Dead branches are pruned and chains left behind are merged:
+        let json = r#"{"root":{"label":[],"children":[
+            {"label":["a"],"children":[
+                {"label":["b"],"children":[],"end":true},
+                {"label":["c"],"children":[],"end":false}
+            ],"end":false}
+        ],"end":false},"count":1}"#;
+        let trie: CharRadixTrie = serde_json::from_str(json).unwrap();
+        assert_eq!(trie.node_count(), 2);
+        assert_eq!(trie.root().get(&'a').unwrap().label(), ['a', 'b']);
+        assert_eq!(trie.count_with_prefix("".chars()), 1);
+
+        for json in [
+            r#"{"root":{"label":[],"children":[{"label":[],"children":[],"end":true}],"end":false},"count":1}"#,
+            r#"{"root":{"label":[],"children":[{"label":["a"],"end":true},{"label":["a","b"],"end":true}]},"count":2}"#,
+            r#"{"root":{"label":["a"],"end":true},"count":1}"#,
+            r#"{"root":{"label":[],"end":true},"count":2}"#,
+        ] {
+            assert!(serde_json::from_str::<CharRadixTrie>(json).is_err());
+        }
+    }
+}
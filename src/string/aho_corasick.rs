@@ -0,0 +1,179 @@
+use std::{
+    io::Read,
+    str::Bytes,
+};
+
+use crate::{
+    aho_corasick::{
+        AhoCorasick,
+        FindIter,
+        MatchKind,
+        ReadMatches,
+        StreamMatcher,
+    },
+    node::TrieNode,
+};
+
+use super::hash::CharHasher;
+
+/// An [`AhoCorasick`] automaton compiled from a string trie.
+///
+/// The automaton works on the UTF-8 bytes of each string, so matches are
+/// reported as byte offsets that can be used to slice the text directly. A
+/// match always starts and ends on a character boundary.
+pub struct StringAhoCorasick(AhoCorasick<u8>);
+
+impl StringAhoCorasick {
+    /// Compiles a [`StringAhoCorasick`] automaton from every string at or
+    /// below `root`.
+    pub fn new<V>(root: &TrieNode<char, CharHasher, V>, kind: MatchKind) -> Self {
+        Self(AhoCorasick::build(root, kind, |value, buffer| {
+            let mut bytes = [0; 4];
+            buffer.extend_from_slice(value.encode_utf8(&mut bytes).as_bytes());
+        }))
+    }
+
+    /// Returns the string for a [`Match::pattern`](crate::aho_corasick::Match::pattern).
+    ///
+    /// # Panics
+    /// Panics if `pattern` is not the index of a string within the automaton.
+    pub fn key(&self, pattern: usize) -> &str {
+        // Every key was encoded from a string:
+        std::str::from_utf8(self.0.key(pattern)).unwrap()
+    }
+
+    /// Returns the number of strings within the automaton.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the automaton contains no strings.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the [`MatchKind`] the automaton was compiled with.
+    pub fn match_kind(&self) -> MatchKind {
+        self.0.match_kind()
+    }
+
+    /// Returns an iterator over every [`Match`](crate::aho_corasick::Match)
+    /// within `text`, as byte offsets.
+    pub fn find_iter<'h>(&self, text: &'h str) -> FindIter<'_, u8, Bytes<'h>> {
+        self.0.find_iter(text.bytes())
+    }
+
+    /// Returns a [`StreamMatcher`], which finds every string within a text
+    /// that arrives in chunks.
+    ///
+    /// Chunks are fed to the matcher as bytes, such as `chunk.as_bytes()`, and
+    /// may be split anywhere, including within a character. Matches are
+    /// reported as byte offsets from the start of the first chunk.
+    pub fn stream(&self) -> StreamMatcher<'_, u8> {
+        self.0.stream()
+    }
+
+    /// Returns an iterator over every [`Match`](crate::aho_corasick::Match)
+    /// within the text read from `reader`, as byte offsets.
+    ///
+    /// The text is decoded as UTF-8 while it is read. If it is not valid
+    /// UTF-8, the iterator returns an [`InvalidData`](std::io::ErrorKind::InvalidData)
+    /// error once the invalid bytes are reached.
+    pub fn stream_reader<R: Read>(&self, reader: R) -> ReadMatches<'_, R> {
+        ReadMatches::new(self.0.stream(), reader, true)
+    }
+
+    /// Returns `true` if any string occurs within `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text.bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::StringTrie;
+
+    use super::*;
+
+    #[test]
+    fn test_find_iter() {
+        let mut trie = StringTrie::new();
+        for value in ["naïve", "ïv", "café", "é", "cafe"] {
+            assert!(trie.insert(value));
+        }
+
+        let text = "a naïve café, not a cafe";
+        let automaton = trie.aho_corasick(MatchKind::Overlapping);
+        let found: Vec<&str> = automaton
+            .find_iter(text)
+            .map(|found| {
+                assert_eq!(automaton.key(found.pattern()), &text[found.range()]);
+                &text[found.range()]
+            })
+            .collect();
+        assert_eq!(found, ["ïv", "naïve", "café", "é", "cafe"]);
+
+        let automaton = trie.aho_corasick(MatchKind::LeftmostLongest);
+        let found: Vec<&str> = automaton
+            .find_iter(text)
+            .map(|found| &text[found.range()])
+            .collect();
+        assert_eq!(found, ["naïve", "café", "cafe"]);
+        assert!(automaton.is_match("résumé"));
+        assert!(!automaton.is_match("resume"));
+    }
+
+    /// A reader that returns at most a few bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.0.len().min(buf.len()).min(3);
+            buf[..read].copy_from_slice(&self.0[..read]);
+            self.0 = &self.0[read..];
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn test_stream_reader() {
+        let mut trie = StringTrie::new();
+        for value in ["日本語", "本", "ü"] {
+            assert!(trie.insert(value));
+        }
+        let automaton = trie.aho_corasick(MatchKind::Overlapping);
+
+        let text = "über 日本語 text";
+        let expected: Vec<_> = automaton.find_iter(text).collect();
+        assert_eq!(expected.len(), 3);
+
+        // Characters are split between reads:
+        let found: Vec<_> = automaton
+            .stream_reader(Trickle(text.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found, expected);
+
+        let mut stream = automaton.stream();
+        let mut found = Vec::new();
+        for chunk in text.as_bytes().chunks(2) {
+            found.extend(stream.feed(chunk));
+        }
+        found.extend(stream.finish());
+        assert_eq!(found, expected);
+
+        // Invalid UTF-8 is reported once it is reached:
+        let mut found = automaton.stream_reader(&b"\xc3\xbc \xff"[..]);
+        assert_eq!(found.next().unwrap().unwrap().range(), 0..2);
+        let error = found.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(found.next().is_none());
+
+        let error = automaton
+            .stream_reader(&b"\xe6\x97"[..])
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
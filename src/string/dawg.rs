@@ -0,0 +1,68 @@
+use crate::dawg::Dawg;
+
+/// A string wrapper over a frozen, minimised [`Dawg`].
+///
+/// This is created with [`StringTrie::freeze`](super::StringTrie::freeze).
+pub struct StringDawg(Dawg<char>);
+
+impl StringDawg {
+    pub(super) fn new(dawg: Dawg<char>) -> Self {
+        Self(dawg)
+    }
+
+    /// Returns `true` if no values are stored within the DAWG.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of values within the DAWG.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the number of nodes within the DAWG, including the root.
+    pub fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    /// Returns `true` if the DAWG contains the string, otherwise returns
+    /// `false`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.0.contains(value.chars())
+    }
+
+    /// Returns `true` if any string within the DAWG starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns an iterator over every [`String`] in the DAWG, in lexicographic
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.iter().map(String::from_iter)
+    }
+
+    /// Returns an iterator over every [`String`] in the DAWG that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = String> + '_ {
+        self.0.iter_prefix(prefix.chars()).map(String::from_iter)
+    }
+
+    /// Returns every string in the DAWG within `max_distance` edits of
+    /// `query`, along with its distance.
+    ///
+    /// Distances are counted in [`char`]s.
+    pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.0
+            .fuzzy_search(query.chars(), max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+
+    /// Returns every string in the DAWG within `max_distance` edits of
+    /// `query`, counting adjacent transpositions as a single edit.
+    pub fn fuzzy_search_transpositions(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.0
+            .fuzzy_search_transpositions(query.chars(), max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+}
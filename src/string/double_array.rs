@@ -0,0 +1,67 @@
+use std::io::{
+    self,
+    Read,
+    Write,
+};
+
+use crate::double_array::DoubleArray;
+
+use super::char_prefixes;
+
+/// A string wrapper over a read-only [`DoubleArray`].
+///
+/// Strings are stored as their UTF-8 bytes, so lookups follow the bytes of a
+/// string directly without decoding it. This is created with
+/// [`StringTrie::double_array`](super::StringTrie::double_array).
+pub struct StringDoubleArray(DoubleArray<char>);
+
+impl StringDoubleArray {
+    pub(super) fn new(array: DoubleArray<char>) -> Self {
+        Self(array)
+    }
+
+    /// Returns `true` if no values are stored within the double array.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of values within the double array.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the double array contains the string, otherwise
+    /// returns `false`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.0.contains_bytes(value.bytes())
+    }
+
+    /// Returns `true` if any string within the double array starts with
+    /// `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with_bytes(prefix.bytes())
+    }
+
+    /// Returns an iterator over every string within the double array that is
+    /// a prefix of `input`, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let lengths = self.0.prefixes_of(input.chars()).map(|length| (length, ()));
+        char_prefixes(input, lengths).map(|(prefix, _)| prefix)
+    }
+
+    /// Returns the longest string within the double array that is a prefix of
+    /// `input`.
+    pub fn longest_prefix_of<'a>(&'a self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Writes the double array to `writer`. See [`DoubleArray::write_to`].
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        self.0.write_to(writer)
+    }
+
+    /// Reads a double array written by [`StringDoubleArray::write_to`].
+    pub fn read_from(reader: impl Read) -> io::Result<Self> {
+        DoubleArray::read_from(reader).map(Self)
+    }
+}
@@ -0,0 +1,155 @@
+use std::str::Chars;
+
+use crate::pattern::Token;
+
+/// The characters matched at a single position of a glob pattern.
+enum CharClass {
+    /// Matches any character (`?`).
+    Any,
+    /// Matches a single character.
+    Literal(char),
+    /// Matches any character within one of the inclusive ranges, or outside
+    /// all of them if the set is negated (`[a-z]`, `[!aeiou]`).
+    Set {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl CharClass {
+    /// Returns `true` if `value` is matched by this class.
+    fn contains(&self, value: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Literal(literal) => *literal == value,
+            CharClass::Set { negated, ranges } => {
+                let found = ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&value));
+                found != *negated
+            },
+        }
+    }
+}
+
+/// Parses a glob pattern into [`Token`]s.
+///
+/// The pattern supports:
+/// - `?`, which matches any single character.
+/// - `*`, which matches any run of characters.
+/// - `[...]`, which matches a single character from a set. Sets may contain
+///   ranges such as `a-z`, and are negated if they start with `!` or `^`. A `]`
+///   directly after the opening bracket is part of the set.
+/// - `\`, which escapes the next character so it is matched literally.
+///
+/// A `[` without a closing `]` is matched literally.
+pub(super) fn parse(pattern: &str) -> Vec<Token<impl Fn(&char) -> bool>> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(next) = chars.next() {
+        let class = match next {
+            '?' => CharClass::Any,
+            '*' => {
+                // Consecutive runs match exactly the same keys as a single
+                // run:
+                if !matches!(tokens.last(), Some(Token::Many)) {
+                    tokens.push(Token::Many);
+                }
+                continue;
+            },
+            '\\' => CharClass::Literal(chars.next().unwrap_or('\\')),
+            '[' => match parse_set(chars.clone()) {
+                Some((set, rest)) => {
+                    chars = rest;
+                    set
+                },
+                None => CharClass::Literal('['),
+            },
+            literal => CharClass::Literal(literal),
+        };
+        tokens.push(Token::One(class));
+    }
+
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            Token::One(class) => Token::One(move |value: &char| class.contains(*value)),
+            Token::Many => Token::Many,
+        })
+        .collect()
+}
+
+/// Parses the rest of a set after its opening `[`.
+///
+/// Returns the set along with the remaining pattern, or [`None`] if the set is
+/// never closed.
+fn parse_set(mut chars: Chars<'_>) -> Option<(CharClass, Chars<'_>)> {
+    let mut rest = chars.clone();
+    let negated = matches!(rest.next(), Some('!' | '^'));
+    if negated {
+        chars = rest;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let start = chars.next()?;
+        if start == ']' && !first {
+            return Some((CharClass::Set { negated, ranges }, chars));
+        }
+        first = false;
+
+        // A `-` is only a range if it is followed by the end of the range,
+        // rather than the end of the set:
+        let mut rest = chars.clone();
+        match (rest.next(), rest.next()) {
+            (Some('-'), Some(end)) if end != ']' => {
+                ranges.push((start, end));
+                chars = rest;
+            },
+            _ => ranges.push((start, start)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `true` if `value` matches `pattern`, by checking it against the
+    /// tokens directly.
+    fn is_match(pattern: &str, value: &str) -> bool {
+        fn matches_from<P: Fn(&char) -> bool>(tokens: &[Token<P>], value: &[char]) -> bool {
+            match tokens.split_first() {
+                None => value.is_empty(),
+                Some((Token::One(predicate), tokens)) => value
+                    .split_first()
+                    .is_some_and(|(first, value)| predicate(first) && matches_from(tokens, value)),
+                Some((Token::Many, tokens)) => {
+                    (0..=value.len()).any(|skip| matches_from(tokens, &value[skip..]))
+                },
+            }
+        }
+        let value: Vec<char> = value.chars().collect();
+        matches_from(&parse(pattern), &value)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(is_match("c?t", "cat"));
+        assert!(!is_match("c?t", "ct"));
+        assert!(is_match("te*", "te"));
+        assert!(is_match("te*", "tea"));
+        assert!(is_match("*a*", "banana"));
+        assert!(is_match("[aeiou]x", "ox"));
+        assert!(!is_match("[aeiou]x", "bx"));
+        assert!(is_match("[a-c][!a-c]", "bz"));
+        assert!(!is_match("[a-c][^a-c]", "ba"));
+        assert!(is_match("[]]", "]"));
+        assert!(is_match("[a-]", "-"));
+        assert!(is_match("\\*\\?", "*?"));
+        assert!(is_match("[abc", "[abc"));
+        assert!(is_match("日?語", "日本語"));
+        assert_eq!(parse("a**b").len(), 3);
+    }
+}
@@ -2,46 +2,132 @@ use crate::node::TrieNode;
 
 use super::hash::CharHasher;
 
-pub struct StringTrieIter<'a> {
-    stack: Vec<(char, &'a TrieNode<char, CharHasher>, usize)>,
+/// Iterates over every [`String`] key and value below a [`TrieNode`].
+///
+/// By default strings are yielded in an arbitrary order. Iterators created
+/// with [`StringTrieMapIter::sorted`] yield strings in lexicographic order
+/// instead.
+pub struct StringTrieMapIter<'a, V> {
+    /// The node the iterator starts from. This is visited on the first call
+    /// to `next`, since it may store a value of its own.
+    start: Option<&'a TrieNode<char, CharHasher, V>>,
+    stack: Vec<(char, &'a TrieNode<char, CharHasher, V>, usize)>,
     buffer: String,
+    /// If `true`, the children of each node are sorted before they are
+    /// visited.
+    sorted: bool,
 }
 
-impl<'a> StringTrieIter<'a> {
-    pub fn new(root: &'a TrieNode<char, CharHasher>) -> Self {
-        let mut stack = Vec::with_capacity(
-            root.children.len()
-        );
-        for (key, child) in &root.children {
-            stack.push((*key, child, 0));
-        }
+impl<'a, V> StringTrieMapIter<'a, V> {
+    pub fn new(root: &'a TrieNode<char, CharHasher, V>) -> Self {
+        Self::with_prefix(Some(root), String::new())
+    }
+
+    /// Creates a new [`StringTrieMapIter`] over the [`TrieNode`] found at the
+    /// end of `prefix`. Every string yielded by the iterator starts with
+    /// `prefix`.
+    ///
+    /// If `node` is [`None`], the iterator is empty.
+    pub fn with_prefix(node: Option<&'a TrieNode<char, CharHasher, V>>, prefix: String) -> Self {
+        Self::create(node, prefix, false)
+    }
+
+    /// Creates a new [`StringTrieMapIter`] that yields every string starting
+    /// with `prefix` in lexicographic order.
+    ///
+    /// If `node` is [`None`], the iterator is empty.
+    pub fn sorted(node: Option<&'a TrieNode<char, CharHasher, V>>, prefix: String) -> Self {
+        Self::create(node, prefix, true)
+    }
+
+    fn create(
+        node: Option<&'a TrieNode<char, CharHasher, V>>,
+        prefix: String,
+        sorted: bool,
+    ) -> Self {
         Self {
-            stack,
-            buffer: String::new(),
+            start: node,
+            stack: Vec::with_capacity(
+                node.map_or(0, |node| node.children.len())
+            ),
+            buffer: prefix,
+            sorted,
+        }
+    }
+
+    /// Pushes the children of `node` onto the stack.
+    ///
+    /// When the iterator is sorted, the children are pushed in reverse order
+    /// so that the smallest child is popped first.
+    fn push_children(&mut self, node: &'a TrieNode<char, CharHasher, V>, depth: usize) {
+        let start = self.stack.len();
+        for (key, child) in &node.children {
+            self.stack.push((*key, child, depth));
+        }
+        if self.sorted {
+            self.stack[start..].sort_unstable_by(|(a, ..), (b, ..)| b.cmp(a));
         }
     }
 }
 
-impl Iterator for StringTrieIter<'_> {
-    type Item = String;
+impl<'a, V> Iterator for StringTrieMapIter<'a, V> {
+    type Item = (String, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
+        // The starting node is the end of the prefix itself:
+        if let Some(start) = self.start.take() {
+            self.push_children(start, self.buffer.len());
+            if let Some(value) = &start.value {
+                return Some((self.buffer.to_string(), value));
+            }
+        }
+
         // Dequeue the stack:
         while let Some((key, node, depth)) = self.stack.pop() {
             // If we're backtracking, truncate the prefix to the current depth:
             self.buffer.truncate(depth);
             self.buffer.push(key);
 
-            // End of value reached, return the buffer:
-            if node.end_of_value {
-                return Some(self.buffer.to_string());
-            }
+            // Push the current node's children onto the stack before
+            // yielding, so strings that extend this one are still visited.
+            // The depth is measured in bytes so the buffer is truncated on a
+            // character boundary when backtracking:
+            self.push_children(node, self.buffer.len());
 
-            // Push the current node's children onto the stack:
-            for (key, child) in &node.children {
-                self.stack.push((*key, child, depth + 1));
+            // End of value reached, return the buffer:
+            if let Some(value) = &node.value {
+                return Some((self.buffer.to_string(), value));
             }
         }
         None
     }
-}
\ No newline at end of file
+}
+
+/// Iterates over every [`String`] below a [`TrieNode`].
+pub struct StringTrieIter<'a>(StringTrieMapIter<'a, ()>);
+
+impl<'a> StringTrieIter<'a> {
+    pub fn new(root: &'a TrieNode<char, CharHasher>) -> Self {
+        Self(StringTrieMapIter::new(root))
+    }
+
+    /// Creates a new [`StringTrieIter`] over the strings starting with
+    /// `prefix`.
+    pub fn with_prefix(node: Option<&'a TrieNode<char, CharHasher>>, prefix: String) -> Self {
+        Self(StringTrieMapIter::with_prefix(node, prefix))
+    }
+
+    /// Creates a new [`StringTrieIter`] over the strings starting with
+    /// `prefix`, in lexicographic order.
+    pub fn sorted(node: Option<&'a TrieNode<char, CharHasher>>, prefix: String) -> Self {
+        Self(StringTrieMapIter::sorted(node, prefix))
+    }
+}
+
+impl Iterator for StringTrieIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+}
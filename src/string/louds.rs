@@ -0,0 +1,62 @@
+use crate::louds::Louds;
+
+/// A string wrapper over a read-only, succinct [`Louds`] trie.
+///
+/// This is created with [`StringTrie::louds`](super::StringTrie::louds).
+pub struct StringLouds(Louds<char>);
+
+impl StringLouds {
+    pub(super) fn new(louds: Louds<char>) -> Self {
+        Self(louds)
+    }
+
+    /// Returns `true` if no values are stored within the trie.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the number of nodes within the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    /// Returns the number of bytes used by the trie. See
+    /// [`Louds::size_in_bytes`].
+    pub fn size_in_bytes(&self) -> usize {
+        self.0.size_in_bytes()
+    }
+
+    /// Returns `true` if the trie contains the string, otherwise returns
+    /// `false`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.0.contains(value.chars())
+    }
+
+    /// Returns `true` if any string within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns the number of strings within the trie that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        self.0.count_with_prefix(prefix.chars())
+    }
+
+    /// Returns an iterator over every [`String`] in the trie, in lexicographic
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.iter().map(String::from_iter)
+    }
+
+    /// Returns an iterator over every [`String`] in the trie that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = String> + '_ {
+        self.0.iter_prefix(prefix.chars()).map(String::from_iter)
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    borrow::Cow,
+    ops::Add,
+};
+
+use crate::{
+    entry::Entry,
+    map::TrieMap,
+    node::TrieNode,
+};
+
+use super::{
+    char_prefixes,
+    glob,
+    hash::CharHasher,
+    iter::StringTrieMapIter,
+    replace,
+    segment::{
+        self,
+        Segmentations,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// A string trie wrapper that stores a value against each string.
+pub struct StringTrieMap<V>(TrieMap<char, V, CharHasher>);
+
+impl<V> Default for StringTrieMap<V> {
+    fn default() -> Self {
+        Self(TrieMap::default())
+    }
+}
+
+impl<V> StringTrieMap<V> {
+    /// Returns a new empty string trie map.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if no values are stored within the trie.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Inserts a string and value into the trie.
+    ///
+    /// Returns the previous value stored against the string, if any.
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        self.0.insert(key.chars(), value)
+    }
+
+    /// Removes a string from the trie, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        self.0.remove(key.chars())
+    }
+
+    /// Returns an immutable reference to the value stored against a string.
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.0.get(key.chars())
+    }
+
+    /// Returns a mutable reference to the value stored against a string.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.0.get_mut(key.chars())
+    }
+
+    /// Returns the [`Entry`] for a string.
+    pub fn entry(&mut self, key: &str) -> Entry<'_, char, V, CharHasher> {
+        self.0.entry(key.chars())
+    }
+
+    /// Returns `true` if the trie contains the string, otherwise returns
+    /// `false`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key.chars())
+    }
+
+    /// Returns `true` if any string within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns the number of strings within the trie that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        self.0.count_with_prefix(prefix.chars())
+    }
+
+    /// Returns the `n`th string and value in the trie, in lexicographic order
+    /// of the strings.
+    #[cfg(feature = "counts")]
+    pub fn nth(&self, n: usize) -> Option<(String, &V)> {
+        self.0
+            .nth(n)
+            .map(|(key, value)| (String::from_iter(key), value))
+    }
+
+    /// Returns the number of strings in the trie that sort before `key` in
+    /// lexicographic order.
+    #[cfg(feature = "counts")]
+    pub fn rank(&self, key: &str) -> usize {
+        self.0.rank(key.chars())
+    }
+
+    /// Returns an iterator over every string and value within the trie whose
+    /// string is a prefix of `input`, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a str) -> impl Iterator<Item = (&'a str, &'a V)> + 'a {
+        char_prefixes(input, self.0.prefixes_of(input.chars()))
+    }
+
+    /// Returns the longest string within the trie that is a prefix of `input`,
+    /// along with its value.
+    pub fn longest_prefix_of<'a>(&'a self, input: &'a str) -> Option<(&'a str, &'a V)> {
+        let found = self.0.longest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once(found)).next()
+    }
+
+    /// Returns the shortest string within the trie that is a prefix of
+    /// `input`, along with its value.
+    pub fn shortest_prefix_of<'a>(&'a self, input: &'a str) -> Option<(&'a str, &'a V)> {
+        let found = self.0.shortest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once(found)).next()
+    }
+
+    /// Returns every string and value in the trie whose string is within
+    /// `max_distance` edits of `query`, along with the distance.
+    ///
+    /// Distances are counted in [`char`]s.
+    pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, &V, usize)> + '_ {
+        self.0
+            .fuzzy_search(query.chars(), max_distance)
+            .map(|(key, value, distance)| (String::from_iter(key), value, distance))
+    }
+
+    /// Returns every string and value in the trie whose string is within
+    /// `max_distance` edits of `query`, counting adjacent transpositions as a
+    /// single edit.
+    pub fn fuzzy_search_transpositions(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, &V, usize)> + '_ {
+        self.0
+            .fuzzy_search_transpositions(query.chars(), max_distance)
+            .map(|(key, value, distance)| (String::from_iter(key), value, distance))
+    }
+
+    /// Returns every string and value in the trie whose string matches a glob
+    /// `pattern`.
+    ///
+    /// See [`StringTrie::matches`](super::StringTrie::matches) for the pattern
+    /// syntax.
+    pub fn matches(&self, pattern: &str) -> impl Iterator<Item = (String, &V)> + '_ {
+        self.0
+            .matches(glob::parse(pattern))
+            .map(|(key, value)| (String::from_iter(key), value))
+    }
+
+    /// Replaces every string within the trie that occurs in `text` with the
+    /// result of `replace`, which is given the matched string and its value.
+    ///
+    /// See [`StringTrie::replace_all`](super::StringTrie::replace_all).
+    pub fn replace_all<'a, R>(&self, text: &'a str, replace: impl FnMut(&str, &V) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(self.0.root(), text, false, replace)
+    }
+
+    /// Variant of [`StringTrieMap::replace_all`] that only replaces whole
+    /// words.
+    pub fn replace_all_words<'a, R>(&self, text: &'a str, replace: impl FnMut(&str, &V) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(self.0.root(), text, true, replace)
+    }
+
+    /// Returns an iterator over every way of splitting `input` into strings
+    /// within the trie. See [`Segmentations`].
+    pub fn segment_all<'a>(&self, input: &'a str) -> Segmentations<'a> {
+        Segmentations::new(self.0.root(), input)
+    }
+
+    /// Returns an immutable reference to the root [`TrieNode`].
+    pub fn root(&self) -> &TrieNode<char, CharHasher, V> {
+        self.0.root()
+    }
+
+    /// Returns an iterator over every [`String`] and value in the trie, in
+    /// lexicographic order of the strings.
+    ///
+    /// The children of each node are sorted as they are visited. Use
+    /// [`StringTrieMap::iter_unordered`] if the order doesn't matter.
+    pub fn iter(&self) -> StringTrieMapIter<'_, V> {
+        StringTrieMapIter::sorted(Some(self.0.root()), String::new())
+    }
+
+    /// Returns an iterator over every [`String`] and value in the trie whose
+    /// string starts with `prefix`, in lexicographic order of the strings.
+    pub fn iter_prefix(&self, prefix: &str) -> StringTrieMapIter<'_, V> {
+        StringTrieMapIter::sorted(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
+
+    /// Returns an iterator over every [`String`] and value in the trie, in
+    /// the order the child storage keeps them, which is arbitrary.
+    pub fn iter_unordered(&self) -> StringTrieMapIter<'_, V> {
+        StringTrieMapIter::new(self.0.root())
+    }
+
+    /// Returns an iterator over every [`String`] and value in the trie whose
+    /// string starts with `prefix`, in the order the child storage keeps
+    /// them.
+    pub fn iter_prefix_unordered(&self, prefix: &str) -> StringTrieMapIter<'_, V> {
+        StringTrieMapIter::with_prefix(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
+}
+
+impl<V> StringTrieMap<V>
+where
+    V: Copy + PartialOrd + Add<Output = V> + Default,
+{
+    /// Splits `input` into strings within the trie, treating each value as
+    /// the weight of its string.
+    ///
+    /// The segmentation whose values have the greatest sum is returned. If
+    /// `input` can't be split into strings within the trie, [`None`] is
+    /// returned.
+    pub fn segment<'a>(&self, input: &'a str) -> Option<Vec<&'a str>> {
+        segment::segment(self.0.root(), input, |value| *value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<V> Serialize for StringTrieMap<V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V> Deserialize<'de> for StringTrieMap<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let map = TrieMap::<char, V, CharHasher>::deserialize(deserializer)?;
+        Ok(StringTrieMap(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let map = StringTrieMap::<usize>::new();
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut map = StringTrieMap::new();
+        assert_eq!(map.insert("test", 1), None);
+        assert_eq!(map.insert("testing", 2), None);
+        assert_eq!(map.insert("test", 3), Some(1));
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get("test"), Some(&3));
+        assert_eq!(map.get("testing"), Some(&2));
+        assert_eq!(map.get("tes"), None);
+
+        *map.get_mut("testing").unwrap() *= 2;
+        assert_eq!(map.get("testing"), Some(&4));
+
+        assert_eq!(map.remove("test"), Some(3));
+        assert_eq!(map.remove("test"), None);
+        assert!(!map.contains_key("test"));
+        assert!(map.contains_key("testing"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut values = std::collections::HashMap::new();
+        values.insert("test", 1);
+        values.insert("other", 2);
+        values.insert("hello world", 3);
+
+        let mut map = StringTrieMap::new();
+        for (key, value) in values.iter() {
+            assert_eq!(map.insert(key, *value), None);
+        }
+
+        for (key, value) in map.iter() {
+            assert_eq!(values.remove(key.as_str()), Some(*value));
+        }
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_prefixes_of() {
+        let mut map = StringTrieMap::new();
+        assert_eq!(map.insert("/api", 1), None);
+        assert_eq!(map.insert("/api/users", 2), None);
+        assert_eq!(map.insert("/static", 3), None);
+
+        assert_eq!(map.longest_prefix_of("/api/users/42"), Some(("/api/users", &2)));
+        assert_eq!(map.longest_prefix_of("/api/posts"), Some(("/api", &1)));
+        assert_eq!(map.shortest_prefix_of("/api/users/42"), Some(("/api", &1)));
+        assert_eq!(map.longest_prefix_of("/other"), None);
+        assert_eq!(map.prefixes_of("/api/users").count(), 2);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let mut map = StringTrieMap::new();
+        assert_eq!(map.insert("colour", "color"), None);
+        assert_eq!(map.insert("colours", "colors"), None);
+        assert_eq!(map.insert("centre", "center"), None);
+
+        let replaced = map.replace_all("colours of the centre", |_, value| *value);
+        assert_eq!(replaced, "colors of the center");
+        let replaced = map.replace_all_words("colourful centre", |_, value| *value);
+        assert_eq!(replaced, "colourful center");
+    }
+
+    #[test]
+    fn test_segment() {
+        let mut map = StringTrieMap::new();
+        assert_eq!(map.insert("sunflower", 1.0), None);
+        assert_eq!(map.insert("sun", 2.0), None);
+        assert_eq!(map.insert("flower", 2.0), None);
+        assert_eq!(map.insert("flow", 1.0), None);
+        assert_eq!(map.insert("er", 0.5), None);
+
+        // The split with the greatest total weight is used, even though it
+        // has more words:
+        assert_eq!(map.segment("sunflower"), Some(vec!["sun", "flower"]));
+        assert_eq!(map.segment_all("sunflower").count(), 3);
+        assert_eq!(map.segment("suns"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_deserialize() {
+        use serde_json;
+
+        let mut map = StringTrieMap::new();
+        assert_eq!(map.insert("hello", "world".to_string()), None);
+        assert_eq!(map.insert("trie", "map".to_string()), None);
+
+        let serialized = serde_json::to_string(&map).unwrap();
+        let deserialized: StringTrieMap<String> = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.get("hello").map(String::as_str), Some("world"));
+        assert_eq!(deserialized.get("trie").map(String::as_str), Some("map"));
+        assert_eq!(deserialized.len(), 2);
+    }
+}
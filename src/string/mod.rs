@@ -1,11 +1,43 @@
+pub mod aho_corasick;
+pub mod dawg;
+pub mod double_array;
+mod glob;
 pub mod hash;
 pub mod iter;
+pub mod louds;
+pub mod map;
+pub mod radix;
+mod replace;
+pub mod segment;
+#[cfg(feature = "weights")]
+pub mod weighted;
+
+use std::{
+    borrow::Cow,
+    io::{
+        self,
+        Read,
+        Write,
+    },
+};
 
+use aho_corasick::StringAhoCorasick;
+use dawg::StringDawg;
+use double_array::StringDoubleArray;
 use hash::CharHasher;
 use iter::StringTrieIter;
+use louds::StringLouds;
+use segment::Segmentations;
 
 use crate::{
+    aho_corasick::MatchKind,
+    entry::Entry,
+    map::InvalidTrie,
     node::TrieNode,
+    subtrie::{
+        SubTrie,
+        SubTrieMut,
+    },
     trie::Trie,
 };
 
@@ -15,6 +47,27 @@ use serde::{
     Serialize,
 };
 
+/// Converts prefix lengths, counted in [`char`]s, into slices of `input`.
+///
+/// The lengths must be increasing. This allows the byte offset to be advanced
+/// from the previous prefix rather than counted from the start each time.
+fn char_prefixes<X>(
+    input: &str,
+    lengths: impl Iterator<Item = (usize, X)>,
+) -> impl Iterator<Item = (&str, X)> {
+    let mut offset = 0;
+    let mut chars = 0;
+    lengths.map(move |(length, extra)| {
+        offset += input[offset..]
+            .chars()
+            .take(length - chars)
+            .map(char::len_utf8)
+            .sum::<usize>();
+        chars = length;
+        (&input[..offset], extra)
+    })
+}
+
 /// A string trie wrapper.
 #[derive(Default)]
 pub struct StringTrie(Trie<char, CharHasher>);
@@ -60,6 +113,11 @@ impl StringTrie {
         self.0.remove(iter)
     }
 
+    /// Returns the [`Entry`] for a string.
+    pub fn entry(&mut self, value: &str) -> Entry<'_, char, (), CharHasher> {
+        self.0.entry(value.chars())
+    }
+
     /// Returns `true` if the trie contains the string, otherwise returns
     /// `false`.
     pub fn contains(&self, value: &str) -> bool {
@@ -72,15 +130,237 @@ impl StringTrie {
         self.0.contains(iter)
     }
 
+    /// Returns `true` if any string within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns the number of strings within the trie that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        self.0.count_with_prefix(prefix.chars())
+    }
+
+    /// Returns the `n`th string in the trie, in lexicographic order.
+    #[cfg(feature = "counts")]
+    pub fn nth(&self, n: usize) -> Option<String> {
+        self.0.nth(n).map(String::from_iter)
+    }
+
+    /// Returns the number of strings in the trie that sort before `value` in
+    /// lexicographic order.
+    #[cfg(feature = "counts")]
+    pub fn rank(&self, value: &str) -> usize {
+        self.0.rank(value.chars())
+    }
+
+    /// Returns a view of every string within the trie that starts with
+    /// `prefix`.
+    pub fn subtrie(&self, prefix: &str) -> Option<SubTrie<'_, char, CharHasher>> {
+        self.0.subtrie(prefix.chars())
+    }
+
+    /// Returns a mutable view of every string within the trie that starts
+    /// with `prefix`.
+    pub fn subtrie_mut(&mut self, prefix: &str) -> Option<SubTrieMut<'_, char, CharHasher>> {
+        self.0.subtrie_mut(prefix.chars())
+    }
+
+    /// Returns an iterator over every string within the trie that is a prefix
+    /// of `input`, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        char_prefixes(input, self.0.root().iter_prefixes_of(input.chars()))
+            .map(|(prefix, _)| prefix)
+    }
+
+    /// Returns the longest string within the trie that is a prefix of
+    /// `input`.
+    pub fn longest_prefix_of<'a>(&self, input: &'a str) -> Option<&'a str> {
+        let length = self.0.longest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once((length, ())))
+            .next()
+            .map(|(prefix, _)| prefix)
+    }
+
+    /// Returns the shortest string within the trie that is a prefix of
+    /// `input`.
+    pub fn shortest_prefix_of<'a>(&self, input: &'a str) -> Option<&'a str> {
+        let length = self.0.shortest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once((length, ())))
+            .next()
+            .map(|(prefix, _)| prefix)
+    }
+
+    /// Returns every string in the trie within `max_distance` edits of
+    /// `query`, along with its distance.
+    ///
+    /// Distances are counted in [`char`]s. See [`Trie::fuzzy_search`].
+    pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        // The query is collected up front so the iterator doesn't borrow it:
+        let query: Vec<char> = query.chars().collect();
+        self.0
+            .fuzzy_search(query, max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+
+    /// Returns every string in the trie within `max_distance` edits of
+    /// `query`, counting adjacent transpositions as a single edit.
+    ///
+    /// See [`Trie::fuzzy_search_transpositions`].
+    pub fn fuzzy_search_transpositions(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        let query: Vec<char> = query.chars().collect();
+        self.0
+            .fuzzy_search_transpositions(query, max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+
+    /// Returns every string in the trie that matches a glob `pattern`.
+    ///
+    /// `?` matches any single character, `*` matches any run of characters
+    /// and `[...]` matches a single character from a set, such as `[aeiou]`,
+    /// `[a-z]` or `[!aeiou]`. A `\` matches the next character literally.
+    pub fn matches(&self, pattern: &str) -> impl Iterator<Item = String> + '_ {
+        self.0
+            .matches(glob::parse(pattern))
+            .map(String::from_iter)
+    }
+
+    /// Replaces every string within the trie that occurs in `text` with the
+    /// result of `replace`, which is given the matched string.
+    ///
+    /// Where matches overlap, the one that starts first is replaced,
+    /// preferring the longest. The text is only copied if at least one match
+    /// is found.
+    pub fn replace_all<'a, R>(&self, text: &'a str, mut replace: impl FnMut(&str) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(self.0.root(), text, false, |matched, _| replace(matched))
+    }
+
+    /// Variant of [`StringTrie::replace_all`] that only replaces whole words.
+    ///
+    /// A match is skipped if it is preceded or followed by an alphanumeric
+    /// character or `_`.
+    pub fn replace_all_words<'a, R>(&self, text: &'a str, mut replace: impl FnMut(&str) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(self.0.root(), text, true, |matched, _| replace(matched))
+    }
+
+    /// Splits `input` into strings within the trie, using as few strings as
+    /// possible.
+    ///
+    /// Returns [`None`] if `input` can't be split into strings within the
+    /// trie. The empty string is never used.
+    pub fn segment<'a>(&self, input: &'a str) -> Option<Vec<&'a str>> {
+        segment::segment(self.0.root(), input, |_| -1isize)
+    }
+
+    /// Returns an iterator over every way of splitting `input` into strings
+    /// within the trie. See [`Segmentations`].
+    pub fn segment_all<'a>(&self, input: &'a str) -> Segmentations<'a> {
+        Segmentations::new(self.0.root(), input)
+    }
+
+    /// Compiles the trie into a [`StringAhoCorasick`] automaton, which finds
+    /// every string within a text in a single pass.
+    ///
+    /// The automaton is a snapshot, so strings added to the trie later are
+    /// not found by it.
+    pub fn aho_corasick(&self, kind: MatchKind) -> StringAhoCorasick {
+        StringAhoCorasick::new(self.0.root(), kind)
+    }
+
+    /// Freezes the trie into an immutable, minimised [`StringDawg`], which
+    /// shares the nodes of equivalent suffixes.
+    pub fn freeze(&self) -> StringDawg {
+        StringDawg::new(self.0.freeze())
+    }
+
+    /// Builds a read-only [`StringDoubleArray`] from the trie, which looks up
+    /// strings with a few array reads per byte.
+    pub fn double_array(&self) -> StringDoubleArray {
+        StringDoubleArray::new(self.0.double_array())
+    }
+
+    /// Writes the trie to `writer` in a versioned binary format, which can be
+    /// read back with [`StringTrie::read_from`].
+    ///
+    /// See [`Trie::write_to`].
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        self.0.write_to(writer)
+    }
+
+    /// Reads a trie written by [`StringTrie::write_to`].
+    ///
+    /// See [`Trie::read_from`].
+    pub fn read_from(reader: impl Read) -> io::Result<Self> {
+        Trie::read_from(reader).map(Self)
+    }
+
+    /// Writes the trie to `writer` in a compact binary form that a
+    /// [`MappedTrie`](crate::mapped::MappedTrie) can query in place. Strings
+    /// are written as their UTF-8 bytes.
+    pub fn write_mapped(&self, writer: impl Write) -> io::Result<()> {
+        self.0.write_mapped(writer)
+    }
+
+    /// Builds a read-only, succinct [`StringLouds`] trie from the trie.
+    pub fn louds(&self) -> StringLouds {
+        StringLouds::new(self.0.louds())
+    }
+
+    /// Returns the number of nodes within the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    /// Checks the invariants of the trie, visiting every node.
+    ///
+    /// See [`TrieMap::validate`](crate::map::TrieMap::validate).
+    pub fn validate(&self) -> Result<(), InvalidTrie> {
+        self.0.validate()
+    }
+
     /// Returns an immutable reference to the root [`TrieNode`].
     pub fn root(&self) -> &TrieNode<char, CharHasher> {
         self.0.root()
     }
 
-    /// Returns an iterator every [`String`] in the trie.
+    /// Returns an iterator over every [`String`] in the trie, in lexicographic
+    /// order.
+    ///
+    /// The children of each node are sorted as they are visited. Use
+    /// [`StringTrie::iter_unordered`] if the order doesn't matter.
     pub fn iter(&self) -> StringTrieIter<'_> {
+        StringTrieIter::sorted(Some(self.0.root()), String::new())
+    }
+
+    /// Returns an iterator over every [`String`] in the trie that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: &str) -> StringTrieIter<'_> {
+        StringTrieIter::sorted(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
+
+    /// Returns an iterator over every [`String`] in the trie, in the order
+    /// the child storage keeps them, which is arbitrary.
+    pub fn iter_unordered(&self) -> StringTrieIter<'_> {
         StringTrieIter::new(self.0.root())
     }
+
+    /// Returns an iterator over every [`String`] in the trie that starts with
+    /// `prefix`, in the order the child storage keeps them.
+    pub fn iter_prefix_unordered(&self, prefix: &str) -> StringTrieIter<'_> {
+        StringTrieIter::with_prefix(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -261,6 +541,294 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prefix_queries() {
+        let mut trie = StringTrie::new();
+        assert!(trie.insert("testing"));
+        assert!(trie.insert("tester"));
+        assert!(trie.insert("tesla"));
+        assert!(trie.insert("tech"));
+        assert!(trie.insert("other"));
+
+        assert!(trie.starts_with("tes"));
+        assert!(trie.starts_with("tech"));
+        assert!(!trie.starts_with("techs"));
+        assert!(!trie.starts_with("x"));
+
+        assert_eq!(trie.count_with_prefix(""), 5);
+        assert_eq!(trie.count_with_prefix("te"), 4);
+        assert_eq!(trie.count_with_prefix("test"), 2);
+        assert_eq!(trie.count_with_prefix("x"), 0);
+
+        let mut values: Vec<String> = trie.iter_prefix("tes").collect();
+        values.sort();
+        assert_eq!(values, ["tesla", "tester", "testing"]);
+        assert_eq!(trie.iter_prefix("x").count(), 0);
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn test_nth_and_rank() {
+        let mut trie = StringTrie::new();
+        for value in ["cherry", "apple", "banana", "app", "日本"] {
+            assert!(trie.insert(value));
+        }
+
+        let sorted = ["app", "apple", "banana", "cherry", "日本"];
+        for (n, value) in sorted.iter().enumerate() {
+            assert_eq!(trie.nth(n).as_deref(), Some(*value));
+            assert_eq!(trie.rank(value), n);
+        }
+        assert_eq!(trie.nth(sorted.len()), None);
+        assert_eq!(trie.rank("b"), 2);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let mut trie = StringTrie::new();
+        for value in ["receive", "recipe", "relieve", "deceive", "naïve", "native"] {
+            assert!(trie.insert(value));
+        }
+
+        let mut found: Vec<(String, usize)> = trie.fuzzy_search("recieve", 2).collect();
+        found.sort();
+        assert_eq!(found, [
+            ("receive".to_string(), 2),
+            ("recipe".to_string(), 2),
+            ("relieve".to_string(), 1),
+        ]);
+
+        // Swapping "ie" is a single edit with transpositions:
+        let mut found: Vec<(String, usize)> = trie.fuzzy_search_transpositions("recieve", 1).collect();
+        found.sort();
+        assert_eq!(found, [
+            ("receive".to_string(), 1),
+            ("relieve".to_string(), 1),
+        ]);
+
+        // Multibyte characters count as a single edit:
+        let mut found: Vec<(String, usize)> = trie.fuzzy_search("naive", 1).collect();
+        found.sort();
+        assert_eq!(found, [
+            ("native".to_string(), 1),
+            ("naïve".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut trie = StringTrie::new();
+        for value in ["cat", "cot", "cut", "coat", "ct", "tea", "teas", "ten", "tent", "te"] {
+            assert!(trie.insert(value));
+        }
+
+        let matches = |pattern: &str| {
+            let mut found: Vec<String> = trie.matches(pattern).collect();
+            found.sort();
+            found
+        };
+        assert_eq!(matches("c?t"), ["cat", "cot", "cut"]);
+        assert_eq!(matches("te*"), ["te", "tea", "teas", "ten", "tent"]);
+        assert_eq!(matches("c[ao]t"), ["cat", "cot"]);
+        assert_eq!(matches("c[!ao]t"), ["cut"]);
+        assert_eq!(matches("*t"), ["cat", "coat", "cot", "ct", "cut", "tent"]);
+        assert_eq!(matches("t?[a-m]*"), ["tea", "teas"]);
+        assert_eq!(matches("*"), trie.iter().collect::<Vec<_>>());
+        assert!(matches("dog").is_empty());
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let mut trie = StringTrie::new();
+        for value in ["cat", "cats", "at", "dog", "日本"] {
+            assert!(trie.insert(value));
+        }
+
+        // The longest match at the leftmost position is replaced:
+        let replaced = trie.replace_all("cats and a dog", |matched| "*".repeat(matched.len()));
+        assert_eq!(replaced, "**** and a ***");
+        assert_eq!(trie.replace_all("concatenate 日本語", |_| "#"), "con#en#e #語");
+
+        // Only whole words are replaced:
+        let replaced = trie.replace_all_words("cat, concatenate, cats_ dog.", |matched| matched.to_uppercase());
+        assert_eq!(replaced, "CAT, concatenate, cats_ DOG.");
+
+        // A whole word is still found if it overlaps a longer match that
+        // isn't one:
+        let mut words = StringTrie::new();
+        for value in ["one tw", "two"] {
+            assert!(words.insert(value));
+        }
+        assert_eq!(words.replace_all("one two", |matched| matched.to_uppercase()), "ONE TWo");
+        assert_eq!(words.replace_all_words("one two", |matched| matched.to_uppercase()), "one TWO");
+
+        // Text without a match isn't copied:
+        assert!(matches!(trie.replace_all("bird", |_| ""), Cow::Borrowed("bird")));
+        assert!(matches!(trie.replace_all_words("dogs", |_| ""), Cow::Borrowed("dogs")));
+        assert_eq!(trie.replace_all("cat", |_| ""), "");
+    }
+
+    #[test]
+    fn test_segment() {
+        let mut trie = StringTrie::new();
+        for value in ["new", "newyork", "york", "times", "ti", "mes", "日本", "語", "日本語"] {
+            assert!(trie.insert(value));
+        }
+
+        assert_eq!(trie.segment("newyorktimes"), Some(vec!["newyork", "times"]));
+        assert_eq!(trie.segment("日本語"), Some(vec!["日本語"]));
+        assert_eq!(trie.segment(""), Some(vec![]));
+        assert_eq!(trie.segment("newyorkcity"), None);
+
+        let all: Vec<Vec<&str>> = trie.segment_all("newyorktimes").collect();
+        assert_eq!(all, [
+            vec!["new", "york", "ti", "mes"],
+            vec!["new", "york", "times"],
+            vec!["newyork", "ti", "mes"],
+            vec!["newyork", "times"],
+        ]);
+        assert_eq!(trie.segment_all("日本語").count(), 2);
+        assert_eq!(trie.segment_all("newyorkcity").count(), 0);
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut trie = StringTrie::new();
+        for value in ["nation", "station", "naïve", "naïvety", "ration"] {
+            assert!(trie.insert(value));
+        }
+
+        let dawg = trie.freeze();
+        assert_eq!(dawg.len(), 5);
+        assert!(dawg.node_count() < trie.node_count());
+        assert!(dawg.contains("naïve"));
+        assert!(!dawg.contains("ation"));
+        assert_eq!(dawg.iter_prefix("na").collect::<Vec<_>>(), ["nation", "naïve", "naïvety"]);
+        assert_eq!(
+            dawg.fuzzy_search("nation", 1).collect::<Vec<_>>(),
+            [("nation".to_string(), 0), ("ration".to_string(), 1)],
+        );
+    }
+
+    #[test]
+    fn test_double_array() {
+        let mut trie = StringTrie::new();
+        for value in ["new", "newyork", "日本", "日本語", "über"] {
+            assert!(trie.insert(value));
+        }
+
+        let mut bytes = Vec::new();
+        trie.double_array().write_to(&mut bytes).unwrap();
+        let array = StringDoubleArray::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(array.len(), 5);
+        for value in trie.iter() {
+            assert!(array.contains(&value));
+        }
+        assert!(!array.contains("日"));
+        assert!(!array.contains("ü"));
+        assert!(array.starts_with("ü"));
+        assert_eq!(array.longest_prefix_of("日本語です"), Some("日本語"));
+        assert_eq!(array.prefixes_of("newyorktimes").collect::<Vec<_>>(), ["new", "newyork"]);
+    }
+
+    #[test]
+    fn test_write_read() {
+        let mut trie = StringTrie::new();
+        for value in ["", "new", "newyork", "日本", "日本語", "über"] {
+            assert!(trie.insert(value));
+        }
+
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        let read = StringTrie::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(read.len(), 6);
+        assert_eq!(read.iter().collect::<Vec<_>>(), trie.iter().collect::<Vec<_>>());
+
+        bytes[0] = b'X';
+        let error = StringTrie::read_from(bytes.as_slice()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_louds() {
+        let mut trie = StringTrie::new();
+        for value in ["testing", "tester", "tesla", "tech", "日本", "日本語"] {
+            assert!(trie.insert(value));
+        }
+
+        let louds = trie.louds();
+        assert_eq!(louds.len(), 6);
+        assert!(louds.contains("日本語"));
+        assert!(!louds.contains("test"));
+        assert!(louds.starts_with("test"));
+        assert_eq!(louds.count_with_prefix("tes"), 3);
+        assert_eq!(louds.count_with_prefix("日"), 2);
+        assert_eq!(louds.iter_prefix("te").collect::<Vec<_>>(), ["tech", "tesla", "tester", "testing"]);
+        assert_eq!(louds.iter().count(), 6);
+    }
+
+    #[test]
+    fn test_iter_nested() {
+        let mut trie = StringTrie::new();
+        for value in ["", "a", "ab", "abc", "日", "日本", "日本語", "b"] {
+            assert!(trie.insert(value));
+        }
+
+        assert_eq!(trie.iter().count(), 8);
+        assert_eq!(
+            trie.iter().collect::<Vec<_>>(),
+            ["", "a", "ab", "abc", "b", "日", "日本", "日本語"],
+        );
+        assert_eq!(trie.iter_prefix("日").collect::<Vec<_>>(), ["日", "日本", "日本語"]);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let mut trie = StringTrie::new();
+        for value in ["delta", "alpha", "charlie", "bravo", "echo", "ábc"] {
+            assert!(trie.insert(value));
+        }
+
+        let sorted: Vec<String> = trie.iter().collect();
+        assert_eq!(sorted, ["alpha", "bravo", "charlie", "delta", "echo", "ábc"]);
+        assert_eq!(trie.iter_prefix("c").collect::<Vec<_>>(), ["charlie"]);
+    }
+
+    #[test]
+    fn test_prefixes_of() {
+        let mut trie = StringTrie::new();
+        assert!(trie.insert("new"));
+        assert!(trie.insert("newyork"));
+        assert!(trie.insert("日本"));
+        assert!(trie.insert("日本語"));
+
+        assert_eq!(trie.prefixes_of("newyorktimes").collect::<Vec<_>>(), ["new", "newyork"]);
+        assert_eq!(trie.longest_prefix_of("newyorktimes"), Some("newyork"));
+        assert_eq!(trie.shortest_prefix_of("newyorktimes"), Some("new"));
+        assert_eq!(trie.longest_prefix_of("newton"), Some("new"));
+        assert_eq!(trie.longest_prefix_of("ne"), None);
+
+        // Prefixes are sliced on character boundaries:
+        assert_eq!(trie.prefixes_of("日本語です").collect::<Vec<_>>(), ["日本", "日本語"]);
+        assert_eq!(trie.longest_prefix_of("日本です"), Some("日本"));
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut trie = StringTrie::new();
+        trie.entry("test").or_insert(());
+        trie.entry("test").or_insert(());
+        assert!(trie.contains("test"));
+        assert_eq!(trie.len(), 1);
+
+        match trie.entry("test") {
+            Entry::Occupied(entry) => entry.remove(),
+            Entry::Vacant(..) => panic!("expected an occupied entry"),
+        }
+        assert!(!trie.contains("test"));
+        assert!(trie.is_empty());
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serialize_deserialize() {
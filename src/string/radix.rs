@@ -0,0 +1,488 @@
+use std::{
+    borrow::Cow,
+    io::{
+        self,
+        Read,
+        Write,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    aho_corasick::MatchKind,
+    map::InvalidTrie,
+    radix::{
+        RadixEntry,
+        RadixNode,
+        RadixSubTrie,
+        RadixSubTrieMut,
+        RadixTrie,
+    },
+};
+
+use super::{
+    aho_corasick::StringAhoCorasick,
+    char_prefixes,
+    dawg::StringDawg,
+    double_array::StringDoubleArray,
+    glob,
+    hash::CharHasher,
+    louds::StringLouds,
+    replace,
+    segment::{
+        self,
+        Segmentations,
+    },
+};
+
+/// A string radix trie wrapper.
+///
+/// See [`RadixTrie`].
+#[derive(Default)]
+pub struct StringRadixTrie(RadixTrie<char, CharHasher>);
+
+impl StringRadixTrie {
+    /// Returns a new empty string radix trie.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if no values are stored within the trie.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of values within the trie.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Inserts a string into the trie.
+    pub fn insert(&mut self, value: &str) -> bool {
+        self.0.insert(value.chars())
+    }
+
+    /// Inserts a string into the trie.
+    pub fn insert_iter(&mut self, iter: impl Iterator<Item = char>) -> bool {
+        self.0.insert(iter)
+    }
+
+    /// Removes a string from the trie.
+    pub fn remove(&mut self, value: &str) -> bool {
+        self.0.remove(value.chars())
+    }
+
+    /// Removes a string from the trie.
+    pub fn remove_iter(&mut self, iter: impl Iterator<Item = char>) -> bool {
+        self.0.remove(iter)
+    }
+
+    /// Returns the [`RadixEntry`] for a string, which can be used to insert
+    /// or remove it.
+    pub fn entry(&mut self, value: &str) -> RadixEntry<'_, char, CharHasher> {
+        self.0.entry(value.chars())
+    }
+
+    /// Returns `true` if the trie contains the string, otherwise returns
+    /// `false`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.0.contains(value.chars())
+    }
+
+    /// Returns `true` if the trie contains the string, otherwise returns
+    /// `false`.
+    pub fn contains_iter(&self, iter: impl Iterator<Item = char>) -> bool {
+        self.0.contains(iter)
+    }
+
+    /// Returns `true` if any string within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns the number of strings within the trie that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        self.0.count_with_prefix(prefix.chars())
+    }
+
+    /// Returns the `n`th string in the trie, in lexicographic order.
+    #[cfg(feature = "counts")]
+    pub fn nth(&self, n: usize) -> Option<String> {
+        self.0.nth(n).map(String::from_iter)
+    }
+
+    /// Returns the number of strings in the trie that sort before `value` in
+    /// lexicographic order.
+    #[cfg(feature = "counts")]
+    pub fn rank(&self, value: &str) -> usize {
+        self.0.rank(value.chars())
+    }
+
+    /// Returns a view of every string within the trie that starts with
+    /// `prefix`.
+    pub fn subtrie(&self, prefix: &str) -> Option<RadixSubTrie<'_, char, CharHasher>> {
+        self.0.subtrie(prefix.chars())
+    }
+
+    /// Returns a mutable view of every string within the trie that starts
+    /// with `prefix`.
+    pub fn subtrie_mut(&mut self, prefix: &str) -> Option<RadixSubTrieMut<'_, char, CharHasher>> {
+        self.0.subtrie_mut(prefix.chars())
+    }
+
+    /// Returns an iterator over every string within the trie that is a prefix
+    /// of `input`, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let lengths = self.0.prefixes_of(input.chars()).map(|length| (length, ()));
+        char_prefixes(input, lengths).map(|(prefix, _)| prefix)
+    }
+
+    /// Returns the longest string within the trie that is a prefix of
+    /// `input`.
+    pub fn longest_prefix_of<'a>(&'a self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns the shortest string within the trie that is a prefix of
+    /// `input`.
+    pub fn shortest_prefix_of<'a>(&'a self, input: &'a str) -> Option<&'a str> {
+        self.prefixes_of(input).next()
+    }
+
+    /// Returns every string in the trie within `max_distance` edits of
+    /// `query`, along with the distance.
+    ///
+    /// Distances are counted in [`char`]s.
+    pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.0
+            .fuzzy_search(query.chars(), max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+
+    /// Returns every string in the trie within `max_distance` edits of
+    /// `query`, counting adjacent transpositions as a single edit.
+    pub fn fuzzy_search_transpositions(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.0
+            .fuzzy_search_transpositions(query.chars(), max_distance)
+            .map(|(key, distance)| (String::from_iter(key), distance))
+    }
+
+    /// Returns every string in the trie that matches a glob `pattern`.
+    ///
+    /// See [`StringTrie::matches`](super::StringTrie::matches) for the pattern
+    /// syntax.
+    pub fn matches(&self, pattern: &str) -> impl Iterator<Item = String> + '_ {
+        self.0
+            .matches(glob::parse(pattern))
+            .map(String::from_iter)
+    }
+
+    /// Replaces every string within the trie that occurs in `text` with the
+    /// result of `replace`, which is given the matched string.
+    ///
+    /// See [`StringTrie::replace_all`](super::StringTrie::replace_all).
+    pub fn replace_all<'a, R>(&self, text: &'a str, mut replace: impl FnMut(&str) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(&self.0.expand(), text, false, |matched, _| replace(matched))
+    }
+
+    /// Variant of [`StringRadixTrie::replace_all`] that only replaces whole
+    /// words.
+    ///
+    /// See [`StringTrie::replace_all_words`](super::StringTrie::replace_all_words).
+    pub fn replace_all_words<'a, R>(&self, text: &'a str, mut replace: impl FnMut(&str) -> R) -> Cow<'a, str>
+    where
+        R: AsRef<str>,
+    {
+        replace::replace_all(&self.0.expand(), text, true, |matched, _| replace(matched))
+    }
+
+    /// Splits `input` into strings within the trie, using as few strings as
+    /// possible.
+    ///
+    /// Returns [`None`] if `input` can't be split into strings within the
+    /// trie. The empty string is never used.
+    pub fn segment<'a>(&self, input: &'a str) -> Option<Vec<&'a str>> {
+        segment::segment(&self.0.expand(), input, |_| -1isize)
+    }
+
+    /// Returns an iterator over every way of splitting `input` into strings
+    /// within the trie. See [`Segmentations`].
+    pub fn segment_all<'a>(&self, input: &'a str) -> Segmentations<'a> {
+        Segmentations::new(&self.0.expand(), input)
+    }
+
+    /// Compiles the trie into a [`StringAhoCorasick`] automaton, which finds
+    /// every string within a text in a single pass.
+    pub fn aho_corasick(&self, kind: MatchKind) -> StringAhoCorasick {
+        StringAhoCorasick::new(&self.0.expand(), kind)
+    }
+
+    /// Freezes the trie into an immutable, minimised [`StringDawg`].
+    pub fn freeze(&self) -> StringDawg {
+        StringDawg::new(self.0.freeze())
+    }
+
+    /// Builds a read-only [`StringDoubleArray`] from the trie.
+    pub fn double_array(&self) -> StringDoubleArray {
+        StringDoubleArray::new(self.0.double_array())
+    }
+
+    /// Writes the trie to `writer` in the same binary format as
+    /// [`StringTrie::write_to`](super::StringTrie::write_to).
+    ///
+    /// See [`RadixTrie::write_to`].
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        self.0.write_to(writer)
+    }
+
+    /// Reads a trie written by [`StringRadixTrie::write_to`] or
+    /// [`StringTrie::write_to`](super::StringTrie::write_to).
+    pub fn read_from(reader: impl Read) -> io::Result<Self> {
+        RadixTrie::read_from(reader).map(Self)
+    }
+
+    /// Writes the trie to `writer` in a compact binary form that a
+    /// [`MappedTrie`](crate::mapped::MappedTrie) can query in place. Strings
+    /// are written as their UTF-8 bytes.
+    pub fn write_mapped(&self, writer: impl Write) -> io::Result<()> {
+        self.0.write_mapped(writer)
+    }
+
+    /// Builds a read-only, succinct [`StringLouds`] trie from the trie.
+    pub fn louds(&self) -> StringLouds {
+        StringLouds::new(self.0.louds())
+    }
+
+    /// Returns the number of nodes within the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    /// Checks the invariants of the trie, visiting every node.
+    ///
+    /// See [`RadixTrie::validate`].
+    pub fn validate(&self) -> Result<(), InvalidTrie> {
+        self.0.validate()
+    }
+
+    /// Returns an immutable reference to the root [`RadixNode`].
+    pub fn root(&self) -> &RadixNode<char, CharHasher> {
+        self.0.root()
+    }
+
+    /// Returns an iterator over every [`String`] in the trie, in lexicographic
+    /// order.
+    ///
+    /// Use [`StringRadixTrie::iter_unordered`] if the order doesn't matter.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.iter().map(String::from_iter)
+    }
+
+    /// Returns an iterator over every [`String`] in the trie that starts with
+    /// `prefix`, in lexicographic order.
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = String> + '_ {
+        self.0.iter_prefix(prefix.chars()).map(String::from_iter)
+    }
+
+    /// Returns an iterator over every [`String`] in the trie, in the order
+    /// the child storage keeps them, which is arbitrary.
+    pub fn iter_unordered(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.iter_unordered().map(String::from_iter)
+    }
+
+    /// Returns an iterator over every [`String`] in the trie that starts with
+    /// `prefix`, in the order the child storage keeps them.
+    pub fn iter_prefix_unordered(&self, prefix: &str) -> impl Iterator<Item = String> + '_ {
+        self.0.iter_prefix_unordered(prefix.chars()).map(String::from_iter)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for StringRadixTrie {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for StringRadixTrie {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let trie = RadixTrie::<char, CharHasher>::deserialize(deserializer)?;
+        Ok(StringRadixTrie(trie))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_radix_trie() {
+        let mut trie = StringRadixTrie::new();
+        for value in ["日本", "日本語", "internationalization", "international"] {
+            assert!(trie.insert(value));
+        }
+        assert!(!trie.insert("日本"));
+
+        assert!(trie.contains("日本語"));
+        assert!(!trie.contains("日"));
+        assert!(trie.starts_with("日"));
+        assert_eq!(trie.count_with_prefix("inter"), 2);
+        assert_eq!(trie.longest_prefix_of("日本語です"), Some("日本語"));
+        assert_eq!(trie.prefixes_of("日本語").collect::<Vec<_>>(), ["日本", "日本語"]);
+        assert_eq!(
+            trie.iter().collect::<Vec<_>>(),
+            ["international", "internationalization", "日本", "日本語"],
+        );
+
+        // root -> international -> ization
+        //      -> 日本 -> 語
+        assert_eq!(trie.node_count(), 5);
+
+        assert!(trie.remove("international"));
+        assert_eq!(trie.node_count(), 4);
+        assert_eq!(trie.iter_prefix("intern").collect::<Vec<_>>(), ["internationalization"]);
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut trie = StringRadixTrie::new();
+        for value in ["日本", "日本語", "日曜日", "月曜日"] {
+            assert!(trie.insert(value));
+        }
+
+        let mut found: Vec<String> = trie.matches("?曜日").collect();
+        found.sort();
+        assert_eq!(found, ["日曜日", "月曜日"]);
+        let mut found: Vec<(String, usize)> = trie.fuzzy_search("日本人", 1).collect();
+        found.sort();
+        assert_eq!(found, [("日本".to_string(), 1), ("日本語".to_string(), 1)]);
+
+        let subtrie = trie.subtrie("日本").unwrap();
+        assert!(subtrie.contains("語".chars()));
+        assert_eq!(subtrie.len(), 2);
+
+        trie.entry("火曜日").or_insert(());
+        assert_eq!(trie.subtrie_mut("火").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_iter_methods() {
+        let mut trie = StringRadixTrie::new();
+        assert!(trie.insert_iter("日本".chars()));
+        assert!(trie.insert_iter("日本語".chars()));
+        assert!(trie.contains_iter("日本".chars()));
+        assert!(trie.remove_iter("日本".chars()));
+        assert!(!trie.contains_iter("日本".chars()));
+        assert_eq!(trie.iter_unordered().collect::<Vec<_>>(), ["日本語"]);
+        assert_eq!(trie.iter_prefix_unordered("日").count(), 1);
+        assert_eq!(trie.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_text() {
+        let mut trie = StringRadixTrie::new();
+        for value in ["cat", "cats", "at", "dog", "日本"] {
+            assert!(trie.insert(value));
+        }
+
+        let replaced = trie.replace_all("cats and a dog", |matched| "*".repeat(matched.len()));
+        assert_eq!(replaced, "**** and a ***");
+        assert_eq!(trie.replace_all("concatenate 日本語", |_| "#"), "con#en#e #語");
+        let replaced = trie.replace_all_words("cat, concatenate, cats_ dog.", |matched| matched.to_uppercase());
+        assert_eq!(replaced, "CAT, concatenate, cats_ DOG.");
+        assert!(matches!(trie.replace_all("bird", |_| ""), Cow::Borrowed("bird")));
+
+        let found: Vec<_> = trie
+            .aho_corasick(MatchKind::LeftmostLongest)
+            .find_iter("a cats")
+            .map(|found| (found.start(), found.end()))
+            .collect();
+        assert_eq!(found, [(2, 6)]);
+
+        let mut words = StringRadixTrie::new();
+        for value in ["new", "newyork", "york", "times", "ti", "mes"] {
+            assert!(words.insert(value));
+        }
+        assert_eq!(words.segment("newyorktimes"), Some(vec!["newyork", "times"]));
+        assert_eq!(words.segment("newyorkcity"), None);
+        assert_eq!(words.segment_all("newyorktimes").count(), 4);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut trie = StringRadixTrie::new();
+        for value in ["nation", "station", "naïve", "naïvety", "ration"] {
+            assert!(trie.insert(value));
+        }
+
+        let expected = ["nation", "naïve", "naïvety", "ration", "station"];
+        assert_eq!(trie.freeze().iter().collect::<Vec<_>>(), expected);
+        assert_eq!(trie.louds().iter().collect::<Vec<_>>(), expected);
+        let array = trie.double_array();
+        assert!(expected.iter().all(|value| array.contains(value)));
+        assert!(!array.contains("na"));
+
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        let read = StringRadixTrie::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(read.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(read.node_count(), trie.node_count());
+
+        // A `StringTrie` reads the same format:
+        let read = super::super::StringTrie::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(read.iter().collect::<Vec<_>>(), expected);
+
+        let mut mapped = Vec::new();
+        trie.write_mapped(&mut mapped).unwrap();
+        let mapped = crate::mapped::MappedTrie::new(&mapped).unwrap();
+        assert!(mapped.contains("naïvety"));
+        assert_eq!(mapped.len(), 5);
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn test_nth_rank() {
+        let mut trie = StringRadixTrie::new();
+        for value in ["b", "a", "ab"] {
+            assert!(trie.insert(value));
+        }
+        assert_eq!(trie.nth(1).as_deref(), Some("ab"));
+        assert_eq!(trie.rank("b"), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use serde_json;
+
+        let mut trie = StringRadixTrie::new();
+        for value in ["internet", "interval"] {
+            assert!(trie.insert(value));
+        }
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: StringRadixTrie = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.iter().collect::<Vec<_>>(), ["internet", "interval"]);
+        assert_eq!(deserialized.node_count(), 4);
+    }
+}
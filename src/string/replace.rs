@@ -0,0 +1,95 @@
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+};
+
+use crate::{
+    aho_corasick::{
+        Match,
+        MatchKind,
+    },
+    node::TrieNode,
+};
+
+use super::{
+    aho_corasick::StringAhoCorasick,
+    hash::CharHasher,
+};
+
+/// Returns `true` if `value` is part of a word, for whole-word matching.
+fn is_word_char(value: char) -> bool {
+    value.is_alphanumeric() || value == '_'
+}
+
+/// Returns `true` if `found` is not preceded or followed by a word character
+/// within `text`.
+fn is_whole_word(text: &str, found: &Match) -> bool {
+    !text[..found.start()].ends_with(is_word_char) && !text[found.end()..].starts_with(is_word_char)
+}
+
+/// Replaces every string at or below `root` that occurs within `text`.
+///
+/// Matches are found leftmost-longest: the match that starts first is
+/// replaced, preferring the longest string if several start at the same
+/// position, and scanning then resumes after it. If `words` is set, a match
+/// must not be preceded or followed by a word character.
+///
+/// The strings are compiled into a [`StringAhoCorasick`] automaton, so the
+/// text is only read once however long the strings are. `text` is only
+/// copied if at least one match is found.
+pub(super) fn replace_all<'a, V, R>(
+    root: &TrieNode<char, CharHasher, V>,
+    text: &'a str,
+    words: bool,
+    mut replace: impl FnMut(&str, &V) -> R,
+) -> Cow<'a, str>
+where
+    R: AsRef<str>,
+{
+    let found: Vec<Match> = match words {
+        false => StringAhoCorasick::new(root, MatchKind::LeftmostLongest)
+            .find_iter(text)
+            .collect(),
+        true => {
+            // The leftmost-longest match may not be a whole word while a
+            // shorter or overlapping one is, so every whole word is found
+            // before picking the leftmost-longest of them:
+            let mut found: Vec<Match> = StringAhoCorasick::new(root, MatchKind::Overlapping)
+                .find_iter(text)
+                .filter(|found| is_whole_word(text, found))
+                .collect();
+            found.sort_unstable_by_key(|found| (found.start(), Reverse(found.end())));
+            let mut resume = 0;
+            found.retain(|found| {
+                let keep = found.start() >= resume;
+                if keep {
+                    resume = found.end();
+                }
+                keep
+            });
+            found
+        },
+    };
+
+    let mut output = String::new();
+    // The offset up to which `text` has been written to `output`:
+    let mut copied = 0;
+    for found in found {
+        let matched = &text[found.range()];
+        // Every match is a key of the trie, so it has a value:
+        let value = root
+            .walk(matched.chars())
+            .and_then(|node| node.value.as_ref())
+            .unwrap();
+        output.push_str(&text[copied..found.start()]);
+        output.push_str(replace(matched, value).as_ref());
+        copied = found.end();
+    }
+
+    // Nothing was replaced, so the text can be returned as it is:
+    if copied == 0 {
+        return Cow::Borrowed(text);
+    }
+    output.push_str(&text[copied..]);
+    Cow::Owned(output)
+}
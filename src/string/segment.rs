@@ -0,0 +1,135 @@
+use std::ops::Add;
+
+use crate::node::TrieNode;
+
+use super::{
+    char_prefixes,
+    hash::CharHasher,
+};
+
+/// Returns the byte length of every non-empty string at or below `root` that
+/// is a prefix of `input`, along with its value, from shortest to longest.
+fn words<'a, V>(
+    root: &'a TrieNode<char, CharHasher, V>,
+    input: &'a str,
+) -> impl Iterator<Item = (usize, &'a V)> + 'a {
+    // The empty string is never used as a word, since it would never advance
+    // through the input:
+    let lengths = root
+        .iter_prefixes_of(input.chars())
+        .filter(|(length, _)| *length > 0);
+    char_prefixes(input, lengths).map(|(word, value)| (word.len(), value))
+}
+
+/// Splits `input` into strings at or below `root`, choosing the
+/// segmentation with the greatest total weight.
+///
+/// Each word is given a weight by `weight`, and the weight of a segmentation
+/// is the sum of its words' weights. Returns [`None`] if `input` can't be
+/// split into words.
+pub(super) fn segment<'a, V, W>(
+    root: &TrieNode<char, CharHasher, V>,
+    input: &'a str,
+    weight: impl Fn(&V) -> W,
+) -> Option<Vec<&'a str>>
+where
+    W: Copy + PartialOrd + Add<Output = W> + Default,
+{
+    // The best segmentation of `input[..end]` for each byte offset `end`,
+    // stored as its weight and the offset where its last word starts:
+    let mut best: Vec<Option<(W, usize)>> = vec![None; input.len() + 1];
+    best[0] = Some((W::default(), 0));
+    for (start, _) in input.char_indices() {
+        let Some((total, _)) = best[start] else {
+            continue;
+        };
+        for (length, value) in words(root, &input[start..]) {
+            let total = total + weight(value);
+            let end = start + length;
+            if best[end].is_none_or(|(current, _)| total > current) {
+                best[end] = Some((total, start));
+            }
+        }
+    }
+
+    // Follow the segmentation back from the end of the input:
+    best[input.len()]?;
+    let mut segments = Vec::new();
+    let mut end = input.len();
+    while end > 0 {
+        let (_, start) = best[end]?;
+        segments.push(&input[start..end]);
+        end = start;
+    }
+    segments.reverse();
+    Some(segments)
+}
+
+/// Iterates over every way of splitting a string into words stored within a
+/// trie.
+///
+/// Segmentations are yielded in lexicographic order of their word lengths,
+/// so those that start with shorter words come first. The number of
+/// segmentations can grow exponentially with the length of the input.
+pub struct Segmentations<'a> {
+    input: &'a str,
+    /// The offsets each word starting at a byte offset can end at, such that
+    /// the rest of the input can also be split into words.
+    ends: Vec<Vec<usize>>,
+    /// The offset of each word in the current segmentation, along with the
+    /// index of the next end to try from it.
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a> Segmentations<'a> {
+    pub(super) fn new<V>(root: &TrieNode<char, CharHasher, V>, input: &'a str) -> Self {
+        // Work back from the end of the input, so only words that are
+        // followed by a complete segmentation are kept:
+        let mut ends = vec![Vec::new(); input.len() + 1];
+        for (start, _) in input.char_indices().rev() {
+            ends[start] = words(root, &input[start..])
+                .map(|(length, _)| start + length)
+                .filter(|end| *end == input.len() || !ends[*end].is_empty())
+                .collect();
+        }
+
+        let complete = input.is_empty() || !ends[0].is_empty();
+        Self {
+            input,
+            ends,
+            stack: match complete {
+                true => vec![(0, 0)],
+                false => Vec::new(),
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Segmentations<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((start, index)) = self.stack.last_mut() {
+            if *start == self.input.len() {
+                let segments = self
+                    .stack
+                    .windows(2)
+                    .map(|pair| &self.input[pair[0].0..pair[1].0])
+                    .collect();
+                self.stack.pop();
+                return Some(segments);
+            }
+
+            match self.ends[*start].get(*index) {
+                Some(end) => {
+                    *index += 1;
+                    self.stack.push((*end, 0));
+                },
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,287 @@
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    node::TrieNode,
+    weighted::{
+        WeightedEntry,
+        WeightedTrie,
+    },
+};
+
+use super::{
+    char_prefixes,
+    glob,
+    hash::CharHasher,
+    iter::StringTrieMapIter,
+};
+
+/// A string trie wrapper that stores a weight, such as a popularity score,
+/// against each string.
+///
+/// See [`WeightedTrie`].
+pub struct StringWeightedTrie<W>(WeightedTrie<char, W, CharHasher>);
+
+impl<W> Default for StringWeightedTrie<W> {
+    fn default() -> Self {
+        Self(WeightedTrie::default())
+    }
+}
+
+impl<W> StringWeightedTrie<W>
+where
+    W: Ord + Copy,
+{
+    /// Returns a new empty weighted string trie.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if no strings are stored within the trie.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of strings within the trie.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Inserts a string with a weight into the trie.
+    ///
+    /// Returns the previous weight stored against the string, if any.
+    pub fn insert(&mut self, value: &str, weight: W) -> Option<W> {
+        self.0.insert(value.chars(), weight)
+    }
+
+    /// Removes a string from the trie, returning its weight.
+    pub fn remove(&mut self, value: &str) -> Option<W> {
+        self.0.remove(value.chars())
+    }
+
+    /// Returns the weight stored against a string.
+    pub fn get(&self, value: &str) -> Option<W> {
+        self.0.get(value.chars())
+    }
+
+    /// Returns the [`WeightedEntry`] for a string, so its weight can be read
+    /// or changed without walking the trie again.
+    pub fn entry(&mut self, value: &str) -> WeightedEntry<'_, char, W, CharHasher> {
+        self.0.entry(value.chars())
+    }
+
+    /// Returns `true` if the trie contains the string, otherwise returns
+    /// `false`.
+    pub fn contains(&self, value: &str) -> bool {
+        self.0.contains(value.chars())
+    }
+
+    /// Returns `true` if any string within the trie starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.chars())
+    }
+
+    /// Returns the number of strings within the trie that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        self.0.count_with_prefix(prefix.chars())
+    }
+
+    /// Returns the greatest weight of any string that starts with `prefix`.
+    pub fn max_weight(&self, prefix: &str) -> Option<W> {
+        self.0.max_weight(prefix.chars())
+    }
+
+    /// Returns an iterator over every string within the trie that is a prefix
+    /// of `input`, along with its weight, from shortest to longest.
+    pub fn prefixes_of<'a>(&'a self, input: &'a str) -> impl Iterator<Item = (&'a str, W)> + 'a {
+        char_prefixes(input, self.0.prefixes_of(input.chars()))
+            .map(|(prefix, weight)| (prefix, *weight))
+    }
+
+    /// Returns the longest string within the trie that is a prefix of `input`,
+    /// along with its weight.
+    pub fn longest_prefix_of<'a>(&'a self, input: &'a str) -> Option<(&'a str, W)> {
+        let found = self.0.longest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once(found)).next()
+    }
+
+    /// Returns the shortest string within the trie that is a prefix of
+    /// `input`, along with its weight.
+    pub fn shortest_prefix_of<'a>(&'a self, input: &'a str) -> Option<(&'a str, W)> {
+        let found = self.0.shortest_prefix_of(input.chars())?;
+        char_prefixes(input, std::iter::once(found)).next()
+    }
+
+    /// Returns every string and weight in the trie whose string is within
+    /// `max_distance` edits of `query`, along with the distance.
+    ///
+    /// Distances are counted in [`char`]s.
+    pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, W, usize)> + '_ {
+        self.0
+            .fuzzy_search(query.chars(), max_distance)
+            .map(|(key, weight, distance)| (String::from_iter(key), *weight, distance))
+    }
+
+    /// Returns every string and weight in the trie whose string is within
+    /// `max_distance` edits of `query`, counting adjacent transpositions as a
+    /// single edit.
+    pub fn fuzzy_search_transpositions(&self, query: &str, max_distance: usize) -> impl Iterator<Item = (String, W, usize)> + '_ {
+        self.0
+            .fuzzy_search_transpositions(query.chars(), max_distance)
+            .map(|(key, weight, distance)| (String::from_iter(key), *weight, distance))
+    }
+
+    /// Returns every string and weight in the trie whose string matches a
+    /// glob `pattern`.
+    ///
+    /// See [`StringTrie::matches`](super::StringTrie::matches) for the pattern
+    /// syntax.
+    pub fn matches(&self, pattern: &str) -> impl Iterator<Item = (String, W)> + '_ {
+        self.0
+            .matches(glob::parse(pattern))
+            .map(|(key, weight)| (String::from_iter(key), *weight))
+    }
+
+    /// Returns the `k` heaviest strings that start with `prefix`, along with
+    /// their weights, from heaviest to lightest.
+    ///
+    /// See [`WeightedTrie::top_k_completions`].
+    pub fn top_k_completions(&self, prefix: &str, k: usize) -> Vec<(String, W)> {
+        self.0
+            .top_k_completions(prefix.chars(), k)
+            .into_iter()
+            .map(|(key, weight)| (String::from_iter(key), weight))
+            .collect()
+    }
+
+    /// Returns an immutable reference to the root [`TrieNode`].
+    pub fn root(&self) -> &TrieNode<char, CharHasher, W> {
+        self.0.root()
+    }
+
+    /// Returns an iterator over every [`String`] and weight in the trie, in
+    /// lexicographic order of the strings.
+    ///
+    /// The children of each node are sorted as they are visited. Use
+    /// [`StringWeightedTrie::iter_unordered`] if the order doesn't matter.
+    pub fn iter(&self) -> StringTrieMapIter<'_, W> {
+        StringTrieMapIter::sorted(Some(self.0.root()), String::new())
+    }
+
+    /// Returns an iterator over every [`String`] and weight in the trie whose
+    /// string starts with `prefix`, in lexicographic order of the strings.
+    pub fn iter_prefix(&self, prefix: &str) -> StringTrieMapIter<'_, W> {
+        StringTrieMapIter::sorted(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
+
+    /// Returns an iterator over every [`String`] and weight in the trie, in
+    /// the order the child storage keeps them, which is arbitrary.
+    pub fn iter_unordered(&self) -> StringTrieMapIter<'_, W> {
+        StringTrieMapIter::new(self.0.root())
+    }
+
+    /// Returns an iterator over every [`String`] and weight in the trie whose
+    /// string starts with `prefix`, in the order the child storage keeps
+    /// them.
+    pub fn iter_prefix_unordered(&self, prefix: &str) -> StringTrieMapIter<'_, W> {
+        StringTrieMapIter::with_prefix(
+            self.0.root().walk(prefix.chars()),
+            prefix.to_string(),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W> Serialize for StringWeightedTrie<W>
+where
+    W: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, W> Deserialize<'de> for StringWeightedTrie<W>
+where
+    W: Deserialize<'de> + Ord + Copy,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let trie = WeightedTrie::<char, W, CharHasher>::deserialize(deserializer)?;
+        Ok(StringWeightedTrie(trie))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k_completions() {
+        let mut trie = StringWeightedTrie::new();
+        for (value, weight) in [("über", 3), ("überall", 7), ("übel", 5), ("uber", 9)] {
+            assert_eq!(trie.insert(value, weight), None);
+        }
+
+        assert_eq!(trie.top_k_completions("üb", 2), [
+            ("überall".to_string(), 7),
+            ("übel".to_string(), 5),
+        ]);
+
+        assert_eq!(trie.remove("überall"), Some(7));
+        assert_eq!(trie.top_k_completions("über", 2), [("über".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut trie = StringWeightedTrie::new();
+        for (value, weight) in [("日", 1), ("日本", 4), ("日本語", 2)] {
+            assert_eq!(trie.insert(value, weight), None);
+        }
+
+        assert_eq!(trie.entry("日本").or_insert(9), 4);
+        assert_eq!(trie.max_weight("日本"), Some(4));
+        assert_eq!(trie.count_with_prefix("日本"), 2);
+        assert_eq!(trie.longest_prefix_of("日本語で"), Some(("日本語", 2)));
+        assert_eq!(trie.prefixes_of("日本").collect::<Vec<_>>(), [("日", 1), ("日本", 4)]);
+        assert_eq!(trie.iter_prefix("日本").collect::<Vec<_>>(), [
+            ("日本".to_string(), &4),
+            ("日本語".to_string(), &2),
+        ]);
+        assert_eq!(trie.fuzzy_search("本", 1).count(), 2);
+        assert_eq!(trie.matches("日*").count(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use serde_json;
+
+        let mut trie = StringWeightedTrie::new();
+        trie.insert("ab", 3u32);
+        trie.insert("b", 5);
+
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: StringWeightedTrie<u32> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get("ab"), Some(3));
+        assert_eq!(deserialized.top_k_completions("", 1), [("b".to_string(), 5)]);
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    hash::RandomState,
+    marker::PhantomData,
+};
+
+use crate::{
+    children::ChildStorage,
+    iter::TrieIter,
+    node::{
+        NodePath,
+        TrieNode,
+    },
+};
+
+/// An immutable view of the values within a [`Trie`](crate::trie::Trie) that
+/// start with a given prefix.
+///
+/// Every value passed to or returned from a [`SubTrie`] is relative to the
+/// prefix. For example, in a subtrie with the prefix "te", `contains("st")`
+/// checks for the value "test".
+pub struct SubTrie<'a, T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// The node at the end of the prefix.
+    node: &'a TrieNode<T, H>,
+    /// The prefix this view is rooted at.
+    prefix: Vec<T>,
+}
+
+impl<'a, T, H> SubTrie<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`SubTrie`] over the node at the end of `prefix`.
+    pub(super) fn new(node: &'a TrieNode<T, H>, prefix: Vec<T>) -> Self {
+        Self {
+            node,
+            prefix,
+        }
+    }
+
+    /// Returns the prefix this view is rooted at.
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
+    }
+
+    /// Returns `true` if no values start with the prefix.
+    pub fn is_empty(&self) -> bool {
+        !self.node.has_values()
+    }
+
+    /// Returns the number of values that start with the prefix.
+    pub fn len(&self) -> usize {
+        self.node.count_values()
+    }
+
+    /// Checks if the view contains a value, relative to the prefix.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.node
+            .walk(iter)
+            .is_some_and(TrieNode::is_end_of_word)
+    }
+
+    /// Returns `true` if any value within the view starts with `prefix`,
+    /// relative to the prefix of the view.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.node
+            .walk(prefix)
+            .is_some_and(TrieNode::has_values)
+    }
+
+    /// Returns an immutable reference to the [`TrieNode`] at the end of the
+    /// prefix.
+    pub fn root(&self) -> &'a TrieNode<T, H> {
+        self.node
+    }
+}
+
+impl<'a, T, H> SubTrie<'a, T, H>
+where
+    T: Eq + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every value within the view, relative to the
+    /// prefix.
+    pub fn iter(&self) -> TrieIter<'a, T, H> {
+        self.node.iter()
+    }
+}
+
+/// A mutable view of the values within a [`Trie`](crate::trie::Trie) that
+/// start with a given prefix.
+///
+/// Values inserted into or removed from the view are relative to the prefix,
+/// and the length of the parent trie is kept up to date. If the view is left
+/// empty, the prefix is removed from the parent trie when the view is
+/// dropped.
+pub struct SubTrieMut<'a, T, H = RandomState>
+where
+    H: ChildStorage<T>,
+{
+    /// The prefix this view is rooted at.
+    prefix: Vec<T>,
+    /// Every node from the root of the parent trie to the end of the prefix.
+    path: NodePath<T, (), H>,
+    /// The number of values within the parent trie.
+    count: &'a mut usize,
+    marker: PhantomData<&'a mut TrieNode<T, H>>,
+}
+
+impl<'a, T, H> SubTrieMut<'a, T, H>
+where
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`SubTrieMut`] for `prefix`.
+    ///
+    /// If `prefix` does not exist within the trie, this function returns
+    /// [`None`].
+    pub(super) fn new(
+        root: &'a mut TrieNode<T, H>,
+        count: &'a mut usize,
+        prefix: Vec<T>,
+    ) -> Option<Self> {
+        let path = NodePath::walk(root, &prefix);
+        if path.len() != prefix.len() + 1 {
+            return None;
+        }
+        Some(Self {
+            prefix,
+            path,
+            count,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the node at the end of the prefix.
+    fn node(&self) -> &TrieNode<T, H> {
+        // SAFETY: The path ends at a live node and this view holds the only
+        // borrow of the parent trie.
+        unsafe { self.path.last().as_ref() }
+    }
+
+    /// Returns the node at the end of the prefix.
+    fn node_mut(&mut self) -> &mut TrieNode<T, H> {
+        // SAFETY: As above; `&mut self` guarantees exclusive access.
+        unsafe { self.path.last().as_mut() }
+    }
+
+    /// Returns the prefix this view is rooted at.
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
+    }
+
+    /// Returns `true` if no values start with the prefix.
+    pub fn is_empty(&self) -> bool {
+        !self.node().has_values()
+    }
+
+    /// Returns the number of values that start with the prefix.
+    pub fn len(&self) -> usize {
+        self.node().count_values()
+    }
+
+    /// Checks if the view contains a value, relative to the prefix.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.node()
+            .walk(iter)
+            .is_some_and(TrieNode::is_end_of_word)
+    }
+
+    /// Returns `true` if any value within the view starts with `prefix`,
+    /// relative to the prefix of the view.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.node()
+            .walk(prefix)
+            .is_some_and(TrieNode::has_values)
+    }
+
+    /// Inserts a value into the view, relative to the prefix.
+    ///
+    /// This function returns `true` if the value that was added is a newly
+    /// added value, otherwise returns `false`.
+    pub fn insert(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let is_new = self
+            .node_mut()
+            .insert_branch(iter.into_iter(), ())
+            .is_none();
+        if is_new {
+            *self.count += 1;
+
+            // The prefix node updated its own count, the nodes above it also
+            // gain a value.
+            // SAFETY: `&mut self` guarantees no other reference is live.
+            #[cfg(feature = "counts")]
+            unsafe {
+                self.path.update(self.path.len() - 1, |node| node.count += 1);
+            }
+        }
+        is_new
+    }
+
+    /// Removes a value from the view, relative to the prefix.
+    ///
+    /// This function returns `true` if the value was successfully removed,
+    /// otherwise if the value doesn't exist, this returns `false`.
+    pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
+        let removed = self
+            .node_mut()
+            .remove_branch(iter.into_iter())
+            .is_some();
+        if removed {
+            *self.count -= 1;
+
+            // SAFETY: `&mut self` guarantees no other reference is live.
+            #[cfg(feature = "counts")]
+            unsafe {
+                self.path.update(self.path.len() - 1, |node| node.count -= 1);
+            }
+        }
+        removed
+    }
+
+    /// Returns an immutable reference to the [`TrieNode`] at the end of the
+    /// prefix.
+    pub fn root(&self) -> &TrieNode<T, H> {
+        self.node()
+    }
+}
+
+impl<T, H> SubTrieMut<'_, T, H>
+where
+    T: Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every value within the view, relative to the
+    /// prefix.
+    pub fn iter(&self) -> TrieIter<'_, T, H> {
+        // SAFETY: The path ends at a live node and the returned iterator
+        // borrows `self`, so the view cannot be modified while it is alive.
+        unsafe { self.path.last().as_ref() }.iter()
+    }
+}
+
+impl<T, H> Drop for SubTrieMut<'_, T, H>
+where
+    H: ChildStorage<T>,
+{
+    fn drop(&mut self) {
+        // The prefix node is kept alive while the view exists so it can be
+        // reused. Once the view is gone, prune it if it no longer leads to a
+        // value.
+        // SAFETY: The view is being dropped and holds the only borrow of the
+        // parent trie.
+        unsafe { self.path.prune(&self.prefix) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie::Trie;
+
+    type CharTrie = Trie<char>;
+
+    fn create_trie() -> CharTrie {
+        let mut trie = CharTrie::new();
+        assert!(trie.insert("test".chars()));
+        assert!(trie.insert("tea".chars()));
+        assert!(trie.insert("teach".chars()));
+        assert!(trie.insert("other".chars()));
+        trie
+    }
+
+    #[test]
+    fn test_subtrie() {
+        let trie = create_trie();
+        assert!(trie.subtrie("x".chars()).is_none());
+
+        let subtrie = trie.subtrie("te".chars()).unwrap();
+        assert_eq!(subtrie.prefix(), &['t', 'e']);
+        assert_eq!(subtrie.len(), 3);
+        assert!(!subtrie.is_empty());
+
+        assert!(subtrie.contains("st".chars()));
+        assert!(subtrie.contains("a".chars()));
+        assert!(!subtrie.contains("".chars()));
+        assert!(!subtrie.contains("test".chars()));
+
+        assert!(subtrie.starts_with("ac".chars()));
+        assert!(!subtrie.starts_with("x".chars()));
+
+        let mut values: Vec<String> = trie
+            .subtrie("tes".chars())
+            .unwrap()
+            .iter()
+            .map(String::from_iter)
+            .collect();
+        values.sort();
+        assert_eq!(values, ["t"]);
+
+        let mut values: Vec<String> = subtrie
+            .iter()
+            .map(String::from_iter)
+            .collect();
+        values.sort();
+        assert_eq!(values, ["a", "ach", "st"]);
+
+        // The prefix itself is yielded as an empty value:
+        let values: Vec<String> = trie
+            .subtrie("tea".chars())
+            .unwrap()
+            .iter()
+            .map(String::from_iter)
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert!(values.contains(&String::new()));
+    }
+
+    #[test]
+    fn test_subtrie_mut() {
+        let mut trie = create_trie();
+        assert!(trie.subtrie_mut("x".chars()).is_none());
+
+        {
+            let mut subtrie = trie.subtrie_mut("te".chars()).unwrap();
+            assert!(subtrie.insert("n".chars()));
+            assert!(!subtrie.insert("st".chars()));
+            assert!(subtrie.remove("a".chars()));
+            assert!(!subtrie.remove("a".chars()));
+            assert!(subtrie.contains("n".chars()));
+            assert!(subtrie.contains("ach".chars()));
+            assert_eq!(subtrie.len(), 3);
+        }
+
+        assert_eq!(trie.len(), 4);
+        assert!(trie.contains("ten".chars()));
+        assert!(trie.contains("teach".chars()));
+        assert!(!trie.contains("tea".chars()));
+        assert_eq!(trie.count_with_prefix("te".chars()), 3);
+    }
+
+    #[test]
+    fn test_subtrie_mut_prunes_on_drop() {
+        let mut trie = create_trie();
+
+        {
+            let mut subtrie = trie.subtrie_mut("o".chars()).unwrap();
+            assert!(subtrie.remove("ther".chars()));
+            assert!(subtrie.is_empty());
+
+            // The view stays usable while it is empty:
+            assert!(subtrie.insert("x".chars()));
+            assert!(subtrie.remove("x".chars()));
+        }
+
+        // The empty prefix should have been removed from the trie:
+        assert_eq!(trie.len(), 3);
+        assert!(!trie.starts_with("o".chars()));
+        assert!(trie.root().get(&'o').is_none());
+    }
+}
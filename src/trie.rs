@@ -1,17 +1,48 @@
-use std::hash::{
-    BuildHasher,
-    Hash,
-    RandomState,
+use std::{
+    hash::{
+        Hash,
+        RandomState,
+    },
+    io::{
+        self,
+        Read,
+        Write,
+    },
 };
 
 #[cfg(feature = "serde")]
 use serde::{
     Deserialize,
     Serialize,
-    ser::SerializeStruct,
 };
 
-use crate::{iter::TrieIter, node::TrieNode};
+use crate::{
+    aho_corasick::{
+        AhoCorasick,
+        MatchKind,
+    },
+    binary,
+    children::ChildStorage,
+    dawg::Dawg,
+    double_array::{
+        DoubleArray,
+        Symbol,
+    },
+    entry::Entry,
+    iter::TrieIter,
+    louds::Louds,
+    mapped::MappedTrie,
+    map::{
+        InvalidTrie,
+        TrieMap,
+    },
+    node::TrieNode,
+    pattern::Token,
+    subtrie::{
+        SubTrie,
+        SubTrieMut,
+    },
+};
 
 /// Stores a list of words efficiently in memory.
 /// 
@@ -46,201 +77,390 @@ use crate::{iter::TrieIter, node::TrieNode};
 /// ## What is this Useful For?
 /// If you need to quickly check a string against a very large number of strings
 /// very quickly.
-#[derive(Default)]
-pub struct Trie<T, H = RandomState> {
-    /// Root node that tracks every value within the trie.
-    root: TrieNode<T, H>,
-    /// Tracks the number of values in the trie.
-    count: usize,
+pub struct Trie<T, H = RandomState>(TrieMap<T, (), H>)
+where
+    H: ChildStorage<T>;
+
+impl<T, H> Default for Trie<T, H>
+where
+    H: Default,
+    H: ChildStorage<T>,
+{
+    fn default() -> Self {
+        Self(TrieMap::default())
+    }
+}
+
+impl<T, H, I> FromIterator<I> for Trie<T, H>
+where
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn from_iter<K: IntoIterator<Item = I>>(keys: K) -> Self {
+        let mut trie = Self::new();
+        trie.extend(keys);
+        trie
+    }
+}
+
+impl<T, H, I> Extend<I> for Trie<T, H>
+where
+    H: ChildStorage<T>,
+    I: IntoIterator<Item = T>,
+{
+    fn extend<K: IntoIterator<Item = I>>(&mut self, keys: K) {
+        for key in keys {
+            self.insert(key);
+        }
+    }
 }
 
 impl<T, H> Trie<T, H>
 where
-    T: Hash + Eq,
-    H: BuildHasher + Default,
+    H: ChildStorage<T>,
 {
     /// Creates a new empty [`Trie`].
     pub fn new() -> Self {
-        Self {
-            root: TrieNode::empty(),
-            count: 0,
-        }
+        Self(TrieMap::new())
     }
 
     /// Returns `true` if the trie is empty, otherwise returns `false`.
     pub fn is_empty(&self) -> bool {
-        self.root.is_empty()
+        self.0.is_empty()
     }
 
     /// Returns the number of values within the trie.
     pub fn len(&self) -> usize {
-        self.count
+        self.0.len()
     }
 
     /// Clears the trie.
     pub fn clear(&mut self) {
-        self.root.clear();
+        self.0.clear();
     }
 
     /// Inserts a value into the trie.
     /// 
     /// This function returns `true` if the value that was added is a newly
-    /// added value, otherwise returns `false`. This function will also return
-    /// `false` if the value provided was empty.
+    /// added value, otherwise returns `false`.
     pub fn insert(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
-        // Walk the iterator until we reach the end:
-        let mut current = &mut self.root;
-        for char in iter.into_iter() {
-            current = current.get_or_create(char);
-        }
-
-        // Determine if it is a new value and update the internal value counter:
-        let is_new = !current.end_of_value;
-        if is_new {
-            self.count += 1;
-        }
-
-        // Finalise:
-        current.end_of_value = true;
-        is_new
+        self.0.insert(iter, ()).is_none()
     }
 
     /// Removes a value from the trie.
     /// 
     /// This function returns `true` if the value was successfully removed,
-    /// otherwise if the value doesn't exist, this returns `false`. This
-    /// function will also return `false` if the value provided was empty.
+    /// otherwise if the value doesn't exist, this returns `false`.
     pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> bool {
-        let removed = self.root.remove_branch(iter.into_iter());
-        if removed {
-            self.count -= 1;
-        }
-        removed
+        self.0.remove(iter).is_some()
+    }
+
+    /// Returns the [`Entry`] for a value, allowing it to be inserted or
+    /// removed after a single walk of the trie.
+    pub fn entry(&mut self, iter: impl IntoIterator<Item = T>) -> Entry<'_, T, (), H> {
+        self.0.entry(iter)
     }
 
     /// Checks if the [`Trie`] contains a value.
     /// 
     /// If the value exists, this function returns `true`, otherwise it returns
-    /// `false`. This function will also return false if the value provided is
-    /// empty.
+    /// `false`.
     pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
-        let mut current = &self.root;
-        for char in iter.into_iter() {
-            match current.get(&char) {
-                Some(next_node) => {
-                    current = next_node
-                },
-                None => {
-                    return false;
-                },
-            }
-        }
-        current.end_of_value
+        self.0.contains_key(iter)
+    }
+
+    /// Returns `true` if any value within the [`Trie`] starts with `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.0.starts_with(prefix)
+    }
+
+    /// Returns the number of values within the [`Trie`] that start with
+    /// `prefix`.
+    pub fn count_with_prefix(&self, prefix: impl IntoIterator<Item = T>) -> usize {
+        self.0.count_with_prefix(prefix)
+    }
+
+    /// Returns a view of every value within the [`Trie`] that starts with
+    /// `prefix`.
+    /// 
+    /// If no node exists for `prefix`, this function returns [`None`].
+    pub fn subtrie(&self, prefix: impl IntoIterator<Item = T>) -> Option<SubTrie<'_, T, H>> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        let node = self.0.root.walk(&prefix)?;
+        Some(SubTrie::new(node, prefix))
+    }
+
+    /// Returns a mutable view of every value within the [`Trie`] that starts
+    /// with `prefix`.
+    /// 
+    /// If no node exists for `prefix`, this function returns [`None`].
+    pub fn subtrie_mut(&mut self, prefix: impl IntoIterator<Item = T>) -> Option<SubTrieMut<'_, T, H>> {
+        SubTrieMut::new(
+            &mut self.0.root,
+            &mut self.0.count,
+            prefix.into_iter().collect(),
+        )
+    }
+
+    /// Returns an iterator over the length of every value within the [`Trie`]
+    /// that is a prefix of `input`, from shortest to longest.
+    pub fn prefixes_of<'a, I>(&'a self, input: I) -> impl Iterator<Item = usize> + 'a
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+    {
+        self.0
+            .prefixes_of(input)
+            .map(|(length, _)| length)
+    }
+
+    /// Returns the length of the longest value within the [`Trie`] that is a
+    /// prefix of `input`.
+    /// 
+    /// If no value is a prefix of `input`, this function returns [`None`].
+    pub fn longest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<usize> {
+        self.prefixes_of(input).last()
+    }
+
+    /// Returns the length of the shortest value within the [`Trie`] that is a
+    /// prefix of `input`.
+    /// 
+    /// If no value is a prefix of `input`, this function returns [`None`].
+    pub fn shortest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<usize> {
+        self.prefixes_of(input).next()
+    }
+
+    /// Compiles the [`Trie`] into an [`AhoCorasick`] automaton, which finds
+    /// every value within a sequence in a single pass.
+    /// 
+    /// The automaton is a snapshot, so values added to the [`Trie`] later are
+    /// not found by it.
+    pub fn aho_corasick(&self, kind: MatchKind) -> AhoCorasick<T>
+    where
+        T: Hash + Eq + Copy,
+    {
+        AhoCorasick::new(self.root(), kind)
+    }
+
+    /// Builds a read-only [`DoubleArray`] from the [`Trie`], which looks up
+    /// values with a few array reads per element.
+    /// 
+    /// The [`DoubleArray`] is a snapshot, so values added to the [`Trie`]
+    /// later are not found by it.
+    pub fn double_array(&self) -> DoubleArray<T>
+    where
+        T: Symbol,
+    {
+        DoubleArray::new(self.root())
+    }
+
+    /// Writes the [`Trie`] to `writer` in a versioned binary format, which
+    /// can be read back with [`Trie::read_from`].
+    ///
+    /// Nodes are written as a flat table rather than nested, followed by a
+    /// CRC-32 checksum. Elements are written as their [`Symbol`] encoding.
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()>
+    where
+        T: Symbol,
+    {
+        binary::write(self.root(), self.len(), writer)
+    }
+
+    /// Reads a [`Trie`] written by [`Trie::write_to`].
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if the
+    /// input isn't a serialized trie, has an unsupported version, fails its
+    /// checksum or doesn't describe a well-formed trie.
+    pub fn read_from(reader: impl Read) -> io::Result<Self>
+    where
+        T: Symbol,
+    {
+        let (root, count) = binary::read(reader)?;
+        Ok(Self(TrieMap { root, count }))
+    }
+
+    /// Writes the [`Trie`] to `writer` in a compact binary form that a
+    /// [`MappedTrie`] can query in place.
+    ///
+    /// See [`MappedTrie::write_to`].
+    pub fn write_mapped(&self, writer: impl Write) -> io::Result<()>
+    where
+        T: Symbol,
+    {
+        MappedTrie::write_to(self.root(), writer)
+    }
+
+    /// Returns the number of nodes within the [`Trie`], including the root.
+    pub fn node_count(&self) -> usize {
+        self.0.root.count_nodes()
+    }
+
+    /// Checks the invariants of the [`Trie`], visiting every node.
+    ///
+    /// See [`TrieMap::validate`].
+    pub fn validate(&self) -> Result<(), InvalidTrie> {
+        self.0.validate()
     }
 
     /// Returns an immutable reference to the root [`TrieNode`].
     /// 
     /// This is the node that contains every value.
     pub fn root(&self) -> &TrieNode<T, H> {
-        &self.root
+        self.0.root()
+    }
+}
+
+#[cfg(feature = "counts")]
+impl<T, H> Trie<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns the `n`th value in the [`Trie`], in lexicographic order.
+    /// 
+    /// If `n` is out of bounds, this function returns [`None`].
+    pub fn nth(&self, n: usize) -> Option<Vec<T>> {
+        self.0.nth(n).map(|(key, _)| key)
+    }
+
+    /// Returns the number of values in the [`Trie`] that sort before `value`
+    /// in lexicographic order.
+    pub fn rank(&self, value: impl IntoIterator<Item = T>) -> usize {
+        self.0.rank(value)
     }
 }
 
 impl<T, H> Trie<T, H>
 where
     T: Eq + Copy,
+    H: ChildStorage<T>,
 {
-    /// Returns an iterator over the [`Trie`].
+    /// Returns an iterator over the [`Trie`] that yields values in the order
+    /// the child storage keeps them.
+    /// 
+    /// This skips sorting the children of each node, so it is faster than
+    /// [`Trie::iter`], but the order is arbitrary for hash maps.
+    pub fn iter_unordered(&self) -> TrieIter<'_, T, H> {
+        self.0.root.iter()
+    }
+
+    /// Returns an iterator over every value in the [`Trie`] that starts with
+    /// `prefix`, in the order the child storage keeps them.
+    /// 
+    /// See [`Trie::iter_unordered`].
+    pub fn iter_prefix_unordered(&self, prefix: impl IntoIterator<Item = T>) -> TrieIter<'_, T, H> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        TrieIter::with_prefix(self.0.root.walk(&prefix), prefix)
+    }
+
+    /// Returns every value in the [`Trie`] within `max_distance` edits of
+    /// `query`, along with its distance.
+    /// 
+    /// An edit is inserting, removing or replacing a single element. Subtrees
+    /// that cannot contain a match are skipped, so this is much faster than
+    /// comparing `query` against every value.
+    pub fn fuzzy_search(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> impl Iterator<Item = (Vec<T>, usize)> + '_ {
+        self.0
+            .fuzzy_search(query, max_distance)
+            .map(|(key, _, distance)| (key, distance))
+    }
+
+    /// Returns every value in the [`Trie`] within `max_distance` edits of
+    /// `query`, along with its distance.
+    /// 
+    /// Unlike [`Trie::fuzzy_search`], swapping two adjacent elements counts as
+    /// a single edit. This suits typos, where neighbouring keys are often
+    /// pressed in the wrong order.
+    pub fn fuzzy_search_transpositions(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> impl Iterator<Item = (Vec<T>, usize)> + '_ {
+        self.0
+            .fuzzy_search_transpositions(query, max_distance)
+            .map(|(key, _, distance)| (key, distance))
+    }
+
+    /// Returns every value in the [`Trie`] that matches `pattern`.
+    /// 
+    /// Each [`Token::One`] matches a single element using its predicate, and
+    /// each [`Token::Many`] matches any run of elements. Only the branches
+    /// that can still match the pattern are walked.
+    pub fn matches<'a, P>(&'a self, pattern: impl IntoIterator<Item = Token<P>>) -> impl Iterator<Item = Vec<T>> + 'a
+    where
+        P: Fn(&T) -> bool + 'a,
+    {
+        self.0
+            .matches(pattern)
+            .map(|(key, _)| key)
+    }
+}
+
+impl<T, H> Trie<T, H>
+where
+    T: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over the [`Trie`] that yields values in
+    /// lexicographic order.
+    /// 
+    /// The children of each node are sorted as they are visited. Use
+    /// [`Trie::iter_unordered`] if the order doesn't matter.
     pub fn iter(&self) -> TrieIter<'_, T, H> {
-        self.root.iter()
+        self.0.keys()
+    }
+
+    /// Returns an iterator over every value in the [`Trie`] that starts with
+    /// `prefix`, in lexicographic order.
+    /// 
+    /// The iterator yields full values, including the prefix.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> TrieIter<'_, T, H> {
+        self.0.keys_prefix(prefix)
+    }
+
+    /// Freezes the [`Trie`] into an immutable, minimised [`Dawg`].
+    /// 
+    /// Equivalent suffixes are shared between keys, so the [`Dawg`] usually
+    /// needs far fewer nodes. Values added to the [`Trie`] later are not
+    /// found in the [`Dawg`].
+    pub fn freeze(&self) -> Dawg<T>
+    where
+        T: Hash,
+    {
+        Dawg::new(self.root())
+    }
+
+    /// Builds a read-only, succinct [`Louds`] trie from the [`Trie`].
+    /// 
+    /// The [`Louds`] trie needs only a few bits per node on top of its
+    /// elements, which suits very large sets of values.
+    pub fn louds(&self) -> Louds<T> {
+        Louds::new(self.root())
     }
 }
 
 #[cfg(feature = "serde")]
 impl<T, H> Serialize for Trie<T, H>
 where
-    T: Hash + Eq + Serialize,
-    H: BuildHasher + Default,
+    T: Serialize,
+    H: ChildStorage<T>,
 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        // Serialize as a struct with two fields: `root` and `count`
-        let mut state = serializer.serialize_struct("Trie", 2)?;
-        state.serialize_field("root", &self.root)?;
-        state.serialize_field("count", &self.count)?;
-        state.end()
+        self.0.serialize_named("Trie", serializer)
     }
 }
 
 #[cfg(feature = "serde")]
 impl<'de, T, H> Deserialize<'de> for Trie<T, H>
 where
-    T: Hash + Eq + Deserialize<'de>,
-    H: BuildHasher + Default,
+    T: Deserialize<'de>,
+    H: ChildStorage<T>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        // Visitor for deserializing a `Trie`.
-        struct TrieVisitor<T, H> {
-            marker: std::marker::PhantomData<(T, H)>,
-        }
-
-        impl<'de, T, H> serde::de::Visitor<'de> for TrieVisitor<T, H>
-        where
-            T: Hash + Eq + Deserialize<'de>,
-            H: BuildHasher + Default,
-        {
-            type Value = Trie<T, H>;
-
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a Trie with root and count fields")
-            }
-
-            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
-            where
-                M: serde::de::MapAccess<'de>,
-            {
-                let mut root = None;
-                let mut count = None;
-
-                while let Some(key) = map.next_key::<&str>()? {
-                    match key {
-                        "root" => {
-                            if root.is_some() {
-                                return Err(serde::de::Error::duplicate_field("root"));
-                            }
-                            root = Some(map.next_value()?);
-                        }
-                        "count" => {
-                            if count.is_some() {
-                                return Err(serde::de::Error::duplicate_field("count"));
-                            }
-                            count = Some(map.next_value()?);
-                        }
-                        _ => {
-                            return Err(serde::de::Error::unknown_field(key, &["root", "count"]));
-                        }
-                    }
-                }
-
-                let root = root.ok_or_else(|| serde::de::Error::missing_field("root"))?;
-                let count = count.ok_or_else(|| serde::de::Error::missing_field("count"))?;
-
-                Ok(Trie { root, count })
-            }
-        }
-
-        deserializer.deserialize_struct(
-            "Trie",
-            &["root", "count"],
-            TrieVisitor {
-                marker: std::marker::PhantomData,
-            },
-        )
+        let map = TrieMap::<T, (), H>::deserialize_named("Trie", deserializer)?;
+        Ok(Trie(map))
     }
 }
 
@@ -314,6 +534,17 @@ mod tests {
         assert_eq!(trie.len(), 0);
     }
 
+    #[test]
+    fn test_from_iter_and_extend() {
+        let mut trie: CharTrie = ["a", "ab", "a"].map(str::chars).into_iter().collect();
+        assert_eq!(trie.len(), 2);
+        assert!(trie.contains("ab".chars()));
+
+        trie.extend(["b", "ab"].map(str::chars));
+        assert_eq!(trie.len(), 3);
+        assert!(trie.contains("b".chars()));
+    }
+
     #[test]
     fn test_deletion() {
         let mut trie = CharTrie::new();
@@ -404,6 +635,185 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_iter_nested() {
+        let values = ["", "t", "te", "test", "testing", "tester", "tea"];
+
+        let mut trie = CharTrie::new();
+        for value in values {
+            assert!(trie.insert(value.chars()));
+        }
+
+        let mut found: Vec<String> = trie.iter_unordered().map(String::from_iter).collect();
+        found.sort();
+        let mut expected = values.to_vec();
+        expected.sort();
+        assert_eq!(found, expected);
+
+        // Iteration yields each value before the values that extend it:
+        let sorted: Vec<String> = trie.iter().map(String::from_iter).collect();
+        assert_eq!(sorted, ["", "t", "te", "tea", "test", "tester", "testing"]);
+
+        // The prefix itself is yielded if it is a value:
+        let sorted: Vec<String> = trie
+            .iter_prefix("test".chars())
+            .map(String::from_iter)
+            .collect();
+        assert_eq!(sorted, ["test", "tester", "testing"]);
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let mut trie = CharTrie::new();
+        assert!(!trie.starts_with("".chars()));
+        assert!(trie.insert("test".chars()));
+        assert!(trie.insert("tech".chars()));
+
+        assert!(trie.starts_with("".chars()));
+        assert!(trie.starts_with("t".chars()));
+        assert!(trie.starts_with("te".chars()));
+        assert!(trie.starts_with("tes".chars()));
+        assert!(trie.starts_with("test".chars()));
+        assert!(!trie.starts_with("tests".chars()));
+        assert!(!trie.starts_with("other".chars()));
+
+        // Removing a value should also remove its prefixes:
+        assert!(trie.remove("test".chars()));
+        assert!(!trie.starts_with("tes".chars()));
+        assert!(trie.starts_with("te".chars()));
+    }
+
+    #[test]
+    fn test_count_with_prefix() {
+        let mut trie = CharTrie::new();
+        assert!(trie.insert("test".chars()));
+        assert!(trie.insert("testing".chars()));
+        assert!(trie.insert("tester".chars()));
+        assert!(trie.insert("tech".chars()));
+        assert!(trie.insert("other".chars()));
+
+        assert_eq!(trie.count_with_prefix("".chars()), 5);
+        assert_eq!(trie.count_with_prefix("t".chars()), 4);
+        assert_eq!(trie.count_with_prefix("tes".chars()), 3);
+        assert_eq!(trie.count_with_prefix("test".chars()), 3);
+        assert_eq!(trie.count_with_prefix("teste".chars()), 1);
+        assert_eq!(trie.count_with_prefix("tech".chars()), 1);
+        assert_eq!(trie.count_with_prefix("missing".chars()), 0);
+    }
+
+    #[test]
+    fn test_iter_prefix() {
+        let mut trie = CharTrie::new();
+        assert!(trie.insert("testing".chars()));
+        assert!(trie.insert("tester".chars()));
+        assert!(trie.insert("tesla".chars()));
+        assert!(trie.insert("tech".chars()));
+        assert!(trie.insert("other".chars()));
+
+        let mut values: Vec<String> = trie
+            .iter_prefix("tes".chars())
+            .map(String::from_iter)
+            .collect();
+        values.sort();
+        assert_eq!(values, ["tesla", "tester", "testing"]);
+
+        assert_eq!(trie.iter_prefix("o".chars()).count(), 1);
+        assert_eq!(trie.iter_prefix("missing".chars()).count(), 0);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let values = ["zebra", "apple", "mango", "banana", "cherry", "apricot"];
+
+        let mut trie = CharTrie::new();
+        for value in values {
+            assert!(trie.insert(value.chars()));
+        }
+
+        let mut expected = values.to_vec();
+        expected.sort();
+        let sorted: Vec<String> = trie.iter().map(String::from_iter).collect();
+        assert_eq!(sorted, expected);
+
+        let sorted: Vec<String> = trie
+            .iter_prefix("a".chars())
+            .map(String::from_iter)
+            .collect();
+        assert_eq!(sorted, ["apple", "apricot"]);
+    }
+
+    #[test]
+    fn test_iter_sorted_many() {
+        // Enough keys, with enough children per node, that a hash map is very
+        // unlikely to yield them in order by chance:
+        let mut state = 12345u32;
+        let mut values = std::collections::BTreeSet::new();
+        while values.len() < 500 {
+            let mut value = String::new();
+            for _ in 0..1 + state % 6 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                value.push(char::from(b'a' + (state >> 16) as u8 % 26));
+            }
+            values.insert(value);
+        }
+
+        let mut trie = CharTrie::new();
+        for value in &values {
+            assert!(trie.insert(value.chars()));
+        }
+        assert!(trie.root().len() > 16);
+
+        let sorted: Vec<String> = trie.iter().map(String::from_iter).collect();
+        assert_eq!(sorted, values.iter().cloned().collect::<Vec<_>>());
+        let sorted: Vec<String> = trie.iter_prefix("q".chars()).map(String::from_iter).collect();
+        let expected: Vec<String> = values.iter().filter(|value| value.starts_with('q')).cloned().collect();
+        assert!(expected.len() > 16);
+        assert_eq!(sorted, expected);
+
+        // The unordered iterators still yield every value:
+        assert_eq!(trie.iter_unordered().count(), values.len());
+        assert_eq!(trie.iter_prefix_unordered("q".chars()).count(), expected.len());
+    }
+
+    #[test]
+    fn test_prefixes_of() {
+        let mut trie = CharTrie::new();
+        assert!(trie.insert("te".chars()));
+        assert!(trie.insert("test".chars()));
+        assert!(trie.insert("testing".chars()));
+        assert!(trie.insert("other".chars()));
+
+        assert_eq!(trie.prefixes_of("testing123".chars()).collect::<Vec<_>>(), [2, 4, 7]);
+        assert_eq!(trie.longest_prefix_of("testing123".chars()), Some(7));
+        assert_eq!(trie.shortest_prefix_of("testing123".chars()), Some(2));
+        assert_eq!(trie.longest_prefix_of("tester".chars()), Some(4));
+        assert_eq!(trie.longest_prefix_of("tes".chars()), Some(2));
+        assert_eq!(trie.longest_prefix_of("t".chars()), None);
+        assert_eq!(trie.longest_prefix_of("missing".chars()), None);
+
+        // The empty value is a prefix of every input:
+        assert!(trie.insert("".chars()));
+        assert_eq!(trie.shortest_prefix_of("missing".chars()), Some(0));
+        assert_eq!(trie.prefixes_of("test".chars()).collect::<Vec<_>>(), [0, 2, 4]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_unchanged_form() {
+        use serde_json;
+
+        // Nodes of a trie without values only record `end_of_value`, as they
+        // always have:
+        let json = r#"{"root":{"children":{"a":{"children":{},"end_of_value":true}},"end_of_value":false},"count":1}"#;
+        let trie: CharTrie = serde_json::from_str(json).unwrap();
+        assert!(trie.contains("a".chars()));
+        assert_eq!(serde_json::to_string(&trie).unwrap(), json);
+
+        // The node type keeps its `TrieNode<T, H>` parameters:
+        let root: &TrieNode<char, RandomState> = trie.root();
+        assert_eq!(serde_json::to_string(root.get(&'a').unwrap()).unwrap(), r#"{"children":{},"end_of_value":true}"#);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serialize_deserialize() {
@@ -437,4 +847,73 @@ mod tests {
         assert!(!deserialized.contains("missing".chars()));
         assert!(!deserialized.contains("data".chars()));
     }
-}
\ No newline at end of file
+
+    /// Runs the same inserts, removals and lookups against a trie using the
+    /// child storage `H`.
+    fn check_storage<H>()
+    where
+        H: ChildStorage<u8>,
+    {
+        let keys: [&[u8]; 6] = [b"", b"tea", b"ten", b"team", b"to", b"inn"];
+        let mut trie = Trie::<u8, H>::new();
+        for key in keys {
+            assert!(trie.insert(key.iter().copied()));
+        }
+        assert!(!trie.insert(b"ten".iter().copied()));
+        assert_eq!(trie.len(), keys.len());
+        assert_eq!(trie.count_with_prefix(b"te".iter().copied()), 3);
+
+        let mut found: Vec<Vec<u8>> = trie.iter().collect();
+        found.sort();
+        assert_eq!(found, [&b""[..], b"inn", b"tea", b"team", b"ten", b"to"]);
+        let found: Vec<Vec<u8>> = trie.iter().collect();
+        assert_eq!(found, [&b""[..], b"inn", b"tea", b"team", b"ten", b"to"]);
+
+        assert!(trie.remove(b"team".iter().copied()));
+        assert!(trie.remove(b"inn".iter().copied()));
+        assert!(!trie.starts_with(b"i".iter().copied()));
+        assert!(trie.contains(b"tea".iter().copied()));
+        assert_eq!(trie.node_count(), 6);
+    }
+
+    #[test]
+    fn test_child_storage() {
+        use crate::children::{Adaptive, BTree, ByteArray, ByteBitmap, SortedVec};
+
+        check_storage::<RandomState>();
+        check_storage::<BTree>();
+        check_storage::<SortedVec>();
+        check_storage::<ByteArray>();
+        check_storage::<ByteBitmap>();
+        check_storage::<Adaptive>();
+
+        // Sorted storage visits children in order without sorting them:
+        let mut trie = Trie::<char, BTree>::new();
+        for key in ["b", "ab", "c", "a"] {
+            assert!(trie.insert(key.chars()));
+        }
+        let found: Vec<String> = trie.iter().map(String::from_iter).collect();
+        assert_eq!(found, ["a", "ab", "b", "c"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_child_storage() {
+        use crate::children::{ByteArray, SortedVec};
+
+        let mut trie = Trie::<u8, ByteArray>::new();
+        for key in [&b"abc"[..], b"abd", b"b"] {
+            assert!(trie.insert(key.iter().copied()));
+        }
+
+        // Every backend writes the same format, so a trie can be read back
+        // with different storage:
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: Trie<u8, SortedVec> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.len(), 3);
+        let found: Vec<Vec<u8>> = deserialized.iter().collect();
+        assert_eq!(found, [&b"abc"[..], b"abd", b"b"]);
+        let deserialized: Trie<u8> = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.contains(b"abd".iter().copied()));
+    }
+}
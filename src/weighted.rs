@@ -0,0 +1,749 @@
+use std::{
+    cmp::{
+        Ordering,
+        Reverse,
+    },
+    collections::BinaryHeap,
+    hash::RandomState,
+    marker::PhantomData,
+};
+
+#[cfg(feature = "serde")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    children::{
+        ChildStorage,
+        Children,
+    },
+    fuzzy::FuzzySearch,
+    iter::{
+        PrefixesOf,
+        TrieMapIter,
+    },
+    map::TrieMap,
+    node::{
+        NodePath,
+        TrieNode,
+    },
+    pattern::{
+        PatternMatches,
+        Token,
+    },
+};
+
+/// Recomputes the cached maximum weight of `node` from its own weight and the
+/// cached maxima of its children.
+fn refresh<T, W, H>(node: &mut TrieNode<T, H, W>)
+where
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    node.max = node
+        .children
+        .values()
+        .filter_map(|child| child.max)
+        .chain(node.value)
+        .max();
+}
+
+/// Recomputes the cached maximum weight of every node at or below `node`.
+#[cfg(feature = "serde")]
+fn refresh_all<T, W, H>(node: &mut TrieNode<T, H, W>)
+where
+    T: Copy,
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    let elements: Vec<T> = node.children.iter().map(|(element, _)| *element).collect();
+    for element in elements {
+        if let Some(child) = node.children.get_mut(&element) {
+            refresh_all(child);
+        }
+    }
+    refresh(node);
+}
+
+/// A view into a single key within a [`WeightedTrie`], which may or may not
+/// have a weight.
+///
+/// This is created by [`WeightedTrie::entry`]. Like an
+/// [`Entry`](crate::entry::Entry), the path to the key is only walked once.
+/// The weights cached by the nodes along the path are updated whenever the
+/// weight of the key changes.
+pub struct WeightedEntry<'a, T, W, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The key this entry points to.
+    key: Vec<T>,
+    /// Every node on the path from the root to the deepest existing node for
+    /// the key. The nodes for the rest of the key are created on insertion.
+    path: NodePath<T, W, H>,
+    /// The number of keys within the trie.
+    count: &'a mut usize,
+    marker: PhantomData<&'a mut TrieNode<T, H, W>>,
+}
+
+impl<'a, T, W, H> WeightedEntry<'a, T, W, H>
+where
+    T: Copy,
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new [`WeightedEntry`] by walking from the root of `map`
+    /// along `iter`.
+    fn new(map: &'a mut TrieMap<T, W, H>, iter: impl IntoIterator<Item = T>) -> Self {
+        let key: Vec<T> = iter.into_iter().collect();
+
+        // The path stems from the mutable borrow of the root held by this
+        // entry:
+        let path = NodePath::walk(&mut map.root, &key);
+        Self {
+            key,
+            path,
+            count: &mut map.count,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the key this entry points to.
+    pub fn key(&self) -> &[T] {
+        &self.key
+    }
+
+    /// Returns the node at the end of the key, if every node for the key
+    /// exists.
+    fn node_mut(&mut self) -> Option<&mut TrieNode<T, H, W>> {
+        if self.path.len() != self.key.len() + 1 {
+            return None;
+        }
+        // SAFETY: The last node on the path is live, and the returned
+        // reference borrows the entry so no other reference to it can exist.
+        Some(unsafe { self.path.last().as_mut() })
+    }
+
+    /// Returns the weight stored against the key, if any.
+    pub fn get(&self) -> Option<W> {
+        if self.path.len() != self.key.len() + 1 {
+            return None;
+        }
+        // SAFETY: The last node on the path is live and not otherwise
+        // borrowed.
+        unsafe { self.path.last().as_ref() }.value
+    }
+
+    /// Stores `weight` against the key.
+    ///
+    /// Returns the previous weight stored against the key, if any.
+    pub fn insert(&mut self, weight: W) -> Option<W> {
+        // SAFETY: The entry holds the only borrow of the trie, so no other
+        // reference to any node on the path is live.
+        unsafe {
+            // Create the remaining nodes below the deepest existing node:
+            let depth = self.path.len() - 1;
+            self.path.extend(self.key[depth..].iter().copied());
+
+            let previous = self.path.last().as_mut().value.replace(weight);
+            if previous.is_none() {
+                *self.count += 1;
+
+                // Every node along the path gains a value:
+                #[cfg(feature = "counts")]
+                self.path.update(self.path.len(), |node| node.count += 1);
+            }
+
+            // A greater weight can simply be raised, but a lowered weight may
+            // have been the maximum:
+            self.path.update_up(|node| match node.max {
+                Some(max) if weight < max && previous == Some(max) => refresh(node),
+                Some(max) if weight <= max => {},
+                _ => node.max = Some(weight),
+            });
+            previous
+        }
+    }
+
+    /// Removes the key from the trie, returning its weight.
+    ///
+    /// Any nodes that no longer lead to a key are removed from the trie.
+    pub fn remove(mut self) -> Option<W> {
+        let removed = self.node_mut()?.value.take()?;
+        *self.count -= 1;
+
+        // SAFETY: The entry is consumed and holds the only borrow of the
+        // trie, so no other reference to any node on the path is live.
+        unsafe {
+            // Every node along the path loses a value:
+            #[cfg(feature = "counts")]
+            self.path.update(self.path.len(), |node| node.count -= 1);
+
+            // Only the removal of the maximum weight changes the maximum:
+            self.path.update_up(|node| {
+                if node.max == Some(removed) {
+                    refresh(node);
+                }
+            });
+
+            // Prune the nodes that no longer lead to a key:
+            self.path.prune(&self.key);
+        }
+        Some(removed)
+    }
+
+    /// Ensures a weight is stored against the key by inserting `default` if
+    /// there is none, and returns the weight.
+    pub fn or_insert(mut self, default: W) -> W {
+        match self.get() {
+            Some(weight) => weight,
+            None => {
+                self.insert(default);
+                default
+            },
+        }
+    }
+
+    /// Replaces the weight stored against the key with the result of `f`, if
+    /// there is one.
+    pub fn and_modify(mut self, f: impl FnOnce(W) -> W) -> Self {
+        if let Some(weight) = self.get() {
+            self.insert(f(weight));
+        }
+        self
+    }
+}
+
+/// A candidate visited by the best-first search in
+/// [`WeightedTrie::top_k_completions`].
+struct Candidate<'a, T, W, H>
+where
+    H: ChildStorage<T>,
+{
+    /// The weight of the key if `complete` is set, otherwise the greatest
+    /// weight below `node`.
+    weight: W,
+    /// If set, the candidate is the key itself rather than the subtree below
+    /// it.
+    complete: bool,
+    key: Vec<T>,
+    node: &'a TrieNode<T, H, W>,
+}
+
+impl<T, W, H> Candidate<'_, T, W, H>
+where
+    T: Ord,
+    W: Ord,
+    H: ChildStorage<T>,
+{
+    /// Returns the order in which candidates are visited. Heavier candidates
+    /// come first, and keys come before subtrees of the same weight so they
+    /// are returned as soon as possible. Ties are broken by the key.
+    fn priority(&self) -> (&W, bool, Reverse<&[T]>) {
+        (&self.weight, self.complete, Reverse(&self.key))
+    }
+}
+
+impl<T: Ord, W: Ord, H: ChildStorage<T>> PartialEq for Candidate<'_, T, W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl<T: Ord, W: Ord, H: ChildStorage<T>> Eq for Candidate<'_, T, W, H> {}
+
+impl<T: Ord, W: Ord, H: ChildStorage<T>> PartialOrd for Candidate<'_, T, W, H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, W: Ord, H: ChildStorage<T>> Ord for Candidate<'_, T, W, H> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
+/// A trie that stores a weight, such as a popularity score, against each key.
+///
+/// This is a [`TrieMap`] whose values are weights. Every node also caches the
+/// greatest weight stored at or below it, so the heaviest keys that start with
+/// a prefix can be found without visiting the whole subtree below the prefix.
+/// The cached weights are kept up to date on the same walk that inserts or
+/// removes a key.
+pub struct WeightedTrie<T, W, H = RandomState>(TrieMap<T, W, H>)
+where
+    H: ChildStorage<T>;
+
+impl<T, W, H> Default for WeightedTrie<T, W, H>
+where
+    H: Default,
+    H: ChildStorage<T>,
+{
+    fn default() -> Self {
+        Self(TrieMap::default())
+    }
+}
+
+impl<T, W, H> WeightedTrie<T, W, H>
+where
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Creates a new empty [`WeightedTrie`].
+    pub fn new() -> Self {
+        Self(TrieMap::new())
+    }
+
+    /// Returns `true` if the trie is empty, otherwise returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of keys within the trie.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Clears the trie.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the weight stored against a key.
+    ///
+    /// If the key does not exist, this function returns [`None`].
+    pub fn get(&self, iter: impl IntoIterator<Item = T>) -> Option<W> {
+        self.0.get(iter).copied()
+    }
+
+    /// Returns `true` if the [`WeightedTrie`] contains a key, otherwise
+    /// returns `false`.
+    pub fn contains(&self, iter: impl IntoIterator<Item = T>) -> bool {
+        self.0.contains_key(iter)
+    }
+
+    /// Returns `true` if any key within the [`WeightedTrie`] starts with
+    /// `prefix`.
+    pub fn starts_with(&self, prefix: impl IntoIterator<Item = T>) -> bool {
+        self.0.starts_with(prefix)
+    }
+
+    /// Returns the number of keys within the [`WeightedTrie`] that start with
+    /// `prefix`.
+    ///
+    /// See [`TrieMap::count_with_prefix`].
+    pub fn count_with_prefix(&self, prefix: impl IntoIterator<Item = T>) -> usize {
+        self.0.count_with_prefix(prefix)
+    }
+
+    /// Returns the greatest weight of any key that starts with `prefix`.
+    ///
+    /// This only walks the prefix, since every node caches the greatest weight
+    /// below it.
+    pub fn max_weight(&self, prefix: impl IntoIterator<Item = T>) -> Option<W> {
+        self.0.root.walk(prefix)?.max
+    }
+
+    /// Returns an iterator over every key within the [`WeightedTrie`] that is
+    /// a prefix of `input`, from shortest to longest.
+    ///
+    /// Each item is the length of the key along with its weight.
+    pub fn prefixes_of<I>(&self, input: I) -> PrefixesOf<'_, T, W, H, I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.0.prefixes_of(input)
+    }
+
+    /// Returns the longest key within the [`WeightedTrie`] that is a prefix of
+    /// `input`, as its length along with its weight.
+    ///
+    /// If no key is a prefix of `input`, this function returns [`None`].
+    pub fn longest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<(usize, W)> {
+        self.0
+            .longest_prefix_of(input)
+            .map(|(length, weight)| (length, *weight))
+    }
+
+    /// Returns the shortest key within the [`WeightedTrie`] that is a prefix
+    /// of `input`, as its length along with its weight.
+    ///
+    /// If no key is a prefix of `input`, this function returns [`None`].
+    pub fn shortest_prefix_of(&self, input: impl IntoIterator<Item = T>) -> Option<(usize, W)> {
+        self.0
+            .shortest_prefix_of(input)
+            .map(|(length, weight)| (length, *weight))
+    }
+
+    /// Returns an immutable reference to the root [`TrieNode`].
+    ///
+    /// This is the node that contains every key.
+    pub fn root(&self) -> &TrieNode<T, H, W> {
+        self.0.root()
+    }
+}
+
+impl<T, W, H> WeightedTrie<T, W, H>
+where
+    T: Copy,
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Inserts a key with a weight into the trie.
+    ///
+    /// If the key was not already present, this function returns [`None`].
+    /// Otherwise the weight is replaced and the previous weight is returned.
+    pub fn insert(&mut self, iter: impl IntoIterator<Item = T>, weight: W) -> Option<W> {
+        self.entry(iter).insert(weight)
+    }
+
+    /// Removes a key from the trie.
+    ///
+    /// Returns the weight that was stored against the key, or [`None`] if the
+    /// key does not exist.
+    pub fn remove(&mut self, iter: impl IntoIterator<Item = T>) -> Option<W> {
+        self.entry(iter).remove()
+    }
+
+    /// Returns the [`WeightedEntry`] for a key, so its weight can be read or
+    /// changed without walking the trie again.
+    pub fn entry(&mut self, iter: impl IntoIterator<Item = T>) -> WeightedEntry<'_, T, W, H> {
+        WeightedEntry::new(&mut self.0, iter)
+    }
+}
+
+impl<T, W, H> WeightedTrie<T, W, H>
+where
+    T: Eq + Copy,
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every key and weight in the [`WeightedTrie`],
+    /// in the order the child storage keeps them.
+    ///
+    /// See [`TrieMap::iter_unordered`].
+    pub fn iter_unordered(&self) -> TrieMapIter<'_, T, W, H> {
+        self.0.iter_unordered()
+    }
+
+    /// Returns an iterator over every key and weight whose key starts with
+    /// `prefix`, in the order the child storage keeps them.
+    pub fn iter_prefix_unordered(&self, prefix: impl IntoIterator<Item = T>) -> TrieMapIter<'_, T, W, H> {
+        self.0.iter_prefix_unordered(prefix)
+    }
+
+    /// Returns an iterator over every key and weight whose key is within
+    /// `max_distance` edits of `query`, along with the distance.
+    ///
+    /// See [`FuzzySearch`].
+    pub fn fuzzy_search(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> FuzzySearch<'_, T, W, H> {
+        self.0.fuzzy_search(query, max_distance)
+    }
+
+    /// Returns an iterator over every key and weight whose key is within
+    /// `max_distance` edits of `query`, where swapping two adjacent elements
+    /// counts as a single edit.
+    ///
+    /// See [`FuzzySearch::with_transpositions`].
+    pub fn fuzzy_search_transpositions(&self, query: impl IntoIterator<Item = T>, max_distance: usize) -> FuzzySearch<'_, T, W, H> {
+        self.0.fuzzy_search_transpositions(query, max_distance)
+    }
+
+    /// Returns an iterator over every key and weight whose key matches
+    /// `pattern`.
+    ///
+    /// See [`PatternMatches`].
+    pub fn matches<P>(&self, pattern: impl IntoIterator<Item = Token<P>>) -> PatternMatches<'_, T, W, H, P>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.0.matches(pattern)
+    }
+}
+
+impl<T, W, H> WeightedTrie<T, W, H>
+where
+    T: Ord + Copy,
+    W: Ord + Copy,
+    H: ChildStorage<T>,
+{
+    /// Returns an iterator over every key and weight in the [`WeightedTrie`],
+    /// in lexicographic order of the keys.
+    ///
+    /// Use [`WeightedTrie::top_k_completions`] for the heaviest keys.
+    pub fn iter(&self) -> TrieMapIter<'_, T, W, H> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over every key and weight whose key starts with
+    /// `prefix`, in lexicographic order of the keys.
+    ///
+    /// The iterator yields full keys, including the prefix.
+    pub fn iter_prefix(&self, prefix: impl IntoIterator<Item = T>) -> TrieMapIter<'_, T, W, H> {
+        self.0.iter_prefix(prefix)
+    }
+
+    /// Returns the `k` heaviest keys that start with `prefix`, along with
+    /// their weights, from heaviest to lightest.
+    ///
+    /// Keys with the same weight are returned in lexicographic order. The
+    /// search is best-first, guided by the greatest weight cached in each
+    /// node, so only the nodes that lead to the returned keys and their
+    /// immediate siblings are visited.
+    pub fn top_k_completions(&self, prefix: impl IntoIterator<Item = T>, k: usize) -> Vec<(Vec<T>, W)> {
+        let prefix: Vec<T> = prefix.into_iter().collect();
+        let mut found = Vec::new();
+        let Some(node) = self.0.root.walk(&prefix) else {
+            return found;
+        };
+
+        let mut heap = BinaryHeap::new();
+        if let Some(max) = node.max {
+            heap.push(Candidate {
+                weight: max,
+                complete: false,
+                key: prefix,
+                node,
+            });
+        }
+        while found.len() < k {
+            let Some(candidate) = heap.pop() else {
+                break;
+            };
+            if candidate.complete {
+                found.push((candidate.key, candidate.weight));
+                continue;
+            }
+
+            // Expand the subtree into the key at its root and its children:
+            let node = candidate.node;
+            for (element, child) in node.children.iter() {
+                if let Some(max) = child.max {
+                    let mut key = candidate.key.clone();
+                    key.push(*element);
+                    heap.push(Candidate {
+                        weight: max,
+                        complete: false,
+                        key,
+                        node: child,
+                    });
+                }
+            }
+            if let Some(weight) = node.value {
+                heap.push(Candidate {
+                    weight,
+                    complete: true,
+                    key: candidate.key,
+                    node,
+                });
+            }
+        }
+        found
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, W, H> Serialize for WeightedTrie<T, W, H>
+where
+    T: Serialize,
+    W: Serialize,
+    H: ChildStorage<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize_named("WeightedTrie", serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, W, H> Deserialize<'de> for WeightedTrie<T, W, H>
+where
+    T: Deserialize<'de> + Copy,
+    W: Deserialize<'de> + Ord + Copy,
+    H: ChildStorage<T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut map = TrieMap::<T, W, H>::deserialize_named("WeightedTrie", deserializer)?;
+
+        // The cached maxima aren't serialized, so they are rebuilt from the
+        // weights:
+        refresh_all(&mut map.root);
+        Ok(WeightedTrie(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::children::SortedVec;
+
+    type CharWeightedTrie = WeightedTrie<char, u32>;
+
+    fn completions<H: ChildStorage<char>>(trie: &WeightedTrie<char, u32, H>, prefix: &str, k: usize) -> Vec<(String, u32)> {
+        trie.top_k_completions(prefix.chars(), k)
+            .into_iter()
+            .map(|(key, weight)| (String::from_iter(key), weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut trie = CharWeightedTrie::new();
+        assert_eq!(trie.insert("test".chars(), 3), None);
+        assert_eq!(trie.insert("tester".chars(), 5), None);
+        assert_eq!(trie.insert("test".chars(), 4), Some(3));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.get("test".chars()), Some(4));
+        assert!(!trie.contains("tes".chars()));
+        assert_eq!(trie.max_weight("".chars()), Some(5));
+        assert_eq!(trie.max_weight("testi".chars()), None);
+
+        assert_eq!(trie.remove("tester".chars()), Some(5));
+        assert_eq!(trie.remove("tester".chars()), None);
+        assert_eq!(trie.max_weight("".chars()), Some(4));
+        assert_eq!(trie.remove("test".chars()), Some(4));
+        assert!(trie.is_empty());
+        assert_eq!(trie.max_weight("".chars()), None);
+        assert!(trie.root().get(&'t').is_none());
+        assert_eq!(trie.0.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_top_k_completions() {
+        let mut trie = CharWeightedTrie::new();
+        for (key, weight) in [("car", 50), ("cart", 20), ("carbon", 70), ("care", 20), ("cat", 90), ("dog", 100)] {
+            assert_eq!(trie.insert(key.chars(), weight), None);
+        }
+
+        assert_eq!(completions(&trie, "ca", 3), [
+            ("cat".to_string(), 90),
+            ("carbon".to_string(), 70),
+            ("car".to_string(), 50),
+        ]);
+        // Ties are broken lexicographically:
+        assert_eq!(completions(&trie, "car", 10), [
+            ("carbon".to_string(), 70),
+            ("car".to_string(), 50),
+            ("care".to_string(), 20),
+            ("cart".to_string(), 20),
+        ]);
+        assert_eq!(completions(&trie, "", 1), [("dog".to_string(), 100)]);
+        assert!(completions(&trie, "x", 3).is_empty());
+        assert!(completions(&trie, "c", 0).is_empty());
+        assert_eq!(completions(&trie, "", usize::MAX).len(), 6);
+    }
+
+    #[test]
+    fn test_cached_maxima() {
+        let mut trie = WeightedTrie::<char, u32, SortedVec>::new();
+        assert_eq!(trie.insert("abc".chars(), 10), None);
+        assert_eq!(trie.insert("abd".chars(), 5), None);
+        assert_eq!(trie.insert("ab".chars(), 1), None);
+
+        // Lowering the heaviest key lowers the maximum above it:
+        assert_eq!(trie.insert("abc".chars(), 2), Some(10));
+        assert_eq!(trie.max_weight("".chars()), Some(5));
+        assert_eq!(trie.max_weight("abc".chars()), Some(2));
+        assert_eq!(completions(&trie, "ab", 1), [("abd".to_string(), 5)]);
+
+        assert_eq!(trie.remove("abd".chars()), Some(5));
+        assert_eq!(trie.max_weight("".chars()), Some(2));
+        assert_eq!(completions(&trie, "a", 2), [
+            ("abc".to_string(), 2),
+            ("ab".to_string(), 1),
+        ]);
+        assert_eq!(trie.0.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut trie = CharWeightedTrie::new();
+        assert_eq!(trie.entry("ab".chars()).or_insert(3), 3);
+        assert_eq!(trie.entry("ab".chars()).or_insert(7), 3);
+        assert_eq!(trie.entry("abc".chars()).get(), None);
+
+        let entry = trie.entry("ab".chars()).and_modify(|weight| weight * 3);
+        assert_eq!(entry.key(), ['a', 'b']);
+        assert_eq!(entry.get(), Some(9));
+        assert_eq!(trie.max_weight("a".chars()), Some(9));
+
+        // Modifying a missing key does nothing:
+        assert_eq!(trie.entry("x".chars()).and_modify(|weight| weight + 1).get(), None);
+        assert_eq!(trie.len(), 1);
+
+        let mut entry = trie.entry("abcd".chars());
+        assert_eq!(entry.insert(4), None);
+        assert_eq!(entry.insert(12), Some(4));
+        assert_eq!(trie.max_weight("".chars()), Some(12));
+        assert_eq!(trie.entry("abcd".chars()).remove(), Some(12));
+        assert_eq!(trie.entry("abcd".chars()).remove(), None);
+        assert_eq!(trie.max_weight("".chars()), Some(9));
+        assert_eq!(trie.len(), 1);
+        assert_eq!(trie.0.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut trie = CharWeightedTrie::new();
+        for (key, weight) in [("a", 1), ("ab", 2), ("abc", 3), ("b", 4)] {
+            trie.insert(key.chars(), weight);
+        }
+
+        assert!(trie.starts_with("ab".chars()));
+        assert!(!trie.starts_with("c".chars()));
+        assert_eq!(trie.count_with_prefix("a".chars()), 3);
+        assert_eq!(trie.prefixes_of("abd".chars()).count(), 2);
+        assert_eq!(trie.longest_prefix_of("abcd".chars()), Some((3, 3)));
+        assert_eq!(trie.shortest_prefix_of("abcd".chars()), Some((1, 1)));
+        assert_eq!(trie.iter().count(), 4);
+        assert_eq!(trie.iter_prefix("ab".chars()).count(), 2);
+        assert_eq!(
+            trie.iter()
+                .map(|(key, weight)| (String::from_iter(key), *weight))
+                .collect::<Vec<_>>(),
+            [
+                ("a".to_string(), 1),
+                ("ab".to_string(), 2),
+                ("abc".to_string(), 3),
+                ("b".to_string(), 4),
+            ],
+        );
+        assert_eq!(trie.iter_prefix("ab".chars()).next(), Some((vec!['a', 'b'], &2)));
+
+        let mut fuzzy: Vec<_> = trie.fuzzy_search("ac".chars(), 1).map(|(key, ..)| String::from_iter(key)).collect();
+        fuzzy.sort();
+        assert_eq!(fuzzy, ["a", "ab", "abc"]);
+        assert_eq!(trie.fuzzy_search_transpositions("ba".chars(), 1).count(), 3);
+        assert_eq!(trie.matches([Token::Many, Token::One(|c: &char| *c == 'c')]).count(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use serde_json;
+
+        let mut trie = CharWeightedTrie::new();
+        for (key, weight) in [("ab", 2), ("abc", 8), ("b", 4)] {
+            trie.insert(key.chars(), weight);
+        }
+
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: WeightedTrie<char, u32, SortedVec> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.len(), 3);
+        assert_eq!(deserialized.get("abc".chars()), Some(8));
+        assert_eq!(deserialized.max_weight("".chars()), Some(8));
+        assert_eq!(deserialized.max_weight("b".chars()), Some(4));
+        assert_eq!(completions(&deserialized, "", 2), [
+            ("abc".to_string(), 8),
+            ("b".to_string(), 4),
+        ]);
+    }
+}